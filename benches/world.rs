@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BenchmarkId,
+    Criterion
+};
+use rust_multiplayer::game::{
+    math::Vector2F,
+    world::{
+        get_tiled_vec,
+        World,
+        WorldSettings,
+        ENTITY_SIZE,
+        TILE_SIZE
+    }
+};
+
+const NPC_COUNTS: [i32; 3] = [1_000, 4_000, 10_000];
+
+/// NPCs on every second tile of square, so they have room to roam
+fn crowded_world(npc_count: i32) -> World {
+    let mut world = World::with_seed(WorldSettings::default(), 44);
+    let side = (npc_count as f32).sqrt().ceil() as i32;
+    for i in 0..npc_count {
+        world.create_entity_npc(format!("Npc{i}"), get_tiled_vec((i % side) * 2, (i / side) * 2), ENTITY_SIZE);
+    }
+    world
+}
+
+fn bench_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_tick");
+    for npc_count in NPC_COUNTS {
+        let mut world = crowded_world(npc_count);
+        group.bench_with_input(BenchmarkId::from_parameter(npc_count), &npc_count, |b, _| {
+            b.iter(|| world.tick(Duration::from_millis(33)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_free_tiles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_free_tiles");
+    for npc_count in NPC_COUNTS {
+        let world = crowded_world(npc_count);
+        group.bench_with_input(BenchmarkId::from_parameter(npc_count), &npc_count, |b, _| {
+            b.iter(|| world.get_free_tiles_positions(get_tiled_vec(20, 20), TILE_SIZE * 10.0));
+        });
+    }
+    group.finish();
+}
+
+fn bench_entities_in_range(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_entities_in_range");
+    for npc_count in NPC_COUNTS {
+        let world = crowded_world(npc_count);
+        group.bench_with_input(BenchmarkId::from_parameter(npc_count), &npc_count, |b, _| {
+            b.iter(|| world.get_entities_in_range(get_tiled_vec(20, 20), TILE_SIZE * 3.0).len());
+        });
+    }
+    group.finish();
+}
+
+fn bench_tile_occupied(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_tile_occupied");
+    for npc_count in NPC_COUNTS {
+        let world = crowded_world(npc_count);
+        group.bench_with_input(BenchmarkId::from_parameter(npc_count), &npc_count, |b, _| {
+            b.iter(|| world.is_tile_occupied(&Vector2F::new(-TILE_SIZE, -TILE_SIZE)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tick, bench_free_tiles, bench_entities_in_range, bench_tile_occupied);
+criterion_main!(benches);
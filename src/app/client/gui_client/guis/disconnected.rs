@@ -12,18 +12,25 @@ use winit::{
 };
 
 use crate::{
-    app::client::gui_client::{
-        guis::{
-            components::templates::{
-                build_gui_plain_button, 
-                GuiComponentSize
+    app::client::{
+        gui_client::{
+            guis::{
+                components::templates::{
+                    build_gui_plain_button, 
+                    GuiComponentSize
+                }, 
+                AppGuiTransition
             }, 
-            AppGuiTransition
+            renderer::Renderer, 
+            AppData
         }, 
-        renderer::Renderer, 
-        AppData
+        MultiplayerClientHandle
     }, 
-    game::math::Vector2F
+    game::math::Vector2F, 
+    requests::{
+        ClientRequest, 
+        ClientResponse
+    }
 };
 
 use super::{
@@ -68,16 +75,13 @@ impl GuiLayout for DisconnectedGuiLayout {
             let mouse_pos = Vector2F::new(position.x as f32, position.y as f32);
             if self.proceed_button.is_inside(&mouse_pos) {
                 log::info!("Button clicked");
-                let response = {
+                let was_set = {
                     let app_data = self.app_data.borrow();
-                    let new_name = app_data.player_name.clone();
                     let cleint_handle = app_data.client_handler.as_ref().unwrap();
-                    cleint_handle.make_request(crate::requests::ClientRequest::SetName { new_name }).unwrap()
-                };
-
-                let was_set = match response {
-                    crate::requests::ClientResponse::SetName { result } => result.is_ok(),
-                    _ => false,
+                    match (&app_data.player_name, &app_data.player_password) {
+                        (Some(name), Some(password)) => Self::login_and_join_lobby(cleint_handle, name, password),
+                        (new_name, _) => Self::join_as_guest(cleint_handle, new_name.clone()),
+                    }
                 };
 
                 if was_set {
@@ -96,4 +100,25 @@ impl GuiLayout for DisconnectedGuiLayout {
         renderer.batch_append_gui_element(GuiElement::Box(outer_gui_box));
         renderer.batch_append_gui_element(GuiElement::Box(inner_gui_box));
     }
-}
\ No newline at end of file
+}
+impl DisconnectedGuiLayout {
+    fn join_as_guest(cleint_handle: &MultiplayerClientHandle, new_name: Option<String>) -> bool {
+        let response = cleint_handle.make_request(ClientRequest::SetName { new_name }).unwrap();
+        matches!(response, ClientResponse::SetName { result: Ok(()) })
+    }
+
+    fn login_and_join_lobby(cleint_handle: &MultiplayerClientHandle, name: &str, password: &str) -> bool {
+        let response = cleint_handle.make_request(ClientRequest::Login { 
+            name: name.to_string(), 
+            password: password.to_string() 
+        }).unwrap();
+
+        if let ClientResponse::Login { result: Err(e) } = &response {
+            log::warn!("Could not log in, reason={e}");
+            return false;
+        }
+
+        let response = cleint_handle.make_request(ClientRequest::JoinLobby).unwrap();
+        matches!(response, ClientResponse::JoinLobby { joined: true })
+    }
+}
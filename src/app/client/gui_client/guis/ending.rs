@@ -1,25 +1,94 @@
 use std::{cell::RefCell, rc::Rc, time::Duration};
-use crate::{app::client::gui_client::AppData, requests::{ClientRequest, ClientResponse, GameplayStateBrief}};
 
-use super::{AppGuiTransition, GuiLayout};
+use clap::builder::styling::RgbColor;
+
+use crate::{
+    app::{
+        client::gui_client::{
+            guis::components::templates::{
+                build_gui_progress_bar, 
+                GuiComponentSize
+            }, 
+            renderer::Renderer, 
+            AppData
+        }, 
+        server::leaderboard::{
+            LeaderboardSort, 
+            PlayerStats
+        }
+    }, 
+    game::math::Vector2F, 
+    requests::{ClientRequest, ClientResponse, GameplayStateBrief}
+};
+
+use super::{components::GuiProgressBar, AppGuiTransition, GuiElement, GuiLayout};
 
 const UPDATE_REQUESTS_INTERVAL: Duration = Duration::from_millis(250);
+const STANDINGS_SIZE: usize = 5;
+const STANDINGS_GAP: f32 = 12.0;
+const OWN_STANDING_COLOR: RgbColor = RgbColor(255, 196, 0);
 
 #[derive(Debug)]
 pub struct EndingGuiLayout {
     pub app_data: Rc<RefCell<AppData>>,
     update_time_accumulator: Duration,
+    /// Points of best players relative to leader, own bar highlighted
+    standings_bars: Vec<GuiProgressBar>,
 }
 
 impl GuiLayout for EndingGuiLayout {
     fn new(app_data: Rc<RefCell<AppData>>) -> Self {
         log::info!("Entered 'Ending' gui");
-        Self { 
+        let (width, height) = {
+            let app_data_borrowed = app_data.borrow();
+            (app_data_borrowed.last_width, app_data_borrowed.last_height)
+        };
+
+        // Profiles are updated in memory before ending starts, standings already include this round
+        let standings_bars = Self::fetch_standings(&app_data)
+            .into_iter()
+            .map(|(stats, is_own, percentage)| {
+                let mut bar = build_gui_progress_bar(Vector2F::zero(), GuiComponentSize::Medium);
+                bar.set_percantage(percentage);
+                if is_own {
+                    bar.color_middle = OWN_STANDING_COLOR;
+                }
+                log::info!("Standing '{}' points={}", stats.name, stats.total_points);
+                bar
+            })
+            .collect();
+
+        let mut result = Self { 
             app_data,
             update_time_accumulator: Duration::from_millis(0),
+            standings_bars,
+        };
+        result.resize_window(width, height);
+        result
+    }
+
+    fn resize_window(&mut self, width: f32, height: f32) {
+        let total_height = self.standings_bars.iter()
+            .map(|bar| bar.rect.size.y + STANDINGS_GAP)
+            .sum::<f32>();
+
+        let mut pos_y = (height - total_height) / 2.0;
+        for bar in self.standings_bars.iter_mut() {
+            bar.rect.pos.x = (width - bar.rect.size.x) / 2.0;
+            bar.rect.pos.y = pos_y;
+            pos_y += bar.rect.size.y + STANDINGS_GAP;
         }
     }
 
+    fn draw(&self, renderer: &mut Renderer) {
+        self.standings_bars.iter().for_each(|bar| {
+            let (gui_box_1, gui_box_2, gui_box_3) = bar.get_drawable_rects();
+            renderer.batch_append_gui_element(GuiElement::Box(gui_box_1));
+            renderer.batch_append_gui_element(GuiElement::Box(gui_box_2));
+            renderer.batch_append_gui_element(GuiElement::Box(gui_box_3));
+        });
+    }
+
     fn update(&mut self, dt: std::time::Duration) {
         self.update_time_accumulator += dt;
         if self.update_time_accumulator > UPDATE_REQUESTS_INTERVAL {
@@ -45,4 +114,39 @@ impl GuiLayout for EndingGuiLayout {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl EndingGuiLayout {
+    /// Best players with flag if it is this client and points percentage of leader
+    fn fetch_standings(app_data: &Rc<RefCell<AppData>>) -> Vec<(PlayerStats, bool, f32)> {
+        let app_data = app_data.borrow();
+        let cleint_handle = app_data.client_handler.as_ref().unwrap();
+
+        let own_name = match cleint_handle.make_request(ClientRequest::GetClientSessionData).unwrap() {
+            ClientResponse::GetClientSessionData { data } => data.get_name().map(ToString::to_string),
+            _ => None,
+        };
+
+        let response = cleint_handle.make_request(ClientRequest::GetLeaderboard { 
+            top_n: STANDINGS_SIZE, 
+            sort_by: LeaderboardSort::TotalPoints 
+        }).unwrap();
+
+        let entries = match response {
+            ClientResponse::GetLeaderboard { entries } => entries,
+            _ => {
+                log::warn!("Could not get leaderboard, response={response:?}");
+                return vec![];
+            }
+        };
+
+        let leader_points = entries.first().map(|stats| stats.total_points).unwrap_or_default().max(1);
+        entries.into_iter()
+            .map(|stats| {
+                let is_own = own_name.as_deref() == Some(stats.name.as_str());
+                let percentage = 100.0 * stats.total_points as f32 / leader_points as f32;
+                (stats, is_own, percentage)
+            })
+            .collect()
+    }
+}
@@ -6,7 +6,10 @@ use winit::{
         ElementState, 
         MouseButton
     }, 
-    keyboard::Key
+    keyboard::{
+        Key, 
+        NamedKey
+    }
 };
 
 use crate::{
@@ -18,14 +21,21 @@ use crate::{
             }, 
             AppData, 
             EntityView
-        }, 
-        SEEKING_MAX_TIME
+        }
     }, game::{
         math::{
             Rect2F, 
-            Vector2F
+            Vector2F, 
+            Vector2I
+        }, 
+        tile_layer::{
+            Obstacle, 
+            TileBounds
         }, 
-        world::PlayerRole
+        world::{
+            MovementMode, 
+            PlayerRole
+        }
     }, 
     requests::{
         ClientRequest, 
@@ -46,6 +56,7 @@ use super::{
 
 use std::{
     cell::RefCell, 
+    collections::HashSet, 
     ops::Mul, 
     rc::Rc, 
     time::Duration
@@ -57,12 +68,23 @@ use std::{
 pub struct IngameGuiLayout {
     pub app_data: Rc<RefCell<AppData>>,
     entity_view_list: Vec<EntityView>,
+    obstacle_view_list: Vec<EntityView>,
+    play_area_view: Option<EntityView>,
+    /// Map fetched with `GetMap`, again only when its id changes
+    map_id: Option<u64>,
+    obstacles: Vec<Obstacle>,
+    bounds: Option<TileBounds>,
+    /// Seeker's floor tiles in sight, `None` when whole map is seen
+    visible_tile_views: Option<Vec<EntityView>>,
     update_time_accumulator: Duration,
 
     is_seeker: bool,
     remaining_time_progress_bar: Option<GuiProgressBar>,
     remaining_tries_count: usize,
     last_world_mouse_position: Option<Vector2F>,
+    /// Arrows pressed since last move, in order of pressing
+    held_arrows: Vec<NamedKey>,
+    movement_mode: MovementMode,
 }
 const SCROLL_SENSITIVITY: f32 = 0.1;
 const UPDATE_NOT_FREQUENT_INTERVAL: Duration = Duration::from_millis(250);
@@ -93,11 +115,19 @@ impl GuiLayout for IngameGuiLayout {
         let mut result = Self {
             app_data,
             entity_view_list: Vec::new(),
+            obstacle_view_list: Vec::new(),
+            play_area_view: None,
+            map_id: None,
+            obstacles: Vec::new(),
+            bounds: None,
+            visible_tile_views: None,
             update_time_accumulator: Duration::from_millis(0),
             is_seeker,
             remaining_time_progress_bar: None,
             remaining_tries_count: 0,
             last_world_mouse_position: None,
+            held_arrows: Vec::new(),
+            movement_mode: MovementMode::default(),
         };
         result.resize_window(width, height);
         result
@@ -128,6 +158,13 @@ impl GuiLayout for IngameGuiLayout {
 
     fn process_mouse_events(&mut self, _position: PhysicalPosition<f64>, button_state: ElementState, button: MouseButton) {
         if let Some(world_mouse_position) = self.last_world_mouse_position{
+            if button_state == ElementState::Released && button == MouseButton::Right {
+                // Server plans path, player walks there on its own
+                let app_data = self.app_data.borrow();
+                let cleint_handle = app_data.client_handler.as_ref().unwrap();
+                let _ = cleint_handle.make_request(ClientRequest::MoveTo { target: world_mouse_position });
+            }
+
             if button_state == ElementState::Released && button == MouseButton::Left && self.is_seeker {
                 println!("Seeker clicked: {}", world_mouse_position);
 
@@ -137,7 +174,7 @@ impl GuiLayout for IngameGuiLayout {
                     cleint_handle.make_request(ClientRequest::WorldCheck).unwrap()
                 };
 
-                let suspicious_entity_id = if let ClientResponse::WorldCheck { entities } = response {
+                let suspicious_entity_id = if let ClientResponse::WorldCheck { entities, map_id: _, visible_tiles: _, movement_mode: _ } = response {
                     entities.iter().find(|e| {
                         let rect = Rect2F {
                             pos: e.position,
@@ -145,7 +182,7 @@ impl GuiLayout for IngameGuiLayout {
                         };
                         rect.contains(&world_mouse_position)
                     })
-                    .and_then(|e| Some(e.id))
+                    .map(|e| e.id)
                 } else {
                     None
                 };
@@ -237,7 +274,7 @@ impl GuiLayout for IngameGuiLayout {
                         }
 
                         if let Some(progres_bar) = &mut self.remaining_time_progress_bar {
-                            let remaining_time_percentage = 100.0 * stats.remaining_ticks as f32 / SEEKING_MAX_TIME as f32;
+                            let remaining_time_percentage = 100.0 * stats.remaining_ticks as f32 / stats.max_ticks.max(1) as f32;
                             // println!("remaining_progress={}, {}", remaining_time_percentage, stats.remaining_ticks);
                             progres_bar.set_percantage(remaining_time_percentage);
                         }
@@ -256,7 +293,12 @@ impl GuiLayout for IngameGuiLayout {
             cleint_handle.make_request(ClientRequest::WorldCheck).unwrap()
         };
 
-        if let ClientResponse::WorldCheck { entities } = response {
+        if let ClientResponse::WorldCheck { entities, map_id, visible_tiles, movement_mode } = response {
+            self.movement_mode = movement_mode;
+            if self.map_id != Some(map_id) {
+                self.fetch_map();
+            }
+
             // Extract observed entity position 
             let extract_observed_entity_position = || {
                 let response = {
@@ -265,13 +307,11 @@ impl GuiLayout for IngameGuiLayout {
                     cleint_handle.make_request(ClientRequest::GetEntityId).unwrap()
                 };
 
-                if let ClientResponse::GetEntityId { id } = response {
-                    if let Some(entity_id) = id {
-                        let found_entity = entities.iter()
-                            .find(|e| e.id == entity_id);
-                        if let Some(found_entity) = found_entity {
-                            return Some(found_entity.position);
-                        }
+                if let ClientResponse::GetEntityId { id: Some(entity_id) } = response {
+                    let found_entity = entities.iter()
+                        .find(|e| e.id == entity_id);
+                    if let Some(found_entity) = found_entity {
+                        return Some(found_entity.position);
                     }
                 }
                 None
@@ -285,6 +325,24 @@ impl GuiLayout for IngameGuiLayout {
                 app_data.camera += delta_pos
             }
 
+            // Obstacles out of sight are darkened
+            let visible_tiles: Option<HashSet<Vector2I>> = visible_tiles.map(|tiles| tiles.into_iter().collect());
+            let is_in_sight = |tile: Vector2I| visible_tiles.as_ref().is_none_or(|visible_tiles| visible_tiles.contains(&tile));
+            self.obstacle_view_list = self.obstacles.iter()
+                .map(|obstacle| match is_in_sight(obstacle.tile) {
+                    true => EntityView::obstacle(obstacle),
+                    false => EntityView::obstacle(obstacle).darkened(),
+                })
+                .collect();
+            self.play_area_view = self.bounds.as_ref().map(|bounds| match visible_tiles {
+                Some(_) => EntityView::play_area(bounds).darkened(),
+                None => EntityView::play_area(bounds),
+            });
+            self.visible_tile_views = visible_tiles.as_ref().map(|visible_tiles| visible_tiles.iter()
+                .copied()
+                .map(EntityView::visible_tile)
+                .collect());
+
             // Update visible entities
             self.entity_view_list.clear();
 
@@ -294,34 +352,8 @@ impl GuiLayout for IngameGuiLayout {
                     size: entity.size,
                 };
 
-                let marker_color = match (self.is_seeker, &entity.entity_type) {
-                    (_, EntityType::Npc) => None,
-                    (true, EntityType::Hider { covered: true }) => {
-                        // I'm seeker, I dont recognise covered hiders
-                        None
-                    },
-                    (true, EntityType::Hider { covered: false }) => {
-                        // I'm seeker, I dont recognise covered hiders
-                        Some(RgbColor(30, 255, 0))
-                    },
-                    (false, EntityType::Hider { covered: _}) => {
-                        // I'm hider, other hiders are my allies, mark them green
-                        Some(RgbColor(0, 255, 0))
-                    },
-                    (true, EntityType::Seeker) => {
-                        // I'm seeker, Mark me blue
-                        Some(RgbColor(0, 0, 255))
-                    },
-                    (false, EntityType::Seeker) => {
-                        // I'm hider, seeker is my enemy, mark him red
-                        Some(RgbColor(255, 0, 0))
-                    },
-                };
-
                 // Highlighting
-                let highlighted = if self.is_seeker && matches!(entity.entity_type, EntityType::Seeker) {
-                    false
-                } else if !self.is_seeker {
+                let highlighted = if !self.is_seeker || matches!(entity.entity_type, EntityType::Seeker) {
                     false
                 } else if let Some(world_mouse_position) = self.last_world_mouse_position {
                     rect.contains(&world_mouse_position)
@@ -329,38 +361,61 @@ impl GuiLayout for IngameGuiLayout {
                     false
                 };
 
-                let entity_view = EntityView {
-                    rect,
-                    color: RgbColor(entity.color[0], entity.color[1], entity.color[2]),
-                    marker_color,
-                    highlighted
-                };
-                self.entity_view_list.push(entity_view);
+                self.entity_view_list.push(EntityView::new(entity, self.is_seeker, highlighted));
             });
 
         }
     }
 
+    /// Arrow moves on release, two arrows held together move diagonally.
+    /// In free movement mode player walks while arrows are held.
     fn process_key_event(&mut self, event: winit::event::KeyEvent) {
-        if event.state == ElementState::Released {
-            let app_data = self.app_data.borrow();
-            let client_handler = app_data.client_handler.as_ref().unwrap();
-            match event.logical_key {
-                Key::Named(winit::keyboard::NamedKey::ArrowUp) => {
-                    let _ = client_handler.make_request(ClientRequest::Move { dir: MoveDirection::Up} );
-                },
-                Key::Named(winit::keyboard::NamedKey::ArrowRight) => {
-                    let _ = client_handler.make_request(ClientRequest::Move { dir: MoveDirection::Right} );
-                },
-                Key::Named(winit::keyboard::NamedKey::ArrowDown) => {
-                    let _ = client_handler.make_request(ClientRequest::Move { dir: MoveDirection::Down} );
-                },
-                Key::Named(winit::keyboard::NamedKey::ArrowLeft) => {
-                    let _ = client_handler.make_request(ClientRequest::Move { dir: MoveDirection::Left} );
+        let Key::Named(arrow @ (NamedKey::ArrowUp | NamedKey::ArrowRight | NamedKey::ArrowDown | NamedKey::ArrowLeft)) = event.logical_key else {
+            return;
+        };
+
+        if self.movement_mode == MovementMode::Free {
+            match event.state {
+                ElementState::Pressed => {
+                    if self.held_arrows.contains(&arrow) {
+                        // Key repeat
+                        return;
+                    }
+                    self.held_arrows.push(arrow);
                 },
-                _ => {}
+                ElementState::Released => self.held_arrows.retain(|held| *held != arrow),
             }
-        } 
+
+            let direction = arrows_direction(&self.held_arrows)
+                .map(|dir| dir.offset())
+                .unwrap_or(Vector2F::zero());
+            let app_data = self.app_data.borrow();
+            let client_handler = app_data.client_handler.as_ref().unwrap();
+            let _ = client_handler.make_request(ClientRequest::SetVelocity { direction });
+            return;
+        }
+
+        match event.state {
+            ElementState::Pressed => {
+                if !self.held_arrows.contains(&arrow) {
+                    self.held_arrows.push(arrow);
+                }
+            },
+            ElementState::Released => {
+                // Other arrow of the pair released right after is already used
+                if !self.held_arrows.contains(&arrow) {
+                    return;
+                }
+                let direction = arrows_direction(&self.held_arrows);
+                self.held_arrows.clear();
+
+                if let Some(dir) = direction {
+                    let app_data = self.app_data.borrow();
+                    let client_handler = app_data.client_handler.as_ref().unwrap();
+                    let _ = client_handler.make_request(ClientRequest::Move { dir });
+                }
+            },
+        }
     }
 
     fn draw(&self, renderer: &mut crate::app::client::gui_client::renderer::Renderer) {
@@ -388,13 +443,59 @@ impl GuiLayout for IngameGuiLayout {
             renderer.batch_append_gui_element(GuiElement::Box(gui_box));
         }
         
+        // Drawn first, everything else stands on it
+        if let Some(play_area_view) = self.play_area_view {
+            renderer.batch_append_entity_view(play_area_view);
+        }
+
+        self.visible_tile_views.iter().flatten().for_each(|tile_view| {
+            renderer.batch_append_entity_view(*tile_view);
+        });
+
+        self.obstacle_view_list.iter().for_each(|obstacle_view| {
+            renderer.batch_append_entity_view(*obstacle_view);
+        });
+
         self.entity_view_list.iter().for_each(|entity_view| {
             renderer.batch_append_entity_view(*entity_view);
         });
     }
 }
 
+impl IngameGuiLayout {
+    /// Obstacles and play area of running round
+    fn fetch_map(&mut self) {
+        let response = {
+            let app_data = self.app_data.borrow();
+            let cleint_handle = app_data.client_handler.as_ref().unwrap();
+            cleint_handle.make_request(ClientRequest::GetMap).unwrap()
+        };
 
+        match response {
+            ClientResponse::GetMap { map_id, obstacles, bounds } => {
+                log::info!("Got map {map_id}, obstacles={}", obstacles.len());
+                self.map_id = Some(map_id);
+                self.obstacles = obstacles;
+                self.bounds = bounds;
+            },
+            _ => log::warn!("Could not get map, response={response:?}"),
+        }
+    }
+}
 
-
-
+/// Last pressed vertical and horizontal arrow make one direction
+fn arrows_direction(arrows: &[NamedKey]) -> Option<MoveDirection> {
+    let vertical = arrows.iter().rev().find(|arrow| matches!(arrow, NamedKey::ArrowUp | NamedKey::ArrowDown));
+    let horizontal = arrows.iter().rev().find(|arrow| matches!(arrow, NamedKey::ArrowLeft | NamedKey::ArrowRight));
+    match (vertical, horizontal) {
+        (Some(NamedKey::ArrowUp), Some(NamedKey::ArrowLeft)) => Some(MoveDirection::UpLeft),
+        (Some(NamedKey::ArrowUp), Some(NamedKey::ArrowRight)) => Some(MoveDirection::UpRight),
+        (Some(NamedKey::ArrowDown), Some(NamedKey::ArrowLeft)) => Some(MoveDirection::DownLeft),
+        (Some(NamedKey::ArrowDown), Some(NamedKey::ArrowRight)) => Some(MoveDirection::DownRight),
+        (Some(NamedKey::ArrowUp), None) => Some(MoveDirection::Up),
+        (Some(NamedKey::ArrowDown), None) => Some(MoveDirection::Down),
+        (None, Some(NamedKey::ArrowLeft)) => Some(MoveDirection::Left),
+        (None, Some(NamedKey::ArrowRight)) => Some(MoveDirection::Right),
+        _ => None,
+    }
+}
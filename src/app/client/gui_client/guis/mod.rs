@@ -2,6 +2,7 @@ pub mod disconnected;
 pub mod lobby;
 pub mod ingame;
 pub mod ending;
+pub mod replay;
 
 use std::{cell::RefCell, rc::Rc};
 
@@ -11,7 +12,7 @@ use winit::{dpi::PhysicalPosition, event::{ElementState, KeyEvent, MouseButton,
 use crate::game::math::{Rect2F, Vector2F};
 
 use super::{renderer::Renderer, AppData};
-use self::{disconnected::DisconnectedGuiLayout, ending::EndingGuiLayout, ingame::IngameGuiLayout, lobby::LobbyGuiLayout};
+use self::{disconnected::DisconnectedGuiLayout, ending::EndingGuiLayout, ingame::IngameGuiLayout, lobby::LobbyGuiLayout, replay::ReplayGuiLayout};
 
 #[derive(Debug, Clone, Copy)]
 pub struct GuiBox {
@@ -350,11 +351,19 @@ pub enum AppGui {
     Ending {
         gui: EndingGuiLayout,
     },
+    Replay {
+        gui: ReplayGuiLayout,
+    },
 }
 
 impl GuiLayout for AppGui {
     fn new(app_data: Rc<RefCell<AppData>>) -> Self {
-        Self::Disconnected { gui: DisconnectedGuiLayout::new(app_data) }
+        let has_replay = app_data.borrow().replay.is_some();
+        if has_replay {
+            Self::Replay { gui: ReplayGuiLayout::new(app_data) }
+        } else {
+            Self::Disconnected { gui: DisconnectedGuiLayout::new(app_data) }
+        }
     }
 
     fn resize_window(&mut self, width: f32, height: f32) {
@@ -363,6 +372,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.resize_window(width, height),
             AppGui::Ingame { gui }  => gui.resize_window(width, height),
             AppGui::Ending { gui }  => gui.resize_window(width, height),
+            AppGui::Replay { gui }  => gui.resize_window(width, height),
         }
     }
 
@@ -374,6 +384,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.process_mouse_events(position, button_state, button),
             AppGui::Ingame { gui }  => gui.process_mouse_events(position, button_state, button),
             AppGui::Ending { gui }  => gui.process_mouse_events(position, button_state, button),
+            AppGui::Replay { gui }  => gui.process_mouse_events(position, button_state, button),
         }
     }
 
@@ -383,6 +394,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.draw(renderer),
             AppGui::Ingame { gui }  => gui.draw(renderer),
             AppGui::Ending { gui }  => gui.draw(renderer),
+            AppGui::Replay { gui }  => gui.draw(renderer),
         }
     }
 
@@ -392,6 +404,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.update(dt),
             AppGui::Ingame { gui }  => gui.update(dt),
             AppGui::Ending { gui }  => gui.update(dt),
+            AppGui::Replay { gui }  => gui.update(dt),
         }
     }
 
@@ -401,6 +414,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.process_key_event(event),
             AppGui::Ingame { gui }  => gui.process_key_event(event),
             AppGui::Ending { gui }  => gui.process_key_event(event),
+            AppGui::Replay { gui }  => gui.process_key_event(event),
         }
     }
 
@@ -410,6 +424,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.process_mouse_wheele(delta),
             AppGui::Ingame { gui }  => gui.process_mouse_wheele(delta),
             AppGui::Ending { gui }  => gui.process_mouse_wheele(delta),
+            AppGui::Replay { gui }  => gui.process_mouse_wheele(delta),
         }
     }
     
@@ -419,6 +434,7 @@ impl GuiLayout for AppGui {
             AppGui::Lobby { gui }  => gui.mouse_move(mouse_position),
             AppGui::Ingame { gui }  => gui.mouse_move(mouse_position),
             AppGui::Ending { gui }  => gui.mouse_move(mouse_position),
+            AppGui::Replay { gui }  => gui.mouse_move(mouse_position),
         }
     }
 }
@@ -430,6 +446,7 @@ impl AppGui {
             AppGui::Lobby { gui } => gui.app_data.clone(),
             AppGui::Ingame { gui } => gui.app_data.clone(),
             AppGui::Ending { gui } => gui.app_data.clone(),
+            AppGui::Replay { gui } => gui.app_data.clone(),
         }
     }
 
@@ -0,0 +1,217 @@
+use std::{cell::RefCell, ops::Mul, rc::Rc, time::Duration};
+
+use winit::{
+    dpi::PhysicalPosition, 
+    event::{
+        ElementState, 
+        MouseButton
+    }, 
+    keyboard::{
+        Key, 
+        NamedKey
+    }
+};
+
+use crate::{
+    app::{
+        client::gui_client::{
+            guis::components::templates::{
+                build_gui_indicator, 
+                build_gui_progress_bar, 
+                GuiComponentSize
+            }, 
+            renderer::Renderer, 
+            AppData, 
+            EntityView
+        }, 
+        replay::Replay
+    }, 
+    game::math::Vector2F, 
+    requests::EntityType
+};
+
+use super::{
+    components::{
+        GuiIndicator, 
+        GuiProgressBar
+    }, 
+    GuiElement, 
+    GuiLayout
+};
+
+const SCROLL_SENSITIVITY: f32 = 0.1;
+const SEEK_STEP: Duration = Duration::from_secs(5);
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 8.0;
+const CONTROLS_MARGIN: f32 = 8.0;
+
+/// Plays back recorded round. Space pauses, arrows left/right seek,
+/// arrows up/down change speed, clicking timeline jumps to that moment.
+#[derive(Debug)]
+pub struct ReplayGuiLayout {
+    pub app_data: Rc<RefCell<AppData>>,
+    replay: Option<Replay>,
+    /// Fractional frame index, playback advances it by ticks
+    position: f32,
+    speed: f32,
+    paused: bool,
+    entity_view_list: Vec<EntityView>,
+    timeline: GuiProgressBar,
+    playing_indicator: GuiIndicator,
+}
+
+impl GuiLayout for ReplayGuiLayout {
+    fn new(app_data: Rc<RefCell<AppData>>) -> Self {
+        log::info!("Entered 'Replay' gui");
+        let (width, height, replay) = {
+            let mut app_data_borrowed = app_data.borrow_mut();
+            (app_data_borrowed.last_width, app_data_borrowed.last_height, app_data_borrowed.replay.take())
+        };
+
+        match &replay {
+            Some(replay) => log::info!(
+                "Replay of match {}, {} frames, {:?}", 
+                replay.header.match_id, 
+                replay.frames_count(), 
+                replay.duration()
+            ),
+            None => log::warn!("No replay to play"),
+        }
+
+        let mut result = Self {
+            app_data,
+            replay,
+            position: 0.0,
+            speed: 1.0,
+            paused: false,
+            entity_view_list: Vec::new(),
+            timeline: build_gui_progress_bar(Vector2F::zero(), GuiComponentSize::Small),
+            playing_indicator: build_gui_indicator(Vector2F::zero(), GuiComponentSize::Medium),
+        };
+        result.resize_window(width, height);
+        result
+    }
+
+    fn resize_window(&mut self, width: f32, _height: f32) {
+        self.playing_indicator.rect.pos = Vector2F::new(CONTROLS_MARGIN, CONTROLS_MARGIN);
+
+        let timeline_pos_x = self.playing_indicator.rect.size.x + 2.0 * CONTROLS_MARGIN;
+        self.timeline.rect.pos = Vector2F::new(timeline_pos_x, CONTROLS_MARGIN);
+        self.timeline.rect.size.x = width - timeline_pos_x - CONTROLS_MARGIN;
+    }
+
+    fn process_mouse_wheele(&mut self, delta: winit::event::MouseScrollDelta) {
+        if let winit::event::MouseScrollDelta::LineDelta(_, y) = delta {
+            let mut app_data = self.app_data.borrow_mut();
+            app_data.world_scale *= (1.0 + SCROLL_SENSITIVITY).powf(y);
+        }
+    }
+
+    fn process_mouse_events(&mut self, position: PhysicalPosition<f64>, button_state: ElementState, button: MouseButton) {
+        if button_state == ElementState::Released && button == MouseButton::Left {
+            let mouse_pos = Vector2F::new(position.x as f32, position.y as f32);
+            if self.timeline.rect.contains(&mouse_pos) {
+                let fraction = (mouse_pos.x - self.timeline.rect.pos.x) / self.timeline.rect.size.x;
+                self.position = fraction * self.last_frame_index() as f32;
+            } else if self.playing_indicator.rect.contains(&mouse_pos) {
+                self.paused = !self.paused;
+            }
+        }
+    }
+
+    fn process_key_event(&mut self, event: winit::event::KeyEvent) {
+        if event.state != ElementState::Released {
+            return;
+        }
+
+        let seek_frames = SEEK_STEP.as_secs_f32() * self.ticks_per_second();
+        match event.logical_key {
+            Key::Named(NamedKey::Space) => {
+                self.paused = !self.paused;
+            },
+            Key::Named(NamedKey::ArrowLeft) => {
+                self.position -= seek_frames;
+            },
+            Key::Named(NamedKey::ArrowRight) => {
+                self.position += seek_frames;
+            },
+            Key::Named(NamedKey::ArrowUp) => {
+                self.speed = (self.speed * 2.0).min(MAX_SPEED);
+                log::info!("Replay speed x{}", self.speed);
+            },
+            Key::Named(NamedKey::ArrowDown) => {
+                self.speed = (self.speed / 2.0).max(MIN_SPEED);
+                log::info!("Replay speed x{}", self.speed);
+            },
+            Key::Named(NamedKey::Home) => {
+                self.position = 0.0;
+            },
+            _ => {}
+        }
+        self.position = self.position.clamp(0.0, self.last_frame_index() as f32);
+    }
+
+    fn update(&mut self, dt: std::time::Duration) {
+        let last_frame_index = self.last_frame_index() as f32;
+        if !self.paused {
+            self.position += dt.as_secs_f32() * self.ticks_per_second() * self.speed;
+            if self.position >= last_frame_index {
+                // Stay on last frame
+                self.position = last_frame_index;
+                self.paused = true;
+            }
+        }
+
+        self.playing_indicator.set_turned_on(!self.paused);
+        self.timeline.set_percantage(100.0 * self.position / last_frame_index.max(1.0));
+
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let entities = replay.entities_at(self.position as usize);
+
+        // Follow seeker, like live play follows own entity
+        if let Some(seeker) = entities.iter().find(|entity| matches!(entity.entity_type, EntityType::Seeker)) {
+            const SMOOTHING_ALPHA: f32 = 0.09;
+            let mut app_data = self.app_data.borrow_mut();
+            let delta_pos = (seeker.position - app_data.camera).mul(SMOOTHING_ALPHA);
+            app_data.camera += delta_pos
+        }
+
+        // Spectator sees hiders as allies do
+        self.entity_view_list = entities.iter()
+            .map(|entity| EntityView::new(entity, false, false))
+            .collect();
+    }
+
+    fn draw(&self, renderer: &mut Renderer) {
+        let (gui_box_1, gui_box_2, gui_box_3) = self.timeline.get_drawable_rects();
+        renderer.batch_append_gui_element(GuiElement::Box(gui_box_1));
+        renderer.batch_append_gui_element(GuiElement::Box(gui_box_2));
+        renderer.batch_append_gui_element(GuiElement::Box(gui_box_3));
+        renderer.batch_append_gui_element(GuiElement::Box(self.playing_indicator.get_drawable_rects()));
+
+        if let Some(replay) = &self.replay {
+            if let Some(bounds) = &replay.header.bounds {
+                renderer.batch_append_entity_view(EntityView::play_area(bounds));
+            }
+            replay.header.obstacles.iter().for_each(|obstacle| {
+                renderer.batch_append_entity_view(EntityView::obstacle(obstacle));
+            });
+        }
+
+        self.entity_view_list.iter().for_each(|entity_view| {
+            renderer.batch_append_entity_view(*entity_view);
+        });
+    }
+}
+
+impl ReplayGuiLayout {
+    fn ticks_per_second(&self) -> f32 {
+        self.replay.as_ref().map(|replay| replay.header.ticks_per_second as f32).unwrap_or(1.0)
+    }
+
+    fn last_frame_index(&self) -> usize {
+        self.replay.as_ref().map(|replay| replay.frames_count().saturating_sub(1)).unwrap_or_default()
+    }
+}
@@ -4,10 +4,12 @@ pub mod guis;
 use clap::builder::styling::RgbColor;
 use guis::AppGuiTransition;
 
-use crate::game::math::{Rect2F, Vector2F};
+use crate::{app::replay::Replay, game::{math::{Rect2F, Vector2F, Vector2I}, tile_layer::{Obstacle, ObstacleKind, TileBounds}, world::{get_tiled_vec, TILE_SIZE}}, requests::{EntityCheckData, EntityType}};
 
 use super::MultiplayerClientHandle;
 
+const PLAY_AREA_COLOR: RgbColor = RgbColor(45, 50, 45);
+
 #[derive(Debug, Copy, Clone)]
 pub struct EntityView {
     pub rect: Rect2F,
@@ -16,15 +18,109 @@ pub struct EntityView {
     pub highlighted: bool,
 }
 
+impl EntityView {
+    /// Entity as seen by seeker or hider, hiders see other hiders as allies
+    pub fn new(entity: &EntityCheckData, is_seeker: bool, highlighted: bool) -> Self {
+        let marker_color = match (is_seeker, &entity.entity_type) {
+            (_, EntityType::Npc) => None,
+            (true, EntityType::Hider { covered: true }) => {
+                // I'm seeker, I dont recognise covered hiders
+                None
+            },
+            (true, EntityType::Hider { covered: false }) => {
+                // I'm seeker, I dont recognise covered hiders
+                Some(RgbColor(30, 255, 0))
+            },
+            (false, EntityType::Hider { covered: _}) => {
+                // I'm hider, other hiders are my allies, mark them green
+                Some(RgbColor(0, 255, 0))
+            },
+            (true, EntityType::Seeker) => {
+                // I'm seeker, Mark me blue
+                Some(RgbColor(0, 0, 255))
+            },
+            (false, EntityType::Seeker) => {
+                // I'm hider, seeker is my enemy, mark him red
+                Some(RgbColor(255, 0, 0))
+            },
+        };
+
+        Self {
+            rect: Rect2F {
+                pos: entity.position,
+                size: entity.size,
+            },
+            color: RgbColor(entity.color[0], entity.color[1], entity.color[2]),
+            marker_color,
+            highlighted
+        }
+    }
+
+    /// Obstacle fills whole tile, colored by its kind
+    pub fn obstacle(obstacle: &Obstacle) -> Self {
+        let color = match obstacle.kind {
+            ObstacleKind::Wall => RgbColor(90, 70, 60),
+            ObstacleKind::Rock => RgbColor(120, 125, 130),
+            ObstacleKind::Crate => RgbColor(170, 120, 50),
+        };
+
+        Self {
+            rect: Rect2F {
+                pos: obstacle.position(),
+                size: Vector2F::new(TILE_SIZE, TILE_SIZE),
+            },
+            color,
+            marker_color: None,
+            highlighted: false
+        }
+    }
+
+    /// Floor of whole play area, lighter than background outside of it
+    pub fn play_area(bounds: &TileBounds) -> Self {
+        Self {
+            rect: bounds.rect(),
+            color: PLAY_AREA_COLOR,
+            marker_color: None,
+            highlighted: false
+        }
+    }
+
+    /// Floor tile in sight of seeker, drawn over darkened play area
+    pub fn visible_tile(tile: Vector2I) -> Self {
+        Self {
+            rect: Rect2F {
+                pos: get_tiled_vec(tile.x, tile.y),
+                size: Vector2F::new(TILE_SIZE, TILE_SIZE),
+            },
+            color: PLAY_AREA_COLOR,
+            marker_color: None,
+            highlighted: false
+        }
+    }
+
+    /// Same view out of sight
+    pub fn darkened(self) -> Self {
+        let RgbColor(r, g, b) = self.color;
+        Self {
+            color: RgbColor(r / 3, g / 3, b / 3),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AppData {
     pub client_handler: Option<MultiplayerClientHandle>,
     pub player_name: Option<String>,
+    /// Log in to account instead of playing as guest
+    pub player_password: Option<String>,
     pub app_gui_expected_transition: Option<AppGuiTransition>,
     pub last_width: f32,
     pub last_height: f32,
     pub world_scale: f32,
     pub camera: Vector2F,
+    /// Recorded round to play back instead of connecting to server
+    pub replay: Option<Replay>,
 }
 
 
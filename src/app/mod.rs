@@ -1,5 +1,3 @@
 pub mod server;
 pub mod client;
-
-pub const SEEKING_MAX_TIME: u32 = 5000;
-pub const SEEKING_MAX_TRIES: usize = 3;
\ No newline at end of file
+pub mod replay;
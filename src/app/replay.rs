@@ -0,0 +1,275 @@
+use std::{
+    collections::{
+        HashMap,
+        HashSet
+    },
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        BufWriter,
+        Write
+    },
+    path::Path,
+    time::{
+        Duration,
+        SystemTime
+    }
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::{
+    game::{
+        math::Vector2F,
+        tile_layer::{
+            Obstacle,
+            TileBounds
+        },
+        world::{
+            EntityId,
+            World
+        }
+    },
+    requests::{
+        EntityCheckData,
+        EntityType
+    }
+};
+
+use super::server::{
+    game_mode::GameModeKind,
+    match_history::MatchId
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("SerializationError, reason='{0}'")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("MissingHeader")]
+    MissingHeader,
+}
+
+/// First line of replay file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub match_id: MatchId,
+    pub game_mode: GameModeKind,
+    /// World seed of recorded round
+    pub seed: u64,
+    pub ticks_per_second: u32,
+    pub started_at: SystemTime,
+    /// Obstacles never move, recorded once for whole round
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    /// Play area of recorded round, `None` for unbounded world
+    #[serde(default)]
+    pub bounds: Option<TileBounds>,
+}
+
+/// Entity properties which never change, written once with first frame entity appears in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntity {
+    pub id: EntityId,
+    pub name: String,
+    pub size: Vector2F,
+    pub color: [u8; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntityState {
+    pub id: EntityId,
+    pub position: Vector2F,
+    pub entity_type: EntityType,
+}
+
+/// World state after single tick of running round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub tick: u32,
+    pub new_entities: Vec<ReplayEntity>,
+    pub entities: Vec<ReplayEntityState>,
+}
+
+/// Writes replay as JSON lines, header followed by frame per tick
+#[derive(Debug)]
+pub struct ReplayWriter {
+    writer: BufWriter<File>,
+    known_entities: HashSet<EntityId>,
+    next_tick: u32,
+}
+
+impl ReplayWriter {
+    pub fn create<P: AsRef<Path>>(path: P, header: &ReplayHeader) -> Result<Self, ReplayError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, header)?;
+        writer.write_all(b"\n")?;
+
+        Ok(Self {
+            writer,
+            known_entities: HashSet::new(),
+            next_tick: 0,
+        })
+    }
+
+    pub fn record_frame(&mut self, world: &World) -> Result<(), ReplayError> {
+        let entities = EntityCheckData::vec_from_iter(world.iter_entities());
+
+        let frame = ReplayFrame {
+            tick: self.next_tick,
+            new_entities: entities.iter()
+                .filter(|entity| self.known_entities.insert(entity.id))
+                .map(|entity| ReplayEntity { 
+                    id: entity.id, 
+                    name: entity.name.clone(), 
+                    size: entity.size, 
+                    color: entity.color 
+                })
+                .collect(),
+            entities: entities.into_iter()
+                .map(|entity| ReplayEntityState { 
+                    id: entity.id, 
+                    position: entity.position, 
+                    entity_type: entity.entity_type 
+                })
+                .collect(),
+        };
+
+        serde_json::to_writer(&mut self.writer, &frame)?;
+        self.writer.write_all(b"\n")?;
+        self.next_tick += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), ReplayError> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Recorded round loaded for playback
+#[derive(Debug)]
+pub struct Replay {
+    pub header: ReplayHeader,
+    frames: Vec<ReplayFrame>,
+    entities: HashMap<EntityId, ReplayEntity>,
+}
+
+impl Replay {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReplayError> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+
+        let header = match lines.next() {
+            Some(line) => serde_json::from_str::<ReplayHeader>(&line?)?,
+            None => return Err(ReplayError::MissingHeader),
+        };
+
+        let mut frames = vec![];
+        let mut entities = HashMap::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut frame = serde_json::from_str::<ReplayFrame>(&line)?;
+            for entity in frame.new_entities.drain(..) {
+                entities.insert(entity.id, entity);
+            }
+            frames.push(frame);
+        }
+
+        Ok(Self { header, frames, entities })
+    }
+
+    pub fn frames_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames.len() as f64 / self.header.ticks_per_second as f64)
+    }
+
+    /// Entities as they were after given frame, same data as live `WorldCheck`
+    pub fn entities_at(&self, frame_index: usize) -> Vec<EntityCheckData> {
+        let Some(frame) = self.frames.get(frame_index) else {
+            return vec![];
+        };
+
+        frame.entities.iter()
+            .filter_map(|state| self.entities.get(&state.id).map(|entity| EntityCheckData {
+                position: state.position,
+                size: entity.size,
+                color: entity.color,
+                id: entity.id,
+                name: entity.name.clone(),
+                is_npc: matches!(state.entity_type, EntityType::Npc),
+                entity_type: state.entity_type.clone(),
+            }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{
+        tile_layer::ObstacleKind,
+        world::{
+            get_tiled_vec,
+            ENTITY_SIZE
+        }
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_replay_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("replay_0.jsonl");
+
+        let mut world = World::new();
+        let seeker_id = world.create_entity_player("Seeker", Vector2F::new(0.0, 0.0), ENTITY_SIZE);
+        let npc_id = world.create_entity_npc("Npc", Vector2F::new(2.0, 0.0), ENTITY_SIZE);
+        world.select_entity_as_seeker(seeker_id, 100, 3).unwrap();
+        world.place_obstacle(get_tiled_vec(3, 0), ObstacleKind::Rock).unwrap();
+
+        let header = ReplayHeader { 
+            match_id: 0, 
+            game_mode: GameModeKind::HideAndSeek, 
+            seed: 0, 
+            ticks_per_second: 10, 
+            started_at: SystemTime::UNIX_EPOCH,
+            obstacles: world.obstacles().to_vec(),
+            bounds: world.bounds()
+        };
+        let mut writer = ReplayWriter::create(&path, &header).unwrap();
+        writer.record_frame(&world).unwrap();
+        world.get_entity_by_id_mut(seeker_id).unwrap().position = Vector2F::new(1.0, 0.0);
+        world.remove_entity(npc_id).unwrap();
+        writer.record_frame(&world).unwrap();
+        writer.finish().unwrap();
+
+        let replay = Replay::load(&path).unwrap();
+        assert_eq!(replay.header, header);
+        assert_eq!(replay.header.obstacles.len(), 1);
+        assert_eq!(replay.frames_count(), 2);
+        assert_eq!(replay.duration(), Duration::from_millis(200));
+
+        let first_frame = replay.entities_at(0);
+        assert_eq!(first_frame.len(), 2);
+
+        let second_frame = replay.entities_at(1);
+        assert_eq!(second_frame.len(), 1);
+        assert_eq!(second_frame[0].name, "Seeker");
+        assert_eq!(second_frame[0].position, Vector2F::new(1.0, 0.0));
+        assert!(matches!(second_frame[0].entity_type, EntityType::Seeker));
+
+        assert!(replay.entities_at(2).is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+use argon2::{
+    password_hash::{
+        PasswordHash, 
+        PasswordHasher, 
+        PasswordVerifier, 
+        SaltString
+    }, 
+    Argon2
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasswordHashError {
+    #[error("HashingFailed, reason='{0}'")]
+    HashingFailed(argon2::password_hash::Error),
+}
+
+/// Argon2 hash in PHC string format, salt included
+pub fn hash_password(password: &str) -> Result<String, PasswordHashError> {
+    let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>())
+        .map_err(PasswordHashError::HashingFailed)?;
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(PasswordHashError::HashingFailed)
+}
+
+/// Malformed hash never matches
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed_hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok(),
+        Err(e) => {
+            log::error!("Stored password hash is malformed, reason={e}");
+            false
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_verification() {
+        let password_hash = hash_password("hunter22").unwrap();
+        assert!(!password_hash.contains("hunter22"));
+        assert!(verify_password("hunter22", &password_hash));
+        assert!(!verify_password("hunter23", &password_hash));
+        assert!(!verify_password("hunter22", "not a hash"));
+    }
+
+    #[test]
+    fn test_same_password_gets_different_salt() {
+        assert_ne!(hash_password("hunter22").unwrap(), hash_password("hunter22").unwrap());
+    }
+}
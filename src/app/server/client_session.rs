@@ -14,7 +14,10 @@ use tokio::io::{
 
 use crate::game::world::EntityId;
 
-use super::MultiplayerServerContext;
+use super::{
+    game_mode::RoundScore, 
+    MultiplayerServerContext
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ClientSessionError {
@@ -25,6 +28,11 @@ pub enum ClientSessionError {
 pub enum ClientSessionState {
     #[default]
     JustConnected,
+    /// Logged in to account, not in lobby yet
+    Authenticated {
+        name: String,
+    },
+    /// In lobby, either as guest or after joining from authenticated state
     NameWasSet {
         name: String,
         ready_to_start: bool,
@@ -36,6 +44,7 @@ pub enum ClientSessionState {
 pub struct ClientSessionData {
     pub state: ClientSessionState,
     pub points: u32,
+    pub last_round_score: Option<RoundScore>,
 }
 
 #[derive(Debug)]
@@ -73,7 +82,7 @@ impl ClientSession {
         log::debug!("Client {client_session_id} connected with address: {address}");
     }
 
-    fn on_client_request(
+    async fn on_client_request(
         server_context: Arc<MultiplayerServerContext>,
         client_session_id: ClientSessionId, 
         session_data: Arc<Mutex<ClientSessionData>>,
@@ -85,7 +94,7 @@ impl ClientSession {
             client_session_id, 
             session_data,
             request
-        )
+        ).await
     }
 
     fn on_client_disconnect(client_session_id: ClientSessionId) {
@@ -120,7 +129,7 @@ impl ClientSession {
                         self.id, 
                         session_data.clone(),
                         line, 
-                    );
+                    ).await;
                     log::debug!("Response with: '{}'", response);
 
                     response.push('\n');
@@ -180,6 +189,7 @@ impl ClientSessionData {
     pub fn get_entity_player_id(&self) -> Option<EntityId> {
         match &self.state {
             ClientSessionState::JustConnected => None,
+            ClientSessionState::Authenticated { name: _ } => None,
             ClientSessionState::NameWasSet { name: _, ready_to_start: _, entity_player_id } => *entity_player_id,
         }
     }
@@ -187,7 +197,12 @@ impl ClientSessionData {
     pub fn get_name(&self) -> Option<&str> {
         match &self.state {
             ClientSessionState::JustConnected => None,
+            ClientSessionState::Authenticated { name } => Some(name.as_str()),
             ClientSessionState::NameWasSet { name, ready_to_start: _, entity_player_id: _ } => Some(name.as_str()),
         }
     }
+
+    pub fn is_in_lobby(&self) -> bool {
+        matches!(self.state, ClientSessionState::NameWasSet { name: _, ready_to_start: _, entity_player_id: _ })
+    }
 }
\ No newline at end of file
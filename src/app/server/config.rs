@@ -0,0 +1,556 @@
+use std::{
+    collections::BTreeMap,
+    path::{
+        Path,
+        PathBuf
+    },
+    time::Duration
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use rand::seq::IndexedRandom;
+
+use crate::game::{
+    map_generator::{
+        MapStyle,
+        MAX_MAP_SIZE
+    },
+    npc_behavior::NpcPersonality,
+    world::{
+        MovementMode,
+        WorldSettings
+    }
+};
+
+use super::{
+    game_mode::GameModeKind,
+    scheduler::{
+        TickRate,
+        DEFAULT_TICKS_PER_SECOND
+    }
+};
+
+/// Longest countdown or round, `Duration::from_secs_f32` panics far above it
+const MAX_DURATION_SECS: f32 = 24.0 * 60.0 * 60.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServerConfigError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("ParseError, reason='{0}'")]
+    ParseError(#[from] toml::de::Error),
+
+    #[error("InvalidValue, field='{field}', reason='{reason}'")]
+    InvalidValue {
+        field: &'static str,
+        reason: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LobbyConfig {
+    pub game_mode: GameModeKind,
+    pub clients_required_to_start: usize,
+    pub countdown_secs: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeekingConfig {
+    pub max_time_secs: f32,
+    pub max_tries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndingConfig {
+    pub countdown_secs: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldConfig {
+    pub npcs_per_hider: usize,
+    pub mapsize_generation_factor: usize,
+    pub map_style: MapStyle,
+    /// Part of map floor covered with rocks and crates
+    pub obstacles_density: f32,
+    /// Units per second
+    pub player_movement_speed: f32,
+    /// Units per second
+    pub npc_movement_speed: f32,
+    /// Tiles seeker sees around itself, walls and obstacles block the view. 0 shows whole map.
+    pub seeker_view_radius: u32,
+    /// Tile by tile steps or free walking in any direction
+    pub movement_mode: MovementMode,
+    /// Seed of every round, random if not set. Logged at round start.
+    pub seed: Option<u64>,
+    pub npc_personalities: NpcPersonalitiesConfig,
+}
+
+/// Relative weights of NPC behaviors, zero turns behavior off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NpcPersonalitiesConfig {
+    pub roamer: u32,
+    pub wanderer: u32,
+    pub follower: u32,
+    pub idler: u32,
+    pub path_walker: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RewardsConfig {
+    pub seeker_win: u32,
+    pub hiders_win: u32,
+    pub seeker_per_hider_found: u32,
+    pub seeker_wrong_guess_penalty: u32,
+    /// Hider gets 1 point for every such period staying covered
+    pub hider_survival_secs_per_point: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Require `Register` or `Login`, guests with `SetName` are rejected
+    pub enabled: bool,
+    pub min_password_length: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Registered accounts allowed to send `Admin` commands
+    pub accounts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// JSON file with player profiles, kept in memory only if not set.
+    /// Read once on server start.
+    pub profiles_path: Option<PathBuf>,
+    /// JSON lines file where every finished match is appended, kept in memory only if not set.
+    /// Read once on server start.
+    pub match_history_path: Option<PathBuf>,
+    /// Directory with gameplay event journals, one JSON lines file per match.
+    /// Read once on server start.
+    pub journal_dir: Option<PathBuf>,
+    /// Directory where every round is recorded tick by tick for `replay` command, disabled if not set
+    pub replay_dir: Option<PathBuf>,
+    /// Directory of worlds saved and restored by admin commands, disabled if not set
+    pub saved_worlds_dir: Option<PathBuf>,
+}
+
+/// Gameplay rules of the server, loaded from TOML file.
+/// Missing fields take default values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub ticks_per_second: u32,
+    pub lobby: LobbyConfig,
+    pub seeking: SeekingConfig,
+    pub ending: EndingConfig,
+    pub world: WorldConfig,
+    pub rewards: RewardsConfig,
+    pub auth: AuthConfig,
+    pub admin: AdminConfig,
+    pub storage: StorageConfig,
+}
+
+/// Values given on command line, they win over config file on start and after every reload
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    pub ticks_per_second: Option<u32>,
+    pub clients_required_to_start: Option<usize>,
+    pub seeking_max_time_secs: Option<f32>,
+    pub seeking_max_tries: Option<usize>,
+}
+
+impl Default for LobbyConfig {
+    fn default() -> Self {
+        Self {
+            game_mode: GameModeKind::default(),
+            clients_required_to_start: 2,
+            countdown_secs: 1.0,
+        }
+    }
+}
+
+impl Default for SeekingConfig {
+    fn default() -> Self {
+        Self {
+            max_time_secs: 160.0,
+            max_tries: 3,
+        }
+    }
+}
+
+impl Default for EndingConfig {
+    fn default() -> Self {
+        Self {
+            countdown_secs: 0.32,
+        }
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        let world_settings = WorldSettings::default();
+        Self {
+            npcs_per_hider: 9,
+            mapsize_generation_factor: 5,
+            map_style: MapStyle::default(),
+            obstacles_density: 0.08,
+            player_movement_speed: world_settings.player_movement_speed,
+            npc_movement_speed: world_settings.npc_movement_speed,
+            seeker_view_radius: world_settings.seeker_view_radius.unwrap_or(0),
+            movement_mode: world_settings.movement_mode,
+            seed: None,
+            npc_personalities: NpcPersonalitiesConfig::default(),
+        }
+    }
+}
+
+impl Default for NpcPersonalitiesConfig {
+    fn default() -> Self {
+        Self {
+            roamer: 1,
+            wanderer: 4,
+            follower: 2,
+            idler: 2,
+            path_walker: 3,
+        }
+    }
+}
+
+impl Default for RewardsConfig {
+    fn default() -> Self {
+        Self {
+            seeker_win: 5,
+            hiders_win: 5,
+            seeker_per_hider_found: 2,
+            seeker_wrong_guess_penalty: 1,
+            hider_survival_secs_per_point: 20,
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_password_length: 8,
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            ticks_per_second: DEFAULT_TICKS_PER_SECOND,
+            lobby: LobbyConfig::default(),
+            seeking: SeekingConfig::default(),
+            ending: EndingConfig::default(),
+            world: WorldConfig::default(),
+            rewards: RewardsConfig::default(),
+            auth: AuthConfig::default(),
+            admin: AdminConfig::default(),
+            storage: StorageConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Read and validate config file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ServerConfigError> {
+        Self::load_with_overrides(path, &ConfigOverrides::default())
+    }
+
+    /// Read config file, apply overrides and validate the result
+    pub fn load_with_overrides<P: AsRef<Path>>(path: P, overrides: &ConfigOverrides) -> Result<Self, ServerConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        let mut config = Self::parse(&content)?;
+        overrides.apply_to(&mut config);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ServerConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn validate(&self) -> Result<(), ServerConfigError> {
+        fn check(valid: bool, field: &'static str, reason: &'static str) -> Result<(), ServerConfigError> {
+            if valid {
+                Ok(())
+            } else {
+                Err(ServerConfigError::InvalidValue { field, reason })
+            }
+        }
+
+        check((1..=1000).contains(&self.ticks_per_second), "ticks_per_second", "must be in range 1..=1000")?;
+        check(self.lobby.clients_required_to_start >= 1, "lobby.clients_required_to_start", "must be at least 1")?;
+        check((0.0..=MAX_DURATION_SECS).contains(&self.lobby.countdown_secs), "lobby.countdown_secs", "must be between 0 and 86400")?;
+        check(self.seeking.max_time_secs > 0.0 && self.seeking.max_time_secs <= MAX_DURATION_SECS, "seeking.max_time_secs", "must be positive and at most 86400")?;
+        check(self.seeking.max_tries >= 1, "seeking.max_tries", "must be at least 1")?;
+        check((0.0..=MAX_DURATION_SECS).contains(&self.ending.countdown_secs), "ending.countdown_secs", "must be between 0 and 86400")?;
+        check(self.world.mapsize_generation_factor >= 1, "world.mapsize_generation_factor", "must be at least 1")?;
+        check((0.0..=0.5).contains(&self.world.obstacles_density), "world.obstacles_density", "must be between 0.0 and 0.5")?;
+        check(self.world.player_movement_speed.is_finite() && self.world.player_movement_speed > 0.0, "world.player_movement_speed", "must be positive")?;
+        check(self.world.npc_movement_speed.is_finite() && self.world.npc_movement_speed > 0.0, "world.npc_movement_speed", "must be positive")?;
+        check(self.world.seeker_view_radius <= MAX_MAP_SIZE, "world.seeker_view_radius", "must be at most 128, 0 sees whole map")?;
+        check(self.world.npc_personalities.total_weight() > 0, "world.npc_personalities", "at least one weight must be positive")?;
+        check(self.world.seed.is_none_or(|seed| i64::try_from(seed).is_ok()), "world.seed", "must fit in TOML integer")?;
+        check(self.rewards.hider_survival_secs_per_point >= 1, "rewards.hider_survival_secs_per_point", "must be at least 1")?;
+        Ok(())
+    }
+
+    /// Values which differ in `other`, formatted as `key: old -> new`
+    pub fn changes(&self, other: &ServerConfig) -> Vec<String> {
+        let old_values = self.flattened_values();
+        let new_values = other.flattened_values();
+
+        new_values.iter()
+            .filter_map(|(key, new_value)| match old_values.get(key) {
+                Some(old_value) if old_value == new_value => None,
+                Some(old_value) => Some(format!("{key}: {old_value} -> {new_value}")),
+                None => Some(format!("{key}: {new_value}")),
+            })
+            .collect()
+    }
+
+    /// Storage fields are read once on server start, reloaded config keeps values of `running` one.
+    /// Returns fields which had different value.
+    pub fn keep_start_only_fields(&mut self, running: &ServerConfig) -> Vec<&'static str> {
+        let fields = [
+            ("storage.profiles_path", &mut self.storage.profiles_path, &running.storage.profiles_path),
+            ("storage.match_history_path", &mut self.storage.match_history_path, &running.storage.match_history_path),
+            ("storage.journal_dir", &mut self.storage.journal_dir, &running.storage.journal_dir),
+        ];
+
+        let mut kept = vec![];
+        for (field, value, running_value) in fields {
+            if value != running_value {
+                value.clone_from(running_value);
+                kept.push(field);
+            }
+        }
+        kept
+    }
+
+    fn flattened_values(&self) -> BTreeMap<String, String> {
+        fn flatten(prefix: &str, value: toml::Value, values: &mut BTreeMap<String, String>) {
+            match value {
+                toml::Value::Table(table) => {
+                    for (key, value) in table {
+                        let key = if prefix.is_empty() { key } else { format!("{prefix}.{key}") };
+                        flatten(&key, value, values);
+                    }
+                },
+                // All float fields are f32, print them without f64 widening noise
+                toml::Value::Float(value) => {
+                    values.insert(prefix.to_string(), (value as f32).to_string());
+                },
+                value => {
+                    values.insert(prefix.to_string(), value.to_string());
+                },
+            }
+        }
+
+        let mut values = BTreeMap::new();
+        flatten("", toml::Value::try_from(self).expect("Config should serialize"), &mut values);
+        values
+    }
+
+    pub fn tick_rate(&self) -> TickRate {
+        TickRate::new(self.ticks_per_second)
+    }
+
+    pub fn world_settings(&self) -> WorldSettings {
+        WorldSettings {
+            player_movement_speed: self.world.player_movement_speed,
+            npc_movement_speed: self.world.npc_movement_speed,
+            seeker_view_radius: Some(self.world.seeker_view_radius).filter(|radius| *radius > 0),
+            movement_mode: self.world.movement_mode,
+        }
+    }
+}
+
+impl ConfigOverrides {
+    pub fn apply_to(&self, config: &mut ServerConfig) {
+        if let Some(ticks_per_second) = self.ticks_per_second {
+            config.ticks_per_second = ticks_per_second;
+        }
+        if let Some(clients_required_to_start) = self.clients_required_to_start {
+            config.lobby.clients_required_to_start = clients_required_to_start;
+        }
+        if let Some(seeking_max_time_secs) = self.seeking_max_time_secs {
+            config.seeking.max_time_secs = seeking_max_time_secs;
+        }
+        if let Some(seeking_max_tries) = self.seeking_max_tries {
+            config.seeking.max_tries = seeking_max_tries;
+        }
+    }
+}
+
+impl LobbyConfig {
+    pub fn countdown(&self) -> Duration {
+        Duration::from_secs_f32(self.countdown_secs)
+    }
+}
+
+impl SeekingConfig {
+    pub fn max_time(&self) -> Duration {
+        Duration::from_secs_f32(self.max_time_secs)
+    }
+}
+
+impl NpcPersonalitiesConfig {
+    fn total_weight(&self) -> u64 {
+        [self.roamer, self.wanderer, self.follower, self.idler, self.path_walker].iter()
+            .map(|weight| *weight as u64)
+            .sum()
+    }
+
+    /// Personality of next NPC, roamer when every weight is zero
+    pub fn choose<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> NpcPersonality {
+        let weighted = [
+            (self.roamer, NpcPersonality::default as fn() -> NpcPersonality),
+            (self.wanderer, NpcPersonality::wanderer),
+            (self.follower, NpcPersonality::follower),
+            (self.idler, NpcPersonality::idler),
+            (self.path_walker, NpcPersonality::path_walker),
+        ];
+        weighted.choose_weighted(rng, |(weight, _)| *weight)
+            .map(|(_, personality)| personality())
+            .unwrap_or_default()
+    }
+}
+
+impl EndingConfig {
+    pub fn countdown(&self) -> Duration {
+        Duration::from_secs_f32(self.countdown_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_config_matches_defaults() {
+        let config = ServerConfig::parse(include_str!("../../../res/server_config.toml")).unwrap();
+        assert_eq!(config, ServerConfig::default());
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config = ServerConfig::parse("
+            ticks_per_second = 60
+
+            [seeking]
+            max_tries = 7
+        ").unwrap();
+
+        assert_eq!(config.ticks_per_second, 60);
+        assert_eq!(config.seeking.max_tries, 7);
+        assert_eq!(config.seeking.max_time_secs, SeekingConfig::default().max_time_secs);
+        assert_eq!(config.world, WorldConfig::default());
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_fields() {
+        let result = ServerConfig::parse("tick_per_second = 60");
+        assert!(matches!(result, Err(ServerConfigError::ParseError(_))), "{result:?}");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = ServerConfig::default();
+        config.seeking.max_time_secs = 0.0;
+        assert!(matches!(
+            config.validate(),
+            Err(ServerConfigError::InvalidValue { field: "seeking.max_time_secs", reason: _ })
+        ));
+
+        let config = ServerConfig { ticks_per_second: 0, ..Default::default() };
+        assert!(config.validate().is_err());
+
+        // Would panic on conversion to `Duration` at round start
+        let mut config = ServerConfig::default();
+        config.seeking.max_time_secs = 1e20;
+        assert!(matches!(
+            config.validate(),
+            Err(ServerConfigError::InvalidValue { field: "seeking.max_time_secs", reason: _ })
+        ));
+        let mut config = ServerConfig::default();
+        config.lobby.countdown_secs = f32::NAN;
+        assert!(config.validate().is_err());
+        let mut config = ServerConfig::default();
+        config.ending.countdown_secs = 1e20;
+        assert!(config.validate().is_err());
+
+        let mut config = ServerConfig::default();
+        config.world.seeker_view_radius = 50_000;
+        assert!(matches!(
+            config.validate(),
+            Err(ServerConfigError::InvalidValue { field: "world.seeker_view_radius", reason: _ })
+        ));
+
+        let mut config = ServerConfig::default();
+        config.world.npc_personalities = NpcPersonalitiesConfig { roamer: 0, wanderer: 0, follower: 0, idler: 0, path_walker: 0 };
+        assert!(matches!(
+            config.validate(),
+            Err(ServerConfigError::InvalidValue { field: "world.npc_personalities", reason: _ })
+        ));
+    }
+
+    #[test]
+    fn test_npc_personality_chosen_by_weight() {
+        let personalities = NpcPersonalitiesConfig { roamer: 0, wanderer: 0, follower: 0, idler: 1, path_walker: 0 };
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            assert_eq!(personalities.choose(&mut rng), NpcPersonality::idler());
+        }
+    }
+
+    #[test]
+    fn test_config_changes() {
+        let old_config = ServerConfig::default();
+        assert!(old_config.changes(&old_config).is_empty());
+
+        let mut new_config = old_config.clone();
+        new_config.seeking.max_tries = 5;
+        new_config.ending.countdown_secs = 2.5;
+        assert_eq!(old_config.changes(&new_config), vec![
+            "ending.countdown_secs: 0.32 -> 2.5".to_string(),
+            "seeking.max_tries: 3 -> 5".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_reloaded_config_keeps_start_only_fields() {
+        let mut running_config = ServerConfig::default();
+        running_config.storage.profiles_path = Some(PathBuf::from("profiles.json"));
+
+        let mut reloaded_config = running_config.clone();
+        reloaded_config.storage.profiles_path = Some(PathBuf::from("other_profiles.json"));
+        reloaded_config.storage.replay_dir = Some(PathBuf::from("replays"));
+        reloaded_config.seeking.max_tries = 5;
+
+        assert_eq!(reloaded_config.keep_start_only_fields(&running_config), vec!["storage.profiles_path"]);
+        assert_eq!(running_config.changes(&reloaded_config), vec![
+            "seeking.max_tries: 3 -> 5".to_string(),
+            "storage.replay_dir: \"replays\"".to_string(),
+        ]);
+    }
+}
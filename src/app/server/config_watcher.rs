@@ -0,0 +1,123 @@
+use std::{
+    path::{
+        Path,
+        PathBuf
+    },
+    sync::Arc,
+    time::{
+        Duration,
+        SystemTime
+    }
+};
+
+use super::{
+    config::{
+        ConfigOverrides,
+        ServerConfig,
+        ServerConfigError
+    },
+    MultiplayerServerContext
+};
+
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Polls modification time of config file and reloads it after every change.
+/// Command line overrides are applied to every reloaded config.
+#[derive(Debug)]
+pub struct ConfigFileWatcher {
+    path: PathBuf,
+    overrides: ConfigOverrides,
+    last_modified: Option<SystemTime>,
+}
+
+impl ConfigFileWatcher {
+    pub fn new<P: Into<PathBuf>>(path: P, overrides: ConfigOverrides) -> Self {
+        let path = path.into();
+        let last_modified = Self::modified_time(&path);
+        Self { 
+            path, 
+            overrides,
+            last_modified 
+        }
+    }
+
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Loads config if file was modified since last poll
+    pub fn poll(&mut self) -> Option<Result<ServerConfig, ServerConfigError>> {
+        let modified = Self::modified_time(&self.path);
+        if modified.is_none() || modified == self.last_modified {
+            return None;
+        }
+
+        self.last_modified = modified;
+        Some(ServerConfig::load_with_overrides(&self.path, &self.overrides))
+    }
+
+    /// Stages every valid reloaded config, it gets applied by main task between rounds
+    pub async fn run(mut self, server_context: Arc<MultiplayerServerContext>) {
+        let mut interval = tokio::time::interval(CONFIG_WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            match self.poll() {
+                Some(Ok(config)) => {
+                    log::info!("Config file {:?} reloaded, changes will be applied after current round", self.path);
+                    server_context.stage_config(config);
+                },
+                Some(Err(e)) => {
+                    log::error!("Rejected config file {:?}, keeping current values, reason={e}", self.path);
+                },
+                None => {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(path: &Path, content: &str, modified: SystemTime) {
+        std::fs::write(path, content).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_watcher_reloads_only_modified_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let start_time = SystemTime::now();
+        rewrite(file.path(), "ticks_per_second = 20", start_time);
+
+        let mut watcher = ConfigFileWatcher::new(file.path(), ConfigOverrides::default());
+        assert!(watcher.poll().is_none());
+
+        rewrite(file.path(), "ticks_per_second = 0", start_time + Duration::from_secs(1));
+        assert!(matches!(watcher.poll(), Some(Err(ServerConfigError::InvalidValue { field: "ticks_per_second", reason: _ }))));
+        assert!(watcher.poll().is_none());
+
+        rewrite(file.path(), "ticks_per_second = 40", start_time + Duration::from_secs(2));
+        let config = watcher.poll().unwrap().unwrap();
+        assert_eq!(config.ticks_per_second, 40);
+    }
+
+    #[test]
+    fn test_watcher_keeps_overrides_after_reload() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let start_time = SystemTime::now();
+        rewrite(file.path(), "ticks_per_second = 20", start_time);
+
+        let overrides = ConfigOverrides { ticks_per_second: Some(60), seeking_max_tries: Some(7), ..Default::default() };
+        let mut watcher = ConfigFileWatcher::new(file.path(), overrides);
+
+        rewrite(file.path(), "ticks_per_second = 40\n[seeking]\nmax_tries = 2\nmax_time_secs = 30.0", start_time + Duration::from_secs(1));
+        let config = watcher.poll().unwrap().unwrap();
+        assert_eq!(config.ticks_per_second, 60);
+        assert_eq!(config.seeking.max_tries, 7);
+        assert_eq!(config.seeking.max_time_secs, 30.0);
+    }
+}
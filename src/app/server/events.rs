@@ -0,0 +1,309 @@
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write
+    },
+    path::{
+        Path,
+        PathBuf
+    },
+    sync::{
+        mpsc,
+        Arc,
+        Mutex
+    }
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::game::{
+    math::Vector2F,
+    world::{
+        EntityId,
+        PlayerRole
+    }
+};
+
+use super::{
+    client_session::ClientSessionId,
+    game_mode::GameModeKind,
+    match_history::MatchId,
+    GameplayResult
+};
+
+pub const GAME_EVENTS_CAPACITY: usize = 1024;
+
+/// File with events happening outside of matches
+pub const LOBBY_JOURNAL_FILE_NAME: &str = "lobby.jsonl";
+
+pub type GameEventReceiver = tokio::sync::broadcast::Receiver<Arc<JournalEntry>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundPlayer {
+    pub client_id: ClientSessionId,
+    pub name: String,
+    pub entity_id: EntityId,
+    pub role: PlayerRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    PlayerJoined {
+        client_id: ClientSessionId,
+        name: String,
+    },
+    PlayerLeft {
+        client_id: ClientSessionId,
+        name: Option<String>,
+    },
+    ReadyToggled {
+        client_id: ClientSessionId,
+        ready: bool,
+    },
+    CountdownStarted {
+        ticks: u32,
+    },
+    RoundStarted {
+        match_id: MatchId,
+        game_mode: GameModeKind,
+        seed: u64,
+        players: Vec<RoundPlayer>,
+    },
+    Moved {
+        entity_id: EntityId,
+        from: Vector2F,
+        to: Vector2F,
+    },
+    UncoverAttempted {
+        seeker_entity_id: EntityId,
+        target_entity_id: EntityId,
+        was_hider: Option<bool>,
+    },
+    SeekerPunished {
+        entity_id: EntityId,
+        remaining_failures: usize,
+    },
+    RoundEnded {
+        match_id: MatchId,
+        result: GameplayResult,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Server tick at which event happened
+    pub tick: u64,
+    /// None for events outside of matches
+    pub match_id: Option<MatchId>,
+    pub event: GameEvent,
+}
+
+#[derive(Debug)]
+struct JournalFile {
+    match_id: Option<MatchId>,
+    writer: BufWriter<File>,
+}
+
+#[derive(Debug)]
+enum JournalCommand {
+    Append(Arc<JournalEntry>),
+    /// Reply once everything sent before is written
+    Flush(mpsc::Sender<()>),
+}
+
+/// Owns journal files, runs on its own thread so emitting never waits for disk
+#[derive(Debug)]
+struct JournalWriter {
+    dir: PathBuf,
+    file: Option<JournalFile>,
+}
+
+/// Stream of gameplay events. Every event is published to subscribers and,
+/// if directory is set, appended to JSON lines file of its match by writer thread.
+#[derive(Debug)]
+pub struct GameEventJournal {
+    dir: Option<PathBuf>,
+    /// Match of emitted events, held while sending so writer gets events in order
+    match_id: Mutex<Option<MatchId>>,
+    writer: Option<mpsc::Sender<JournalCommand>>,
+    subscribers: tokio::sync::broadcast::Sender<Arc<JournalEntry>>,
+}
+
+fn journal_path(dir: &Path, match_id: Option<MatchId>) -> PathBuf {
+    match match_id {
+        Some(match_id) => dir.join(format!("match_{match_id}.jsonl")),
+        None => dir.join(LOBBY_JOURNAL_FILE_NAME),
+    }
+}
+
+impl JournalWriter {
+    fn run(mut self, commands: mpsc::Receiver<JournalCommand>) {
+        while let Ok(command) = commands.recv() {
+            self.handle(command);
+            // Flush once queue is drained, not after every event
+            while let Ok(command) = commands.try_recv() {
+                self.handle(command);
+            }
+            if let Err(e) = self.flush() {
+                log::error!("Could not flush game events journal, reason={e}");
+            }
+        }
+    }
+
+    fn handle(&mut self, command: JournalCommand) {
+        match command {
+            JournalCommand::Append(entry) => {
+                if let Err(e) = self.append(&entry) {
+                    log::error!("Could not write game event to journal, reason={e}");
+                }
+            },
+            JournalCommand::Flush(reply) => {
+                if let Err(e) = self.flush() {
+                    log::error!("Could not flush game events journal, reason={e}");
+                }
+                let _ = reply.send(());
+            },
+        }
+    }
+
+    fn append(&mut self, entry: &JournalEntry) -> Result<(), std::io::Error> {
+        let file = match self.file.take() {
+            Some(file) if file.match_id == entry.match_id => file,
+            Some(mut file) => {
+                file.writer.flush()?;
+                self.open(entry.match_id)?
+            },
+            None => self.open(entry.match_id)?,
+        };
+        let file = self.file.insert(file);
+
+        serde_json::to_writer(&mut file.writer, entry)?;
+        file.writer.write_all(b"\n")
+    }
+
+    fn open(&self, match_id: Option<MatchId>) -> Result<JournalFile, std::io::Error> {
+        let path = journal_path(&self.dir, match_id);
+        Ok(JournalFile {
+            match_id,
+            writer: BufWriter::new(File::options().create(true).append(true).open(path)?),
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        match &mut self.file {
+            Some(file) => file.writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl GameEventJournal {
+    pub fn new(dir: Option<PathBuf>) -> Result<Self, std::io::Error> {
+        if let Some(dir) = &dir {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let writer = match &dir {
+            Some(dir) => {
+                let (commands_tx, commands_rx) = mpsc::channel();
+                let journal_writer = JournalWriter { dir: dir.clone(), file: None };
+                std::thread::Builder::new()
+                    .name("journal-writer".to_string())
+                    .spawn(move || journal_writer.run(commands_rx))?;
+                Some(commands_tx)
+            },
+            None => None,
+        };
+
+        let (subscribers, _) = tokio::sync::broadcast::channel(GAME_EVENTS_CAPACITY);
+        Ok(Self {
+            dir,
+            match_id: Mutex::new(None),
+            writer,
+            subscribers,
+        })
+    }
+
+    /// Journal file of given match, `None` for lobby journal
+    pub fn journal_path(&self, match_id: Option<MatchId>) -> Option<PathBuf> {
+        self.dir.as_deref().map(|dir| journal_path(dir, match_id))
+    }
+
+    /// Receive events emitted from now on, slow receivers lose oldest events
+    pub fn subscribe(&self) -> GameEventReceiver {
+        self.subscribers.subscribe()
+    }
+
+    pub fn emit(&self, tick: u64, event: GameEvent) {
+        let mut match_id_guard = self.match_id.lock().unwrap();
+        if let GameEvent::RoundStarted { match_id, game_mode: _, seed: _, players: _ } = &event {
+            *match_id_guard = Some(*match_id);
+        }
+
+        let entry = Arc::new(JournalEntry { tick, match_id: *match_id_guard, event });
+        if let Some(writer) = &self.writer {
+            if writer.send(JournalCommand::Append(entry.clone())).is_err() {
+                log::error!("Could not write game event to journal, writer thread is gone");
+            }
+        }
+
+        if let GameEvent::RoundEnded { match_id: _, result: _ } = &entry.event {
+            *match_id_guard = None;
+        }
+
+        // No subscribers is fine
+        let _ = self.subscribers.send(entry);
+    }
+
+    /// Wait until every event emitted so far is written to journal file
+    pub fn flush(&self) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        let (reply_tx, reply_rx) = mpsc::channel();
+        if writer.send(JournalCommand::Flush(reply_tx)).is_ok() {
+            let _ = reply_rx.recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_journal(journal: &GameEventJournal, match_id: Option<MatchId>) -> Vec<JournalEntry> {
+        std::fs::read_to_string(journal.journal_path(match_id).unwrap()).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_journal_splits_events_by_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = GameEventJournal::new(Some(dir.path().join("journal"))).unwrap();
+        let mut receiver = journal.subscribe();
+
+        journal.emit(1, GameEvent::PlayerJoined { client_id: 0, name: "Pig_1".to_string() });
+        journal.emit(2, GameEvent::RoundStarted { match_id: 4, game_mode: GameModeKind::HideAndSeek, seed: 0, players: vec![] });
+        journal.emit(3, GameEvent::Moved { entity_id: 0, from: Vector2F::new(0.0, 0.0), to: Vector2F::new(1.0, 0.0) });
+        journal.emit(4, GameEvent::RoundEnded { match_id: 4, result: GameplayResult::HidersWin { reward: 5 } });
+        journal.emit(5, GameEvent::PlayerLeft { client_id: 0, name: Some("Pig_1".to_string()) });
+        journal.flush();
+
+        let match_events: Vec<_> = read_journal(&journal, Some(4)).into_iter().map(|entry| entry.tick).collect();
+        assert_eq!(match_events, vec![2, 3, 4]);
+
+        let lobby_entries = read_journal(&journal, None);
+        assert_eq!(lobby_entries.len(), 2);
+        assert!(lobby_entries.iter().all(|entry| entry.match_id.is_none()));
+
+        let first_entry = receiver.try_recv().unwrap();
+        assert_eq!(first_entry.event, GameEvent::PlayerJoined { client_id: 0, name: "Pig_1".to_string() });
+        assert_eq!(receiver.len(), 4);
+    }
+}
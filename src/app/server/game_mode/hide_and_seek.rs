@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+
+use rand::seq::{
+    IndexedRandom, 
+    IteratorRandom
+};
+
+use crate::{
+    app::server::{
+        client_session::ClientSessionId, 
+        config::{
+            NpcPersonalitiesConfig,
+            ServerConfig
+        }, 
+        gameplay::GameplayCommandError, 
+        GameplayResult, 
+        StartGameError
+    }, 
+    game::{
+        map_file::MapFile, 
+        map_generator::GeneratedMap, 
+        map_layout::MapLayout, 
+        math::Vector2F, 
+        world::{
+            EntityId, 
+            PlayerRole, 
+            World, 
+            ENTITY_SIZE
+        }
+    }, 
+    requests::UncoverResult
+};
+
+use super::{
+    GameMode, 
+    GameModeKind, 
+    LobbyPlayer, 
+    PlayerAction, 
+    PlayerActionResult, 
+    RoundScore, 
+    ScoreReason
+};
+
+/// One seeker tries to find hiders in crowd of NPCs before time or tries run out.
+#[derive(Debug)]
+pub struct HideAndSeek;
+
+impl GameMode for HideAndSeek {
+    fn kind(&self) -> GameModeKind {
+        GameModeKind::HideAndSeek
+    }
+
+    fn setup(
+        &self,
+        world: &mut World,
+        players: &[LobbyPlayer],
+        rng: &mut dyn rand::RngCore,
+        config: &ServerConfig,
+        map: Option<&MapFile>
+    ) -> Result<HashMap<ClientSessionId, EntityId>, StartGameError> {
+        if players.is_empty() {
+            return Err(StartGameError::NotEnoughPlayers);
+        }
+
+        let hiders_count = players.len().saturating_sub(1);
+
+        // Hand-authored map replaces generated one
+        let generated_map;
+        let map: &dyn MapLayout = match map {
+            Some(map) => {
+                log::info!("Using map '{}'", map.name);
+                map
+            },
+            None => {
+                generated_map = Self::generate_map(rng, hiders_count, config);
+                &generated_map
+            },
+        };
+        map.apply_to(world)?;
+
+        // Assign entity to players
+        let assigned_entities = Self::assign_world_entities_to_players(world, players, rng, map, config)?;
+
+        // Add NPCs
+        Self::place_npcs_around_world(world, rng, map, hiders_count * config.world.npcs_per_hider, &config.world.npc_personalities)?;
+
+        Ok(assigned_entities)
+    }
+
+    fn tick(&self, world: &mut World) {
+        world.tick_seeker_remaining_time();
+        world.tick_hiders_survival_time();
+    }
+
+    fn player_action(
+        &self,
+        world: &mut World,
+        entity_id: EntityId,
+        action: PlayerAction
+    ) -> Result<PlayerActionResult, GameplayCommandError> {
+        match action {
+            PlayerAction::Uncover { target_entity_id } => {
+                Self::try_uncover(world, entity_id, target_entity_id).map(PlayerActionResult::Uncover)
+            },
+        }
+    }
+
+    fn check_result(&self, world: &World, config: &ServerConfig) -> Option<GameplayResult> {
+        let hiders_reward = config.rewards.hiders_win;
+        let seeker_reward = config.rewards.seeker_win;
+
+        let summary = world.get_seeker_hiders_summary();
+
+        let seeker_win = if let Some((_, seeker_stats)) = summary.seeker {
+            if seeker_stats.remaining_failures == 0 || seeker_stats.remaining_ticks == 0 {
+                Some(GameplayResult::HidersWin { reward: hiders_reward })
+            } else {
+                None
+            }
+        } else {
+            // Seeker has gone, hiders win
+            Some(GameplayResult::HidersWin { reward: hiders_reward })
+        };
+
+        if seeker_win.is_some() {
+            return seeker_win;
+        }
+
+        summary.hiders.iter()
+            .all(|(_, h)| !h.covered)
+            .then_some(GameplayResult::SeekerWin { reward: seeker_reward })
+    }
+
+    fn score_round(&self, world: &World, result: &GameplayResult, config: &ServerConfig) -> HashMap<EntityId, RoundScore> {
+        let rewards = &config.rewards;
+        let summary = world.get_seeker_hiders_summary();
+        let mut scores = HashMap::new();
+
+        if let Some((seeker_id, stats)) = summary.seeker {
+            let mut score = RoundScore::new(PlayerRole::Seeker { stats });
+            if let GameplayResult::SeekerWin { reward } = result {
+                score.add(ScoreReason::Win, *reward as i32);
+            }
+
+            let hiders_found = summary.hiders.iter().filter(|(_, h)| !h.covered).count();
+            score.add(ScoreReason::HidersFound { count: hiders_found }, (hiders_found as u32 * rewards.seeker_per_hider_found) as i32);
+
+            let wrong_guesses = stats.max_failures.saturating_sub(stats.remaining_failures);
+            score.add(ScoreReason::WrongGuesses { count: wrong_guesses }, -((wrong_guesses as u32 * rewards.seeker_wrong_guess_penalty) as i32));
+
+            scores.insert(seeker_id, score);
+        }
+
+        for (hider_id, stats) in summary.hiders {
+            let mut score = RoundScore::new(PlayerRole::Hider { stats });
+            if let GameplayResult::HidersWin { reward } = result {
+                score.add(ScoreReason::Win, *reward as i32);
+            }
+
+            let survived_secs = stats.survived_ticks / config.ticks_per_second;
+            score.add(ScoreReason::Survived { secs: survived_secs }, (survived_secs / rewards.hider_survival_secs_per_point) as i32);
+
+            scores.insert(hider_id, score);
+        }
+
+        scores
+    }
+}
+
+impl HideAndSeek {
+    /// Map sized for players, walls and scenery from config
+    fn generate_map(
+        rng: &mut dyn rand::RngCore,
+        hiders_count: usize,
+        config: &ServerConfig
+    ) -> GeneratedMap {
+        let map_size = GeneratedMap::size_for(hiders_count, config.world.mapsize_generation_factor);
+        log::info!("Generating {:?} map {map_size}x{map_size}", config.world.map_style);
+        GeneratedMap::generate(config.world.map_style, map_size, config.world.obstacles_density, rng)
+    }
+
+    /// Positions not taken by any entity
+    fn free_positions(world: &World, positions: Vec<Vector2F>) -> Vec<Vector2F> {
+        positions.into_iter()
+            .filter(|position| !world.is_tile_occupied(position))
+            .collect()
+    }
+
+    /// NPCs fill density regions of map, without regions `expected_npcs_count` spread over whole floor
+    fn place_npcs_around_world(
+        world: &mut World, 
+        rng: &mut dyn rand::RngCore,
+        map: &dyn MapLayout,
+        expected_npcs_count: usize,
+        personalities: &NpcPersonalitiesConfig
+    ) -> Result<(), StartGameError> {
+        let regions = map.npc_regions();
+        if !regions.is_empty() {
+            for region in regions {
+                let free_tiles = Self::free_positions(world, region.positions);
+                let npcs_count = (free_tiles.len() as f32 * region.density).round() as usize;
+                for initial_position in free_tiles.choose_multiple(rng, npcs_count).copied().collect::<Vec<_>>() {
+                    let personality = personalities.choose(rng);
+                    let _entity_id = world.create_entity_npc_with_personality("NPC", initial_position, ENTITY_SIZE, personality);
+                }
+            }
+            return Ok(());
+        }
+
+        let free_tiles = Self::free_positions(world, map.floor_positions());
+        
+        // Need at least 1 spot for NPCs
+        if free_tiles.is_empty() {
+            return Err(StartGameError::NoFreeTiles);
+        }
+
+        let nps_count = free_tiles.len().min(expected_npcs_count);
+
+        let npcs_initial_positions: Vec<Vector2F> = free_tiles.choose_multiple(rng, nps_count).copied().collect();
+        for initial_position in npcs_initial_positions {
+            let personality = personalities.choose(rng);
+            let _entity_id = world.create_entity_npc_with_personality("NPC", initial_position, ENTITY_SIZE, personality);
+        }
+
+        Ok(())
+    }
+
+    fn assign_world_entities_to_players(
+        world: &mut World, 
+        players: &[LobbyPlayer],
+        rng: &mut dyn rand::RngCore,
+        map: &dyn MapLayout,
+        config: &ServerConfig
+    ) -> Result<HashMap<ClientSessionId, EntityId>, StartGameError> {
+        let seeker_client_id = players.iter()
+            .map(|player| player.client_id)
+            .choose(rng)
+            .ok_or(StartGameError::NotEnoughPlayers)?;
+
+        let free_tiles = Self::free_positions(world, map.player_spawn_positions());
+        
+        if free_tiles.len() < players.len() {
+            return Err(StartGameError::NoFreeTiles);
+        }
+
+        // TODO spread hiders and seekers, some Voronoi can work
+        let mut initial_players_positions = free_tiles.choose_multiple(rng, players.len());
+        let mut assigned_entities = HashMap::new();
+        
+        for player in players {
+            let intial_position = initial_players_positions.next().expect("Player should have initial position selected");
+            let assigned_id = world.create_entity_player(&player.name, *intial_position, ENTITY_SIZE);
+            assigned_entities.insert(player.client_id, assigned_id);
+
+            // Assign seeker role to one entity
+            if seeker_client_id == player.client_id {
+                world.select_entity_as_seeker(
+                    assigned_id, 
+                    config.tick_rate().ticks_in(config.seeking.max_time()), 
+                    config.seeking.max_tries
+                )?;
+            }
+
+            log::info!("Player '{}' gets Entity assigned id={assigned_id}", player.name);
+        }
+
+        Ok(assigned_entities)
+    }
+
+    fn try_uncover(
+        world: &mut World, 
+        seeker_entity_id: EntityId, 
+        uncovering_entity_id: EntityId
+    ) -> Result<UncoverResult, GameplayCommandError> {
+        // Only seeker uncovers
+        let is_seeker = world.get_entity_by_id(seeker_entity_id)
+            .ok_or(GameplayCommandError::EntityNotFound { id: seeker_entity_id })?
+            .get_player_role()
+            .is_some_and(|role| matches!(role, PlayerRole::Seeker { stats: _ }));
+        if !is_seeker {
+            return Err(GameplayCommandError::EntityNotSeeker { id: seeker_entity_id });
+        }
+
+        // Cannot uncover self
+        if seeker_entity_id == uncovering_entity_id {
+            return Ok(UncoverResult { was_hider: None });
+        }
+
+        // Check if can uncover
+        let can_uncover = {
+            let (seeker_entity, other_entity) = match (world.get_entity_by_id(seeker_entity_id), world.get_entity_by_id(uncovering_entity_id)) {
+                (Some(entity_s), Some(entity_o)) => (entity_s, entity_o),
+                (None, _) => {
+                    return Err(GameplayCommandError::EntityNotFound { id: seeker_entity_id });
+                }, 
+                (_, None) => {
+                    return Err(GameplayCommandError::EntityNotFound { id: uncovering_entity_id });
+                }, 
+            };
+
+            World::is_entity_inrange(seeker_entity.position, other_entity.position)
+        };
+
+        let was_hider = if can_uncover {
+            let was_hider = world.get_entity_by_id(uncovering_entity_id)
+                .ok_or(GameplayCommandError::EntityNotFound { id: uncovering_entity_id })?
+                .get_player_role()
+                .is_some_and(|role| matches!(role, PlayerRole::Hider { stats: _ }));
+
+            if was_hider {
+                // Uncover hider player do not remove
+                world.get_entity_by_id_mut(uncovering_entity_id)
+                    .ok_or(GameplayCommandError::EntityNotFound { id: uncovering_entity_id })?
+                    .set_hider_covered(false)?;
+                world.get_entity_by_id_mut(seeker_entity_id)
+                    .ok_or(GameplayCommandError::EntityNotFound { id: seeker_entity_id })?
+                    .reward_seeker()?;
+                Some(true)
+            } else {
+                // Remove NPC and punish seeker
+                world.remove_entity(uncovering_entity_id)?;
+                world.get_entity_by_id_mut(seeker_entity_id)
+                    .ok_or(GameplayCommandError::EntityNotFound { id: seeker_entity_id })?
+                    .punish_seeker()?;
+                Some(false)
+            }
+        } else {
+            // Notify not in range
+            None
+        };
+
+        Ok(UncoverResult { was_hider })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn players(count: usize) -> Vec<LobbyPlayer> {
+        (0..count)
+            .map(|id| LobbyPlayer { client_id: id as ClientSessionId, name: format!("Player_{id}") })
+            .collect()
+    }
+
+    #[test]
+    fn test_setup_assigns_single_seeker() {
+        let mut world = World::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let config = ServerConfig::default();
+
+        let assigned_entities = HideAndSeek.setup(&mut world, &players(3), &mut rng, &config, None).unwrap();
+        assert_eq!(assigned_entities.len(), 3);
+
+        let summary = world.get_seeker_hiders_summary();
+        assert!(summary.seeker.is_some());
+        assert_eq!(summary.hiders.len(), 2);
+        assert!(HideAndSeek.check_result(&world, &config).is_none());
+    }
+
+    #[test]
+    fn test_setup_with_same_seed_is_reproducible() {
+        fn setup(seed: u64) -> Vec<(EntityId, Vector2F, bool)> {
+            let mut world = World::with_seed(Default::default(), seed);
+            let mut rng = world.fork_rng();
+            HideAndSeek.setup(&mut world, &players(3), &mut rng, &ServerConfig::default(), None).unwrap();
+            world.iter_entities()
+                .map(|e| (e.id, e.position, matches!(e.get_player_role(), Some(PlayerRole::Seeker { stats: _ }))))
+                .collect()
+        }
+
+        assert_eq!(setup(11), setup(11));
+    }
+
+    #[test]
+    fn test_setup_places_obstacles_apart_from_entities() {
+        let mut world = World::with_seed(Default::default(), 3);
+        let mut rng = world.fork_rng();
+        let mut config = ServerConfig::default();
+        config.world.obstacles_density = 0.3;
+        HideAndSeek.setup(&mut world, &players(4), &mut rng, &config, None).unwrap();
+
+        let obstacles = world.obstacles();
+        assert!(!obstacles.is_empty());
+        for entity in world.iter_entities() {
+            assert!(!world.is_tile_blocked(&entity.position), "Entity {} placed on obstacle", entity.id);
+        }
+    }
+
+    #[test]
+    fn test_setup_uses_map_file() {
+        let map = MapFile::parse(concat!(
+            "name = 'Two rooms'\n",
+            "grid = '''\n",
+            "#########\n",
+            "#SS.#...#\n",
+            "#S......#\n",
+            "#########\n",
+            "'''\n",
+            "[[npc_regions]]\n",
+            "area = { x = 5, y = 1, width = 3, height = 2 }\n",
+            "density = 1.0\n",
+        )).unwrap();
+
+        let mut world = World::with_seed(Default::default(), 3);
+        let mut rng = world.fork_rng();
+        HideAndSeek.setup(&mut world, &players(3), &mut rng, &ServerConfig::default(), Some(&map)).unwrap();
+
+        let spawns = map.player_spawn_positions();
+        let npc_area = map.npc_regions().remove(0).positions;
+        for entity in world.iter_entities() {
+            if entity.is_player() {
+                assert!(spawns.contains(&entity.position), "Player {} outside spawn zone", entity.id);
+            } else {
+                assert!(npc_area.contains(&entity.position), "NPC {} outside region", entity.id);
+            }
+        }
+        assert_eq!(world.iter_entities().filter(|entity| !entity.is_player()).count(), npc_area.len());
+        assert_eq!(world.obstacles().len(), 23);
+        assert_eq!(world.bounds(), Some(map.bounds()));
+    }
+
+    #[test]
+    fn test_setup_requires_players() {
+        let mut world = World::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let result = HideAndSeek.setup(&mut world, &[], &mut rng, &ServerConfig::default(), None);
+        assert!(matches!(result, Err(StartGameError::NotEnoughPlayers)));
+    }
+
+    #[test]
+    fn test_seeker_wins_after_uncovering_all_hiders() {
+        let mut world = World::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let config = ServerConfig::default();
+        HideAndSeek.setup(&mut world, &players(2), &mut rng, &config, None).unwrap();
+
+        let summary = world.get_seeker_hiders_summary();
+        let (seeker_id, _) = summary.seeker.unwrap();
+        let (hider_id, _) = summary.hiders[0];
+
+        // Bring hider next to seeker
+        let seeker_position = world.get_entity_by_id(seeker_id).unwrap().position;
+        world.teleport_entity(hider_id, seeker_position).unwrap();
+
+        let action_result = HideAndSeek.player_action(&mut world, seeker_id, PlayerAction::Uncover { target_entity_id: hider_id }).unwrap();
+        assert!(matches!(action_result, PlayerActionResult::Uncover(UncoverResult { was_hider: Some(true) })));
+        assert!(matches!(HideAndSeek.check_result(&world, &config), Some(GameplayResult::SeekerWin { reward: _ })));
+    }
+
+    #[test]
+    fn test_hider_cannot_uncover() {
+        let mut world = World::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let config = ServerConfig::default();
+        HideAndSeek.setup(&mut world, &players(3), &mut rng, &config, None).unwrap();
+
+        let summary = world.get_seeker_hiders_summary();
+        let (seeker_id, _) = summary.seeker.unwrap();
+        let (hider_id, _) = summary.hiders[0];
+        let (other_hider_id, _) = summary.hiders[1];
+        let npc_id = world.iter_entities().find(|e| e.get_player_role().is_none()).unwrap().id;
+        let entities_count = world.iter_entities().count();
+
+        // Everything in range of hider
+        let hider_position = world.get_entity_by_id(hider_id).unwrap().position;
+        for target_id in [seeker_id, other_hider_id, npc_id] {
+            world.teleport_entity(target_id, hider_position).unwrap();
+            let result = HideAndSeek.player_action(&mut world, hider_id, PlayerAction::Uncover { target_entity_id: target_id });
+            assert!(matches!(result, Err(GameplayCommandError::EntityNotSeeker { id }) if id == hider_id));
+        }
+
+        assert_eq!(world.iter_entities().count(), entities_count);
+        let other_hider_role = world.get_entity_by_id(other_hider_id).unwrap().get_player_role();
+        assert!(matches!(other_hider_role, Some(PlayerRole::Hider { stats }) if stats.covered));
+        let seeker_role = world.get_entity_by_id(seeker_id).unwrap().get_player_role();
+        assert!(matches!(seeker_role, Some(PlayerRole::Seeker { stats }) if stats.remaining_failures == config.seeking.max_tries));
+    }
+
+    #[test]
+    fn test_round_scoring_breakdown() {
+        let mut world = World::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let config = ServerConfig::default();
+        HideAndSeek.setup(&mut world, &players(3), &mut rng, &config, None).unwrap();
+
+        let summary = world.get_seeker_hiders_summary();
+        let (seeker_id, _) = summary.seeker.unwrap();
+        let (found_hider_id, _) = summary.hiders[0];
+        let (covered_hider_id, _) = summary.hiders[1];
+
+        // First hider found after 45 secs, second stays covered for 65 secs
+        let ticks_per_second = config.ticks_per_second;
+        for _ in 0..(45 * ticks_per_second) {
+            HideAndSeek.tick(&mut world);
+        }
+        world.get_entity_by_id_mut(found_hider_id).unwrap().set_hider_covered(false).unwrap();
+        for _ in 0..(20 * ticks_per_second) {
+            HideAndSeek.tick(&mut world);
+        }
+        world.get_entity_by_id_mut(seeker_id).unwrap().punish_seeker().unwrap();
+
+        let scores = HideAndSeek.score_round(&world, &GameplayResult::HidersWin { reward: 5 }, &config);
+        assert_eq!(scores.len(), 3);
+
+        let seeker_score = &scores[&seeker_id];
+        let seeker_reasons: Vec<_> = seeker_score.entries.iter().map(|e| (e.reason.clone(), e.points)).collect();
+        assert_eq!(seeker_reasons, vec![
+            (ScoreReason::HidersFound { count: 1 }, 2),
+            (ScoreReason::WrongGuesses { count: 1 }, -1),
+        ]);
+        assert_eq!(seeker_score.total(), 1);
+
+        assert_eq!(scores[&found_hider_id].total(), 5 + 45 / 20);
+        assert_eq!(scores[&covered_hider_id].total(), 5 + 65 / 20);
+    }
+}
@@ -0,0 +1,148 @@
+pub mod hide_and_seek;
+
+use std::collections::HashMap;
+
+use hide_and_seek::HideAndSeek;
+
+use serde::{
+    Deserialize, 
+    Serialize
+};
+
+use crate::{
+    game::{
+        map_file::MapFile, 
+        world::{
+            EntityId, 
+            PlayerRole, 
+            World
+        }
+    }, 
+    requests::UncoverResult
+};
+
+use super::{
+    client_session::ClientSessionId, 
+    config::ServerConfig, 
+    gameplay::GameplayCommandError, 
+    GameplayResult, 
+    StartGameError
+};
+
+/// Selectable game modes, chosen in lobby config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameModeKind {
+    #[default]
+    HideAndSeek,
+}
+
+impl GameModeKind {
+    pub fn create(&self) -> Box<dyn GameMode> {
+        match self {
+            GameModeKind::HideAndSeek => Box::new(HideAndSeek),
+        }
+    }
+}
+
+/// Client taking part in starting round
+#[derive(Debug, Clone)]
+pub struct LobbyPlayer {
+    pub client_id: ClientSessionId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PlayerAction {
+    Uncover {
+        target_entity_id: EntityId,
+    },
+}
+
+#[derive(Debug)]
+pub enum PlayerActionResult {
+    Uncover(UncoverResult),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScoreReason {
+    Win,
+    HidersFound {
+        count: usize
+    },
+    WrongGuesses {
+        count: usize
+    },
+    Survived {
+        secs: u32
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub reason: ScoreReason,
+    pub points: i32,
+}
+
+/// Points player gained in single round with reasons
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoundScore {
+    pub role: PlayerRole,
+    pub entries: Vec<ScoreEntry>,
+}
+
+impl RoundScore {
+    pub fn new(role: PlayerRole) -> Self {
+        Self { 
+            role, 
+            entries: vec![] 
+        }
+    }
+
+    /// Entries giving no points are skipped
+    pub fn add(&mut self, reason: ScoreReason, points: i32) {
+        if points != 0 {
+            self.entries.push(ScoreEntry { reason, points });
+        }
+    }
+
+    /// Sum of entries, round never takes points away
+    pub fn total(&self) -> u32 {
+        self.entries.iter()
+            .map(|entry| entry.points)
+            .sum::<i32>()
+            .max(0) as u32
+    }
+}
+
+/// Rules of a round. Server owns lobby, clients and world simulation,
+/// game mode decides what players do in the world and who wins.
+pub trait GameMode: std::fmt::Debug + Send + Sync {
+    fn kind(&self) -> GameModeKind;
+
+    /// Populate fresh world on given map or generated one, returns entity assigned to every player
+    fn setup(
+        &self,
+        world: &mut World,
+        players: &[LobbyPlayer],
+        rng: &mut dyn rand::RngCore,
+        config: &ServerConfig,
+        map: Option<&MapFile>
+    ) -> Result<HashMap<ClientSessionId, EntityId>, StartGameError>;
+
+    /// Called every tick before result check and world simulation
+    fn tick(&self, world: &mut World);
+
+    fn player_action(
+        &self,
+        world: &mut World,
+        entity_id: EntityId,
+        action: PlayerAction
+    ) -> Result<PlayerActionResult, GameplayCommandError>;
+
+    /// Round ends when result is returned, reward is granted to winners
+    fn check_result(&self, world: &World, config: &ServerConfig) -> Option<GameplayResult>;
+
+    /// Points of every player entity when round ends with `result`
+    fn score_round(&self, world: &World, result: &GameplayResult, config: &ServerConfig) -> HashMap<EntityId, RoundScore>;
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc
+};
+
+use tokio::sync::oneshot;
+
+use crate::{
+    game::{
+        math::{
+            Vector2F,
+            Vector2I
+        },
+        world::{
+            EntityId,
+            MovementMode,
+            PlayerRole,
+            World,
+            WorldError,
+            WorldMap
+        }
+    },
+    requests::{
+        EntityCheckData,
+        GameplayStateBrief,
+        MoveDirection
+    }
+};
+
+use super::{
+    events::RoundPlayer,
+    game_mode::{
+        PlayerAction,
+        PlayerActionResult
+    },
+    saved_world::SavedWorldError,
+    GameplayState
+};
+
+pub const GAMEPLAY_COMMANDS_CAPACITY: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameplayCommandError {
+    #[error("BadState")]
+    BadState,
+
+    #[error("EntityNotFound, id={id}")]
+    EntityNotFound {
+        id: EntityId
+    },
+
+    #[error("ActionNotSupported")]
+    ActionNotSupported,
+
+    #[error("NoPathFound")]
+    NoPathFound,
+
+    #[error("EntityNotSeeker, id={id}")]
+    EntityNotSeeker {
+        id: EntityId
+    },
+
+    #[error("MoveOutOfBounds, destination={destination}")]
+    MoveOutOfBounds {
+        destination: Vector2F
+    },
+
+    #[error("Gameplay task is not running")]
+    GameplayTaskGone,
+
+    #[error("SavedWorldError, reason='{0}'")]
+    SavedWorldError(#[from] SavedWorldError),
+
+    #[error("WorldError, reason='{0}'")]
+    WorldError(#[from] WorldError),
+}
+
+pub type GameplayCommandReply<T> = oneshot::Sender<Result<T, GameplayCommandError>>;
+
+/// Requests mutating gameplay state. Processed by main task between ticks,
+/// every command gets its result through `reply`.
+#[derive(Debug)]
+pub enum GameplayCommand {
+    Move {
+        entity_id: EntityId,
+        dir: MoveDirection,
+        reply: GameplayCommandReply<bool>,
+    },
+    /// Plan path for player entity, it is walked in following ticks
+    MoveTo {
+        entity_id: EntityId,
+        target: Vector2F,
+        reply: GameplayCommandReply<usize>,
+    },
+    /// Walking direction of player entity in free movement mode
+    SetVelocity {
+        entity_id: EntityId,
+        direction: Vector2F,
+        reply: GameplayCommandReply<()>,
+    },
+    /// Action handled by game mode of running round
+    PlayerAction {
+        entity_id: EntityId,
+        action: PlayerAction,
+        reply: GameplayCommandReply<PlayerActionResult>,
+    },
+    /// Write world of running round to file
+    SaveWorld {
+        path: PathBuf,
+        reply: GameplayCommandReply<()>,
+    },
+    /// Replace current state with round loaded from file
+    RestoreWorld {
+        path: PathBuf,
+        reply: GameplayCommandReply<Vec<RoundPlayer>>,
+    },
+}
+
+/// Part of world seeker sees, other entities stay hidden from it
+#[derive(Debug, Default)]
+pub struct SeekerView {
+    pub visible_tiles: Vec<Vector2I>,
+    pub entities: Vec<EntityCheckData>,
+}
+
+#[derive(Debug, Default)]
+pub struct WorldSnapshot {
+    pub entities: Vec<EntityCheckData>,
+    pub player_roles: HashMap<EntityId, PlayerRole>,
+    /// Shared with world, built once per round
+    pub map: Arc<WorldMap>,
+    /// Only seekers limited by view radius have their view
+    pub seeker_views: HashMap<EntityId, SeekerView>,
+    pub movement_mode: MovementMode,
+}
+
+/// Read-only view of gameplay state, published by main task after every tick
+/// and every processed command. Readers never block simulation.
+#[derive(Debug)]
+pub struct GameplaySnapshot {
+    pub tick: u64,
+    pub state: GameplayStateBrief,
+    pub world: Option<WorldSnapshot>,
+}
+
+pub type GameplaySnapshotSender = tokio::sync::watch::Sender<Arc<GameplaySnapshot>>;
+pub type GameplaySnapshotReceiver = tokio::sync::watch::Receiver<Arc<GameplaySnapshot>>;
+
+impl Default for GameplaySnapshot {
+    fn default() -> Self {
+        Self::from_state(0, &GameplayState::default())
+    }
+}
+
+impl GameplaySnapshot {
+    pub fn from_state(tick: u64, state: &GameplayState) -> Self {
+        let world = match state {
+            GameplayState::GameRunning { world, game_mode: _, match_recorder: _, replay: _ } => Some(WorldSnapshot {
+                entities: EntityCheckData::vec_from_iter(world.iter_entities()),
+                player_roles: world.iter_entities()
+                    .filter_map(|e| e.get_player_role().map(|role| (e.id, *role)))
+                    .collect(),
+                map: world.map(),
+                seeker_views: world.iter_entities()
+                    .filter_map(|seeker| world.seeker_sight(seeker.id).map(|sight| (seeker.id, sight)))
+                    .map(|(seeker_id, sight)| (seeker_id, SeekerView {
+                        visible_tiles: sight.tiles().collect(),
+                        entities: EntityCheckData::vec_from_iter(world.iter_entities().filter(|e| World::is_entity_in_sight(e, &sight))),
+                    }))
+                    .collect(),
+                movement_mode: world.movement_mode(),
+            }),
+            _ => None,
+        };
+
+        Self {
+            tick,
+            state: state.into(),
+            world,
+        }
+    }
+}
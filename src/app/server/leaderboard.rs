@@ -0,0 +1,128 @@
+use std::cmp::Ordering;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use super::profiles::PlayerProfile;
+
+/// Leaderboard never returns more entries regardless of requested size
+pub const MAX_LEADERBOARD_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeaderboardSort {
+    #[default]
+    TotalPoints,
+    Wins,
+    GamesPlayed,
+    UncoverAccuracy,
+    LongestSurvival,
+}
+
+/// Public statistics derived from player profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub name: String,
+    pub total_points: u32,
+    pub games_played: u32,
+    pub wins: u32,
+    /// None if never played as seeker
+    pub seeker_win_rate: Option<f32>,
+    /// None if never played as hider
+    pub hider_win_rate: Option<f32>,
+    /// Part of uncover attempts which found hider, None if never tried
+    pub uncover_accuracy: Option<f32>,
+    pub longest_survival_secs: u32,
+}
+
+fn ratio(part: u32, total: u32) -> Option<f32> {
+    (total > 0).then(|| part as f32 / total as f32)
+}
+
+impl From<&PlayerProfile> for PlayerStats {
+    fn from(profile: &PlayerProfile) -> Self {
+        Self {
+            name: profile.name.clone(),
+            total_points: profile.total_points,
+            games_played: profile.games_played,
+            wins: profile.wins_as_seeker + profile.wins_as_hider,
+            seeker_win_rate: ratio(profile.wins_as_seeker, profile.games_as_seeker),
+            hider_win_rate: ratio(profile.wins_as_hider, profile.games_as_hider),
+            uncover_accuracy: ratio(profile.hiders_found, profile.hiders_found + profile.wrong_guesses),
+            longest_survival_secs: profile.longest_survival_secs,
+        }
+    }
+}
+
+impl PlayerStats {
+    /// Greater is better, missing rates are worse than any rate
+    fn compare_by(&self, other: &Self, sort_by: LeaderboardSort) -> Ordering {
+        let compare_rates = |a: Option<f32>, b: Option<f32>| {
+            a.unwrap_or(-1.0).partial_cmp(&b.unwrap_or(-1.0)).unwrap_or(Ordering::Equal)
+        };
+
+        match sort_by {
+            LeaderboardSort::TotalPoints => self.total_points.cmp(&other.total_points),
+            LeaderboardSort::Wins => self.wins.cmp(&other.wins),
+            LeaderboardSort::GamesPlayed => self.games_played.cmp(&other.games_played),
+            LeaderboardSort::UncoverAccuracy => compare_rates(self.uncover_accuracy, other.uncover_accuracy),
+            LeaderboardSort::LongestSurvival => self.longest_survival_secs.cmp(&other.longest_survival_secs),
+        }
+    }
+}
+
+/// Best `top_n` players, ties ordered by name
+pub fn build_leaderboard(profiles: &[PlayerProfile], top_n: usize, sort_by: LeaderboardSort) -> Vec<PlayerStats> {
+    let mut stats: Vec<PlayerStats> = profiles.iter().map(PlayerStats::from).collect();
+    stats.sort_by(|a, b| b.compare_by(a, sort_by).then_with(|| a.name.cmp(&b.name)));
+    stats.truncate(top_n.min(MAX_LEADERBOARD_SIZE));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, total_points: u32, hiders_found: u32, wrong_guesses: u32) -> PlayerProfile {
+        PlayerProfile {
+            total_points,
+            hiders_found,
+            wrong_guesses,
+            ..PlayerProfile::new(name)
+        }
+    }
+
+    #[test]
+    fn test_player_stats_rates() {
+        let stats = PlayerStats::from(&PlayerProfile {
+            games_as_seeker: 4,
+            wins_as_seeker: 1,
+            hiders_found: 3,
+            wrong_guesses: 1,
+            ..PlayerProfile::new("Goose_2")
+        });
+
+        assert_eq!(stats.seeker_win_rate, Some(0.25));
+        assert_eq!(stats.hider_win_rate, None);
+        assert_eq!(stats.uncover_accuracy, Some(0.75));
+    }
+
+    #[test]
+    fn test_leaderboard_sorting() {
+        let profiles = vec![
+            profile("Cat_1", 10, 0, 0),
+            profile("Beaver_1", 30, 1, 3),
+            profile("Anaconda_1", 10, 2, 0),
+        ];
+
+        let names = |sort_by| build_leaderboard(&profiles, 10, sort_by)
+            .into_iter()
+            .map(|stats| stats.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names(LeaderboardSort::TotalPoints), vec!["Beaver_1", "Anaconda_1", "Cat_1"]);
+        assert_eq!(names(LeaderboardSort::UncoverAccuracy), vec!["Anaconda_1", "Beaver_1", "Cat_1"]);
+        assert_eq!(build_leaderboard(&profiles, 1, LeaderboardSort::TotalPoints).len(), 1);
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    io::Write,
+    path::{
+        Path,
+        PathBuf
+    },
+    sync::Mutex,
+    time::SystemTime
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::game::world::{
+    EntityId,
+    PlayerRole,
+    World
+};
+
+use super::{
+    game_mode::GameModeKind,
+    scheduler::TickRate,
+    GameplayResult
+};
+
+pub type MatchId = u64;
+
+/// Match history never returns more summaries regardless of requested limit
+pub const MAX_MATCH_HISTORY_SIZE: usize = 100;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MatchHistoryError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("SerializationError, reason='{0}'")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchPlayer {
+    pub name: String,
+    pub entity_id: EntityId,
+    /// Role with stats at the end of match
+    pub role: PlayerRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UncoverAttempt {
+    /// Time since match start
+    pub at_secs: f32,
+    pub seeker_name: String,
+    pub target_entity_id: EntityId,
+    /// Same as `UncoverResult`, None if target was out of range
+    pub was_hider: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HiderFound {
+    pub name: String,
+    /// Time since match start
+    pub at_secs: f32,
+}
+
+/// Archived round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub id: MatchId,
+    pub game_mode: GameModeKind,
+    /// World seed, replaying inputs with it gives the same round
+    #[serde(default)]
+    pub seed: u64,
+    pub started_at: SystemTime,
+    pub ended_at: SystemTime,
+    pub players: Vec<MatchPlayer>,
+    pub uncover_attempts: Vec<UncoverAttempt>,
+    pub hiders_found: Vec<HiderFound>,
+    pub result: GameplayResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchSummary {
+    pub id: MatchId,
+    pub started_at: SystemTime,
+    pub ended_at: SystemTime,
+    pub players: Vec<String>,
+    pub result: GameplayResult,
+}
+
+impl From<&MatchRecord> for MatchSummary {
+    fn from(record: &MatchRecord) -> Self {
+        Self {
+            id: record.id,
+            started_at: record.started_at,
+            ended_at: record.ended_at,
+            players: record.players.iter().map(|player| player.name.clone()).collect(),
+            result: record.result,
+        }
+    }
+}
+
+/// Collects what happens during running round, turned into `MatchRecord` when round ends
+#[derive(Debug)]
+pub struct MatchRecorder {
+    id: MatchId,
+    game_mode: GameModeKind,
+    seed: u64,
+    started_at: SystemTime,
+    ticks: u32,
+    players: Vec<(EntityId, String)>,
+    uncover_attempts: Vec<(u32, EntityId, EntityId, Option<bool>)>,
+}
+
+impl MatchRecorder {
+    pub fn new(id: MatchId, game_mode: GameModeKind, seed: u64) -> Self {
+        Self {
+            id,
+            game_mode,
+            seed,
+            started_at: SystemTime::now(),
+            ticks: 0,
+            players: vec![],
+            uncover_attempts: vec![],
+        }
+    }
+
+    pub fn id(&self) -> MatchId {
+        self.id
+    }
+
+    pub fn add_player<S: Into<String>>(&mut self, entity_id: EntityId, name: S) {
+        self.players.push((entity_id, name.into()));
+    }
+
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    pub fn record_uncover(&mut self, seeker_entity_id: EntityId, target_entity_id: EntityId, was_hider: Option<bool>) {
+        self.uncover_attempts.push((self.ticks, seeker_entity_id, target_entity_id, was_hider));
+    }
+
+    fn player_name(&self, entity_id: EntityId) -> String {
+        self.players.iter()
+            .find(|(id, _)| *id == entity_id)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn finish(&self, world: &World, result: GameplayResult, tick_rate: TickRate) -> MatchRecord {
+        let ticks_to_secs = |ticks: u32| ticks as f32 / tick_rate.ticks_per_second() as f32;
+
+        let players = self.players.iter()
+            .filter_map(|(entity_id, name)| world.get_entity_by_id(*entity_id)
+                .and_then(|entity| entity.get_player_role())
+                .map(|role| MatchPlayer { name: name.clone(), entity_id: *entity_id, role: *role })
+            )
+            .collect::<Vec<_>>();
+
+        // Hiders survival time stops growing when they are found
+        let mut hiders_found = players.iter()
+            .filter_map(|player| match player.role {
+                PlayerRole::Hider { stats } if !stats.covered => Some(HiderFound { 
+                    name: player.name.clone(), 
+                    at_secs: ticks_to_secs(stats.survived_ticks) 
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        hiders_found.sort_by(|a, b| a.at_secs.total_cmp(&b.at_secs));
+
+        let uncover_attempts = self.uncover_attempts.iter()
+            .map(|(tick, seeker_entity_id, target_entity_id, was_hider)| UncoverAttempt {
+                at_secs: ticks_to_secs(*tick),
+                seeker_name: self.player_name(*seeker_entity_id),
+                target_entity_id: *target_entity_id,
+                was_hider: *was_hider,
+            })
+            .collect();
+
+        MatchRecord {
+            id: self.id,
+            game_mode: self.game_mode,
+            seed: self.seed,
+            started_at: self.started_at,
+            ended_at: SystemTime::now(),
+            players,
+            uncover_attempts,
+            hiders_found,
+            result,
+        }
+    }
+}
+
+pub trait MatchHistoryStore: Send + Sync {
+    /// Keep record in memory, `get` and `recent` see it at once. Written by next `persist`.
+    fn add_deferred(&self, record: MatchRecord);
+
+    /// Write records added by `add_deferred`, may be slow
+    fn persist(&self) -> Result<(), MatchHistoryError>;
+
+    fn add(&self, record: MatchRecord) -> Result<(), MatchHistoryError> {
+        self.add_deferred(record);
+        self.persist()
+    }
+
+    fn get(&self, id: MatchId) -> Result<Option<MatchRecord>, MatchHistoryError>;
+
+    /// Newest matches first
+    fn recent(&self, limit: usize) -> Result<Vec<MatchSummary>, MatchHistoryError>;
+
+    fn next_id(&self) -> MatchId;
+}
+
+fn recent_summaries(records: &[MatchRecord], limit: usize) -> Vec<MatchSummary> {
+    records.iter()
+        .rev()
+        .take(limit)
+        .map(MatchSummary::from)
+        .collect()
+}
+
+fn next_match_id(records: &[MatchRecord]) -> MatchId {
+    records.iter()
+        .map(|record| record.id + 1)
+        .max()
+        .unwrap_or_default()
+}
+
+/// History lost on restart, used when no history file is configured
+#[derive(Debug, Default)]
+pub struct InMemoryMatchHistory {
+    records: Mutex<Vec<MatchRecord>>,
+}
+
+impl MatchHistoryStore for InMemoryMatchHistory {
+    fn add_deferred(&self, record: MatchRecord) {
+        self.records.lock().unwrap().push(record);
+    }
+
+    fn persist(&self) -> Result<(), MatchHistoryError> {
+        Ok(())
+    }
+
+    fn get(&self, id: MatchId) -> Result<Option<MatchRecord>, MatchHistoryError> {
+        Ok(self.records.lock().unwrap().iter().find(|record| record.id == id).cloned())
+    }
+
+    fn recent(&self, limit: usize) -> Result<Vec<MatchSummary>, MatchHistoryError> {
+        Ok(recent_summaries(&self.records.lock().unwrap(), limit))
+    }
+
+    fn next_id(&self) -> MatchId {
+        next_match_id(&self.records.lock().unwrap())
+    }
+}
+
+/// Every match is appended as single JSON line
+#[derive(Debug)]
+pub struct JsonLinesMatchHistory {
+    path: PathBuf,
+    records: Mutex<Vec<MatchRecord>>,
+    /// Number of records already in file, held while file is appended.
+    /// Always taken before `records`.
+    written: Mutex<usize>,
+}
+
+impl JsonLinesMatchHistory {
+    /// Read archived matches, missing file means no matches yet
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MatchHistoryError> {
+        let path = path.as_ref().to_path_buf();
+        let records = match std::fs::read_to_string(&path) {
+            Ok(content) => content.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str::<MatchRecord>)
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+
+        log::info!("Loaded {} archived matches from {path:?}", records.len());
+        Ok(Self {
+            path,
+            written: Mutex::new(records.len()),
+            records: Mutex::new(records)
+        })
+    }
+}
+
+impl MatchHistoryStore for JsonLinesMatchHistory {
+    fn add_deferred(&self, record: MatchRecord) {
+        self.records.lock().unwrap().push(record);
+    }
+
+    fn persist(&self) -> Result<(), MatchHistoryError> {
+        // Records stay unlocked during write, so deferred adds never wait for disk.
+        // Lines which failed to be written are retried by next call.
+        let mut written_guard = self.written.lock().unwrap();
+        let (lines, records_count) = {
+            let records_guard = self.records.lock().unwrap();
+            let mut lines = String::new();
+            for record in &records_guard[*written_guard..] {
+                lines.push_str(&serde_json::to_string(record)?);
+                lines.push('\n');
+            }
+            (lines, records_guard.len())
+        };
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(lines.as_bytes())?;
+        *written_guard = records_count;
+        Ok(())
+    }
+
+    fn get(&self, id: MatchId) -> Result<Option<MatchRecord>, MatchHistoryError> {
+        Ok(self.records.lock().unwrap().iter().find(|record| record.id == id).cloned())
+    }
+
+    fn recent(&self, limit: usize) -> Result<Vec<MatchSummary>, MatchHistoryError> {
+        Ok(recent_summaries(&self.records.lock().unwrap(), limit))
+    }
+
+    fn next_id(&self) -> MatchId {
+        next_match_id(&self.records.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::world::ENTITY_SIZE;
+
+    use super::*;
+
+    fn finished_match(id: MatchId) -> MatchRecord {
+        let mut world = World::new();
+        let seeker_id = world.create_entity_player("Seeker", Default::default(), ENTITY_SIZE);
+        let hider_id = world.create_entity_player("Hider", Default::default(), ENTITY_SIZE);
+        world.select_entity_as_seeker(seeker_id, 100, 3).unwrap();
+
+        let mut recorder = MatchRecorder::new(id, GameModeKind::HideAndSeek, 0);
+        recorder.add_player(seeker_id, "Seeker");
+        recorder.add_player(hider_id, "Hider");
+
+        for _ in 0..20 {
+            recorder.tick();
+            world.tick_hiders_survival_time();
+        }
+        recorder.record_uncover(seeker_id, hider_id, Some(true));
+        world.get_entity_by_id_mut(hider_id).unwrap().set_hider_covered(false).unwrap();
+
+        recorder.finish(&world, GameplayResult::SeekerWin { reward: 5 }, TickRate::new(10))
+    }
+
+    #[test]
+    fn test_recorder_builds_match_record() {
+        let record = finished_match(3);
+        assert_eq!(record.id, 3);
+        assert_eq!(record.players.len(), 2);
+        assert_eq!(record.uncover_attempts, vec![UncoverAttempt { 
+            at_secs: 2.0, 
+            seeker_name: "Seeker".to_string(), 
+            target_entity_id: 1, 
+            was_hider: Some(true) 
+        }]);
+        assert_eq!(record.hiders_found, vec![HiderFound { name: "Hider".to_string(), at_secs: 2.0 }]);
+    }
+
+    #[test]
+    fn test_json_lines_history_persists_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matches.jsonl");
+
+        {
+            let history = JsonLinesMatchHistory::open(&path).unwrap();
+            assert_eq!(history.next_id(), 0);
+            history.add(finished_match(0)).unwrap();
+            history.add(finished_match(1)).unwrap();
+        }
+
+        let history = JsonLinesMatchHistory::open(&path).unwrap();
+        assert_eq!(history.next_id(), 2);
+        assert_eq!(history.get(1).unwrap().map(|record| record.id), Some(1));
+        assert_eq!(history.get(2).unwrap(), None);
+
+        let recent: Vec<_> = history.recent(5).unwrap().into_iter().map(|summary| summary.id).collect();
+        assert_eq!(recent, vec![1, 0]);
+        assert_eq!(history.recent(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_deferred_match_is_seen_before_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matches.jsonl");
+        let history = JsonLinesMatchHistory::open(&path).unwrap();
+
+        history.add_deferred(finished_match(0));
+        history.add_deferred(finished_match(1));
+        assert_eq!(history.next_id(), 2);
+        assert_eq!(history.recent(5).unwrap().len(), 2);
+        assert!(!path.exists());
+
+        history.persist().unwrap();
+        history.persist().unwrap();
+        let reopened_history = JsonLinesMatchHistory::open(&path).unwrap();
+        assert_eq!(reopened_history.recent(5).unwrap().len(), 2);
+    }
+}
@@ -1,9 +1,21 @@
+pub mod auth;
 pub mod client_session;
 pub mod routes;
 pub mod chat;
+pub mod scheduler;
+pub mod gameplay;
+pub mod config;
+pub mod config_watcher;
+pub mod events;
+pub mod game_mode;
+pub mod leaderboard;
+pub mod match_history;
+pub mod profiles;
+pub mod saved_world;
 
 use std::{
     collections::HashMap, 
+    path::PathBuf, 
     sync::{
         Arc, 
         Mutex
@@ -15,35 +27,85 @@ use chat::ChatMessage;
 
 use client_session::{
     ClientSession, 
+    ClientSessionData, 
     ClientSessionDisconnectEvent, 
     ClientSessionId, 
     ClientSessionState
 };
 
-use rand::seq::{
-    IndexedRandom, 
-    IteratorRandom
+use config::{
+    ConfigOverrides, 
+    ServerConfig, 
+    ServerConfigError
 };
 
+use config_watcher::ConfigFileWatcher;
+
+use events::{
+    GameEvent, 
+    GameEventJournal, 
+    RoundPlayer
+};
+
+use game_mode::{
+    GameMode, 
+    LobbyPlayer, 
+    PlayerAction, 
+    PlayerActionResult, 
+    RoundScore
+};
+
+use gameplay::{
+    GameplayCommand, 
+    GameplayCommandError, 
+    GameplaySnapshot, 
+    GameplaySnapshotReceiver, 
+    GameplaySnapshotSender, 
+    GAMEPLAY_COMMANDS_CAPACITY
+};
+
+use match_history::{
+    InMemoryMatchHistory, 
+    JsonLinesMatchHistory, 
+    MatchHistoryError, 
+    MatchHistoryStore, 
+    MatchRecorder
+};
+
+use profiles::{
+    InMemoryProfileStore, 
+    JsonFileProfileStore, 
+    ProfileStore, 
+    ProfileStoreError
+};
+
+use saved_world::SavedWorld;
+
+use scheduler::TickScheduler;
+
 use serde::{
     Deserialize, 
     Serialize
 };
 
 use crate::{
-    app::{
-        SEEKING_MAX_TIME, 
-        SEEKING_MAX_TRIES
+    app::replay::{
+        ReplayError, 
+        ReplayHeader, 
+        ReplayWriter
     }, 
     game::{
+        map_file::MapFile, 
         math::Vector2F, 
         world::{
-            get_tiled_value, 
+            self, 
+            EntityId, 
+            PlayerRole, 
             World, 
-            WorldError, 
-            ENTITY_SIZE
+            WorldError
         }
-    }
+    }, 
+    requests::MoveDirection
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -56,12 +118,22 @@ pub enum MultiplayerServerError {
 
     #[error("Could not join task, reason='{0}'")]
     TaskJoinError(#[from] tokio::task::JoinError),
+
+    #[error("Invalid config, reason='{0}'")]
+    ConfigError(#[from] ServerConfigError),
+
+    #[error("Could not open profiles, reason='{0}'")]
+    ProfileStoreError(#[from] ProfileStoreError),
+
+    #[error("Could not open match history, reason='{0}'")]
+    MatchHistoryError(#[from] MatchHistoryError),
 }
 
 pub struct MultiplayerServerHandler {
     connection_task_handler: tokio::task::JoinHandle<()>,
     pub server_context: Arc<MultiplayerServerContext>,
     main_task_handler: tokio::task::JoinHandle<()>,
+    config_watcher_task_handler: Option<tokio::task::JoinHandle<()>>,
     shutdown_sender: tokio::sync::oneshot::Sender<()>,
     notify_no_connection: Arc<tokio::sync::Notify>,
     notify_any_connection: Arc<tokio::sync::Notify>,
@@ -72,6 +144,9 @@ pub enum StartGameError {
     #[error("NoFreeTiles")]
     NoFreeTiles,
 
+    #[error("NotEnoughPlayers")]
+    NotEnoughPlayers,
+
     #[error("WorldErrorHappen, reason='{0}'")]
     WorldErrorHappen(#[from] WorldError)
 }
@@ -89,7 +164,12 @@ pub enum GameplayState {
         last_result: Option<GameplayResult>,
     },
     GameRunning {
-        world: World
+        /// Boxed, world with its tile layer is much bigger than other states
+        world: Box<World>,
+        game_mode: Box<dyn GameMode>,
+        match_recorder: MatchRecorder,
+        /// Recording of round, if enabled in config
+        replay: Option<Box<ReplayWriter>>,
     },
     Ending {
         countdown: u32,
@@ -97,7 +177,7 @@ pub enum GameplayState {
     },
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum GameplayResult {
     SeekerWin {
         reward: u32,
@@ -109,16 +189,29 @@ pub enum GameplayResult {
 pub struct MultiplayerServerContext {
     pub client_sessions_handlers: Mutex<HashMap<ClientSessionId, client_session::ClientSessionHandler>>,
     pub chat: Mutex<Vec<ChatMessage>>,
-    pub gameplay_state: Mutex<GameplayState>,
+    pub gameplay_commands: tokio::sync::mpsc::Sender<GameplayCommand>,
+    pub gameplay_snapshot: GameplaySnapshotReceiver,
+    pub profiles: Arc<dyn ProfileStore>,
+    pub match_history: Arc<dyn MatchHistoryStore>,
+    pub events: GameEventJournal,
+    /// Seed set by admin, used by next round instead of config one
+    pub next_round_seed: Mutex<Option<u64>>,
+    /// Hand-authored map used by every round instead of generated one
+    pub map: Option<Arc<MapFile>>,
+    config: Mutex<Arc<ServerConfig>>,
+    staged_config: Mutex<Option<ServerConfig>>,
 }
 
 pub struct MultiplayerServer {
     listener: tokio::net::TcpListener,
+    config: ServerConfig,
+    watched_config: Option<(PathBuf, ConfigOverrides)>,
+    profiles: Option<Arc<dyn ProfileStore>>,
+    match_history: Option<Arc<dyn MatchHistoryStore>>,
+    map: Option<Arc<MapFile>>,
 }
 
 impl MultiplayerServer {
-    const MAIN_LOOP_INTERVAL: Duration = Duration::from_millis(32);
-
     pub async fn bind_any_local() -> Result<Self, MultiplayerServerError> {
         Self::bind("127.0.0.1:0").await
     }
@@ -126,23 +219,83 @@ impl MultiplayerServer {
     pub async fn bind<A: tokio::net::ToSocketAddrs>(addr: A) -> Result<Self, MultiplayerServerError> {
         Ok(Self {
             listener: tokio::net::TcpListener::bind(addr).await?,
+            config: ServerConfig::default(),
+            watched_config: None,
+            profiles: None,
+            match_history: None,
+            map: None,
         })
     }
 
+    pub fn with_config(mut self, config: ServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Reload config whenever file changes, new values are applied between rounds.
+    /// `overrides` are applied to every reloaded config.
+    pub fn with_config_watch<P: Into<PathBuf>>(mut self, path: P, overrides: ConfigOverrides) -> Self {
+        self.watched_config = Some((path.into(), overrides));
+        self
+    }
+
+    /// Use given store instead of one selected by config
+    pub fn with_profile_store(mut self, profiles: Arc<dyn ProfileStore>) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Use given match history instead of one selected by config
+    pub fn with_match_history(mut self, match_history: Arc<dyn MatchHistoryStore>) -> Self {
+        self.match_history = Some(match_history);
+        self
+    }
+
+    /// Play every round on given map instead of generated one
+    pub fn with_map(mut self, map: MapFile) -> Self {
+        self.map = Some(Arc::new(map));
+        self
+    }
+
     pub fn get_local_address(&self) -> Result<std::net::SocketAddr, std::io::Error> {
         self.listener.local_addr()
     }
 
     pub async fn run(self) -> Result<MultiplayerServerHandler, MultiplayerServerError> {
+        self.config.validate()?;
+        log::info!("Server config: {:?}", self.config);
+
+        let profiles: Arc<dyn ProfileStore> = match (&self.profiles, &self.config.storage.profiles_path) {
+            (Some(profiles), _) => profiles.clone(),
+            (None, Some(profiles_path)) => Arc::new(JsonFileProfileStore::open(profiles_path)?),
+            (None, None) => Arc::new(InMemoryProfileStore::default()),
+        };
+
+        let match_history: Arc<dyn MatchHistoryStore> = match (&self.match_history, &self.config.storage.match_history_path) {
+            (Some(match_history), _) => match_history.clone(),
+            (None, Some(match_history_path)) => Arc::new(JsonLinesMatchHistory::open(match_history_path)?),
+            (None, None) => Arc::new(InMemoryMatchHistory::default()),
+        };
+
         let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
         let (shutdown_server_sender, shutdown_server_receiver) = tokio::sync::oneshot::channel();
 
         let (client_disconnect_tx, client_disconnect_rx) = tokio::sync::mpsc::channel::<ClientSessionDisconnectEvent>(32);
+        let (gameplay_commands_tx, gameplay_commands_rx) = tokio::sync::mpsc::channel(GAMEPLAY_COMMANDS_CAPACITY);
+        let (gameplay_snapshot_tx, gameplay_snapshot_rx) = tokio::sync::watch::channel(Arc::new(GameplaySnapshot::default()));
         
         let server_context = Arc::new(MultiplayerServerContext {
             client_sessions_handlers: Mutex::new(HashMap::new()),
             chat: Mutex::new(Vec::default()),
-            gameplay_state: Mutex::new(GameplayState::default())
+            gameplay_commands: gameplay_commands_tx,
+            gameplay_snapshot: gameplay_snapshot_rx,
+            profiles,
+            match_history,
+            events: GameEventJournal::new(self.config.storage.journal_dir.clone())?,
+            next_round_seed: Mutex::new(None),
+            map: self.map.clone(),
+            config: Mutex::new(Arc::new(self.config.clone())),
+            staged_config: Mutex::new(None),
         });
         let server_context_shared = server_context.clone();
         server_context_shared.chat.lock().unwrap().push(ChatMessage::new_from_server("Message of the day 'Pizza!'".to_string()));
@@ -153,6 +306,11 @@ impl MultiplayerServer {
         let notify_any_connection = Arc::new(tokio::sync::Notify::new());
         let notify_any_connection_shared = notify_any_connection.clone();
 
+        let config_watcher_task_handler = self.watched_config.clone().map(|(path, overrides)| {
+            log::info!("Watching config file {path:?}");
+            tokio::spawn(ConfigFileWatcher::new(path, overrides).run(server_context.clone()))
+        });
+
         let connection_task_handler = tokio::spawn(async move {
             self.connection_procedure(
                 shutdown_server_receiver,
@@ -169,7 +327,9 @@ impl MultiplayerServer {
             Self::main_task_procedure(
                 shutdown_receiver, 
                 shutdown_server_sender, 
-                server_context_shared_main_loop
+                server_context_shared_main_loop,
+                gameplay_commands_rx,
+                gameplay_snapshot_tx
             ).await;
         });
 
@@ -177,6 +337,7 @@ impl MultiplayerServer {
             connection_task_handler,
             server_context,
             main_task_handler,
+            config_watcher_task_handler,
             shutdown_sender,
             notify_no_connection,
             notify_any_connection
@@ -213,6 +374,11 @@ impl MultiplayerServer {
                             (removed_client, no_more_clients)
                         };  
 
+                        if let Some(client_session_handler) = &client_session_handler {
+                            let name = client_session_handler.data.lock().unwrap().get_name().map(str::to_string);
+                            server_context_shared.emit_event(GameEvent::PlayerLeft { client_id: client_session_id.id, name });
+                        }
+
                         if no_more_clients {
                             notify_no_connection_shared.notify_one();
                         }
@@ -263,11 +429,20 @@ impl MultiplayerServer {
         }
     }
 
+    /// Main task is the only owner of gameplay state. Other tasks mutate it
+    /// with `GameplayCommand`s and read published `GameplaySnapshot`s.
     async fn main_task_procedure(
         mut shutdown_receiver: tokio::sync::oneshot::Receiver<()>, 
         shutdown_server_sender: tokio::sync::oneshot::Sender<()>, 
-        server_context_shared_main_loop: Arc<MultiplayerServerContext>
+        server_context_shared_main_loop: Arc<MultiplayerServerContext>,
+        mut gameplay_commands_rx: tokio::sync::mpsc::Receiver<GameplayCommand>,
+        gameplay_snapshot_tx: GameplaySnapshotSender
     ) {
+        let mut gameplay_state = GameplayState::default();
+        let mut completed_ticks = 0;
+        let mut scheduler = TickScheduler::new(server_context_shared_main_loop.config().tick_rate());
+        log::info!("Main loop runs at {} ticks/sec", scheduler.tick_rate().ticks_per_second());
+
         loop {
             tokio::select! {
                 _ = &mut shutdown_receiver => {
@@ -280,24 +455,40 @@ impl MultiplayerServer {
 
                     break;
                 },
-                _ = tokio::time::sleep(Self::MAIN_LOOP_INTERVAL) => {
-                    // TODO add timing to have steady ticks/sec average
-                    Self::main_loop_procedure(server_context_shared_main_loop.clone());
+                Some(command) = gameplay_commands_rx.recv() => {
+                    Self::handle_gameplay_command(&mut gameplay_state, command, &server_context_shared_main_loop);
+                    gameplay_snapshot_tx.send_replace(Arc::new(GameplaySnapshot::from_state(completed_ticks, &gameplay_state)));
+                },
+                tick = scheduler.next_tick() => {
+                    let was_ending = matches!(gameplay_state, GameplayState::Ending { countdown: _, result: _ });
+                    Self::main_loop_procedure(&mut gameplay_state, server_context_shared_main_loop.clone());
+
+                    // Config changes are applied only between rounds
+                    let round_finished = was_ending && matches!(gameplay_state, GameplayState::Lobby { counting_to_start: _, last_result: _ });
+                    if round_finished {
+                        if let Some(config) = server_context_shared_main_loop.apply_staged_config() {
+                            if config.tick_rate() != scheduler.tick_rate() {
+                                scheduler.set_tick_rate(config.tick_rate());
+                                log::info!("Main loop runs at {} ticks/sec", scheduler.tick_rate().ticks_per_second());
+                            }
+                        }
+                    }
+
+                    completed_ticks = tick + 1;
+                    gameplay_snapshot_tx.send_replace(Arc::new(GameplaySnapshot::from_state(completed_ticks, &gameplay_state)));
                 },
             }
         }
     }
 
-    fn main_loop_procedure(server_context: Arc<MultiplayerServerContext>) {
-        const CLIENTS_REQUIRED_TO_START: usize = 2;
-        const TICKS_TO_COUNTDOWN: u32 = 30;
-        //TODO constnt tick time no matter FPS
+    fn main_loop_procedure(gameplay_state: &mut GameplayState, server_context: Arc<MultiplayerServerContext>) {
+        let config = server_context.config();
+        let config = config.as_ref();
+        let tick_rate = config.tick_rate();
 
-        let mut gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-
-        if let GameplayState::Lobby { counting_to_start, last_result:_ } = &mut *gameplay_state_guard {
+        if let GameplayState::Lobby { counting_to_start, last_result:_ } = gameplay_state {
             let all_ready = server_context.are_all_clients_ready();
-            let enough_clients = server_context.get_connections_count() >= CLIENTS_REQUIRED_TO_START;
+            let enough_clients = server_context.get_connections_count() >= config.lobby.clients_required_to_start;
 
             // Counting transitions
             match counting_to_start {
@@ -311,7 +502,9 @@ impl MultiplayerServer {
                 }
                 None if all_ready && enough_clients => {
                     // Should start counting
-                    *counting_to_start = Some(TICKS_TO_COUNTDOWN);
+                    let ticks = tick_rate.ticks_in(config.lobby.countdown());
+                    *counting_to_start = Some(ticks);
+                    server_context.emit_event(GameEvent::CountdownStarted { ticks });
                 },
                 _ => {}
             }
@@ -319,12 +512,31 @@ impl MultiplayerServer {
             if let Some(count) = counting_to_start {
                 // Countdown exhausted
                 if *count == 0 {
-                    gameplay_state_guard.try_transition_from_lobby_to_gamerunning().unwrap();
-                    if let GameplayState::GameRunning { world } = &mut *gameplay_state_guard {       
-                        let start_game_reuslt = Self::start_new_game(world, &server_context.client_sessions_handlers);
-
-                        if start_game_reuslt.is_err() {
-                            gameplay_state_guard.unexpected_transition_to_lobby();
+                    let seed = server_context.next_round_seed.lock().unwrap().take()
+                        .or(config.world.seed)
+                        .unwrap_or_else(rand::random);
+                    let world = World::with_seed(config.world_settings(), seed);
+
+                    let match_recorder = MatchRecorder::new(server_context.match_history.next_id(), config.lobby.game_mode, seed);
+                    log::info!("Starting match {}, seed={seed}", match_recorder.id());
+                    gameplay_state.try_transition_from_lobby_to_gamerunning(world, config.lobby.game_mode.create(), match_recorder).unwrap();
+                    if let GameplayState::GameRunning { world, game_mode, match_recorder, replay } = gameplay_state {       
+                        let start_game_reuslt = Self::start_new_game(world, game_mode.as_ref(), match_recorder, &server_context.client_sessions_handlers, config, server_context.map.as_deref());
+
+                        match start_game_reuslt {
+                            Ok(players) => {
+                                server_context.emit_event(GameEvent::RoundStarted { 
+                                    match_id: match_recorder.id(), 
+                                    game_mode: game_mode.kind(), 
+                                    seed: world.seed(),
+                                    players 
+                                });
+                                *replay = Self::start_replay(world, match_recorder.id(), config);
+                            },
+                            Err(e) => {
+                                log::error!("Could not start game, reason={e}");
+                                gameplay_state.unexpected_transition_to_lobby();
+                            },
                         }
                     }
                     return;
@@ -332,169 +544,282 @@ impl MultiplayerServer {
             }
         }
         
-        if let GameplayState::GameRunning { world } = &mut *gameplay_state_guard {
-            world.tick_seeker_remaining_time();
+        if let GameplayState::GameRunning { world, game_mode, match_recorder, replay } = gameplay_state {
+            game_mode.tick(world);
+            match_recorder.tick();
 
-            // TODO some game end check
-            let result = Self::check_gameplay_result(world);
+            let result = game_mode.check_result(world, config);
 
             if let Some(result) = result {
-                // Has result, detach entities from clients, transition to ending countdownstage 
+                // Has result, credit points, detach entities from clients, transition to ending countdownstage 
+                let scores = game_mode.score_round(world, &result, config);
+                server_context.credit_round_scores(scores, tick_rate);
+
+                // Archived at once, history file is appended in background
+                server_context.match_history.add_deferred(match_recorder.finish(world, result, tick_rate));
+                let match_history = server_context.match_history.clone();
+                tokio::task::spawn_blocking(move || {
+                    if let Err(e) = match_history.persist() {
+                        log::error!("Could not archive match, reason={e}");
+                    }
+                });
+                server_context.emit_event(GameEvent::RoundEnded { match_id: match_recorder.id(), result });
+
+                // Final frame shows outcome of last actions
+                if let Some(mut replay) = replay.take() {
+                    if let Err(e) = replay.record_frame(world).and_then(|_| replay.finish()) {
+                        log::error!("Could not finish replay, reason={e}");
+                    }
+                }
 
                 // All not ready, EntityIds to None
                 server_context.detach_entities_from_clients();
 
-                gameplay_state_guard.try_transition_from_gamerunning_to_ending(result).unwrap();
+                gameplay_state.try_transition_from_gamerunning_to_ending(result, tick_rate.ticks_in(config.ending.countdown())).unwrap();
                 return;
             } else {
                 // No result yet
-                world.tick();
+                let movements = world.tick(tick_rate.tick_duration());
+                // NPCs wander all the time, only players are journaled
+                movements.into_iter()
+                    .filter(|movement| world.get_entity_by_id(movement.entity_id).is_some_and(|e| e.get_player_role().is_some()))
+                    .for_each(|movement| server_context.emit_event(GameEvent::Moved { 
+                        entity_id: movement.entity_id, 
+                        from: movement.from, 
+                        to: movement.to 
+                    }));
+
+                if let Some(replay_writer) = replay {
+                    if let Err(e) = replay_writer.record_frame(world) {
+                        log::error!("Could not record replay frame, recording stopped, reason={e}");
+                        *replay = None;
+                    }
+                }
             }
         }
 
-        if let GameplayState::Ending { countdown, result: _ }= &mut *gameplay_state_guard {
+        if let GameplayState::Ending { countdown, result: _ } = gameplay_state {
             *countdown = countdown.saturating_sub(1);
             
             if *countdown == 0 || server_context.get_connections_count() == 0 {
-                gameplay_state_guard.try_transition_from_ending_to_lobby().unwrap();
+                gameplay_state.try_transition_from_ending_to_lobby().unwrap();
             }
         }
 
     }
 
-    fn check_gameplay_result(world: &World) -> Option<GameplayResult> {
-        const SMALL_HIDERS_REWARD: u32 = 5; // Seeker has gone
-        // const MEDIUM_HIDERS_REWARD: u32 = 10;
-        // const BIGL_HIDERS_REWARD: u32 = 20; // All surviwed
-        
-        const SMALL_SEEKER_REWARD: u32 = 5;
-
-        // let clients_guard = clients.lock().unwrap();
-        let summary = world.get_seeker_hiders_summary();
-        // TODO use summary to togglegameplay state
+    /// Create replay file of just started round and record initial world
+    fn start_replay(world: &World, match_id: match_history::MatchId, config: &ServerConfig) -> Option<Box<ReplayWriter>> {
+        let replay_dir = config.storage.replay_dir.as_ref()?;
+
+        let replay_writer = std::fs::create_dir_all(replay_dir)
+            .map_err(ReplayError::from)
+            .and_then(|_| ReplayWriter::create(
+                replay_dir.join(format!("replay_{match_id}.jsonl")), 
+                &ReplayHeader { 
+                    match_id, 
+                    game_mode: config.lobby.game_mode, 
+                    seed: world.seed(), 
+                    ticks_per_second: config.ticks_per_second, 
+                    started_at: std::time::SystemTime::now(),
+                    obstacles: world.obstacles().to_vec(),
+                    bounds: world.bounds()
+                }
+            ))
+            .and_then(|mut replay_writer| replay_writer.record_frame(world).map(|_| Box::new(replay_writer)));
 
-        let seeker_win = if let Some((_, seeker_stats)) = summary.seeker {
-            if seeker_stats.remaining_failures == 0 || seeker_stats.remaining_ticks == 0 {
-                Some(GameplayResult::HidersWin { reward: SMALL_HIDERS_REWARD })
-            } else {
-                None
-            }
-        } else {
-            // Seeker has gone, hiders win
-            Some(GameplayResult::HidersWin { reward: SMALL_HIDERS_REWARD })
-        };
+        replay_writer
+            .inspect_err(|e| log::error!("Could not start replay recording, reason={e}"))
+            .ok()
+    }
 
-        if seeker_win.is_some() {
-            return seeker_win;
+    fn handle_gameplay_command(gameplay_state: &mut GameplayState, command: GameplayCommand, server_context: &MultiplayerServerContext) {
+        match command {
+            GameplayCommand::Move { entity_id, dir, reply } => {
+                let result = match gameplay_state {
+                    GameplayState::GameRunning { world, game_mode: _, match_recorder: _, replay: _ } => {
+                        // Journaled on arrival, like all movements
+                        Self::try_move_entity(world, entity_id, dir).map(|movement| movement.is_some())
+                    },
+                    _ => Err(GameplayCommandError::BadState),
+                };
+                let _ = reply.send(result);
+            },
+            GameplayCommand::MoveTo { entity_id, target, reply } => {
+                let result = match gameplay_state {
+                    GameplayState::GameRunning { world, game_mode: _, match_recorder: _, replay: _ } => {
+                        world.set_entity_path_to(entity_id, target).map_err(|e| match e {
+                            WorldError::OutOfBounds(destination) => GameplayCommandError::MoveOutOfBounds { destination },
+                            WorldError::NoPathFound => GameplayCommandError::NoPathFound,
+                            WorldError::EntityNotExist => GameplayCommandError::EntityNotFound { id: entity_id },
+                            _ => GameplayCommandError::ActionNotSupported,
+                        })
+                    },
+                    _ => Err(GameplayCommandError::BadState),
+                };
+                let _ = reply.send(result);
+            },
+            GameplayCommand::SetVelocity { entity_id, direction, reply } => {
+                let result = match gameplay_state {
+                    GameplayState::GameRunning { world, game_mode: _, match_recorder: _, replay: _ } => {
+                        world.set_entity_velocity(entity_id, direction).map_err(|e| match e {
+                            WorldError::EntityNotExist => GameplayCommandError::EntityNotFound { id: entity_id },
+                            _ => GameplayCommandError::ActionNotSupported,
+                        })
+                    },
+                    _ => Err(GameplayCommandError::BadState),
+                };
+                let _ = reply.send(result);
+            },
+            GameplayCommand::PlayerAction { entity_id, action, reply } => {
+                let result = match gameplay_state {
+                    GameplayState::GameRunning { world, game_mode, match_recorder, replay: _ } => {
+                        let result = game_mode.player_action(world, entity_id, action.clone());
+                        if let (PlayerAction::Uncover { target_entity_id }, Ok(PlayerActionResult::Uncover(uncover_result))) = (&action, &result) {
+                            match_recorder.record_uncover(entity_id, *target_entity_id, uncover_result.was_hider);
+                            server_context.emit_event(GameEvent::UncoverAttempted { 
+                                seeker_entity_id: entity_id, 
+                                target_entity_id: *target_entity_id, 
+                                was_hider: uncover_result.was_hider 
+                            });
+
+                            // Uncovering NPC costs seeker one try
+                            let seeker_role = world.get_entity_by_id(entity_id).and_then(|entity| entity.get_player_role());
+                            if let (Some(false), Some(PlayerRole::Seeker { stats })) = (uncover_result.was_hider, seeker_role) {
+                                server_context.emit_event(GameEvent::SeekerPunished { entity_id, remaining_failures: stats.remaining_failures });
+                            }
+                        }
+                        result
+                    },
+                    _ => Err(GameplayCommandError::BadState),
+                };
+                let _ = reply.send(result);
+            },
+            GameplayCommand::SaveWorld { path, reply } => {
+                let result = match gameplay_state {
+                    GameplayState::GameRunning { world, game_mode, match_recorder: _, replay: _ } => {
+                        log::info!("Saving world to {path:?}, tick={}", world.ticks());
+                        SavedWorld::save(&path, game_mode.kind(), world).map_err(GameplayCommandError::from)
+                    },
+                    _ => Err(GameplayCommandError::BadState),
+                };
+                let _ = reply.send(result);
+            },
+            GameplayCommand::RestoreWorld { path, reply } => {
+                let result = SavedWorld::load(&path)
+                    .map(|saved_world| Self::restore_world(gameplay_state, saved_world, server_context))
+                    .map_err(GameplayCommandError::from);
+                let _ = reply.send(result);
+            },
         }
-
-        summary.hiders.iter()
-            .all(|(_, h)| !h.covered)
-            .then_some(GameplayResult::SeekerWin { reward: SMALL_SEEKER_REWARD })
     }
 
-    fn start_new_game(
-        world: &mut World,
-        clients: &Mutex<HashMap<u32, client_session::ClientSessionHandler>>
-    ) -> Result<(), StartGameError> {
-        const MAPSIZE_GENERATION_FACTOR: usize = 5;
-
-        log::info!("Game just started!");
-        let mut rng = rand::rng();
-
-        let hiders_count = {
-            let clients_guard = clients.lock().unwrap();
-            clients_guard.len().saturating_sub(1)
-        };
-
-        let generation_range = get_tiled_value((hiders_count.min(1) * MAPSIZE_GENERATION_FACTOR) as i32);
-
-        // Generate world
-        Self::generate_world(world, &mut rng, generation_range)?;
-
-        // Assign entity to clients
-        Self::assign_world_entities_to_clients(world, clients, &mut rng, generation_range)?;
+    /// Abandon current state and continue saved round, abandoned round is not archived
+    fn restore_world(gameplay_state: &mut GameplayState, saved_world: SavedWorld, server_context: &MultiplayerServerContext) -> Vec<RoundPlayer> {
+        if let GameplayState::GameRunning { world: _, game_mode: _, match_recorder: _, replay } = gameplay_state {
+            if let Some(replay) = replay.take() {
+                if let Err(e) = replay.finish() {
+                    log::error!("Could not finish replay, reason={e}");
+                }
+            }
+        }
+        server_context.detach_entities_from_clients();
 
-        // Add NPCs
-        Self::place_npcs_around_world(world, &mut rng, generation_range, hiders_count)?;
+        let SavedWorld { game_mode, saved_at: _, world } = saved_world;
+        let mut match_recorder = MatchRecorder::new(server_context.match_history.next_id(), game_mode, world.seed());
+        let players = server_context.attach_clients_by_entity_names(&world);
+        for player in players.iter() {
+            match_recorder.add_player(player.entity_id, player.name.as_str());
+        }
 
-        Ok(())
-    }
+        log::info!("Restored match {}, tick={}, seed={}", match_recorder.id(), world.ticks(), world.seed());
+        server_context.emit_event(GameEvent::RoundStarted { 
+            match_id: match_recorder.id(), 
+            game_mode, 
+            seed: world.seed(), 
+            players: players.clone() 
+        });
 
-    fn generate_world(
-        _world: &mut World, 
-        _rng: &mut rand::prelude::ThreadRng,
-        _generation_range: f32
-    ) -> Result<(), StartGameError> {
-        // In future some obstacles, scenery
-        Ok(())
+        let replay = Self::start_replay(&world, match_recorder.id(), &server_context.config());
+        *gameplay_state = GameplayState::GameRunning { world: Box::new(world), game_mode: game_mode.create(), match_recorder, replay };
+        players
     }
 
-    fn place_npcs_around_world(
-        world: &mut World, 
-        rng: &mut rand::prelude::ThreadRng,
-        generation_range: f32,
-        hiders_count: usize
-    ) -> Result<(), StartGameError> {
-        const NPCS_PER_HIDER: usize = 9;
-        let free_tiles = world.get_free_tiles_positions(Vector2F::zero(), generation_range);
+    /// Start and end position of started movement, leaving world bounds is an error
+    fn try_move_entity(world: &mut World, entity_id: EntityId, dir: MoveDirection) -> Result<Option<(Vector2F, Vector2F)>, GameplayCommandError> {
+        let player_info = world
+            .get_entity_by_id(entity_id)
+            .map(|player| (player.position, player.is_moving()));
         
-        // Need at least 1 spot for NPCs
-        if free_tiles.is_empty() {
-            return Err(StartGameError::NoFreeTiles);
+        if let Some((player_pos, player_moving)) = player_info {
+            if player_moving {
+                // Can move only after not moving
+                Ok(None)
+            } else {
+                let next_player_pos = player_pos + dir.offset() * world::TILE_SIZE;
+                
+                match world.try_start_move_entity_to(entity_id, next_player_pos) {
+                    Ok(()) => Ok(Some((player_pos, next_player_pos))),
+                    Err(WorldError::OutOfBounds(destination)) => Err(GameplayCommandError::MoveOutOfBounds { destination }),
+                    Err(_) => Ok(None),
+                }
+            }
+        } else {
+            Ok(None)
         }
+    }
 
-        let nps_count = {
-            let expectednpc_count = NPCS_PER_HIDER * hiders_count;
-            free_tiles.len().min(expectednpc_count)
-        };
+    fn start_new_game(
+        world: &mut World,
+        game_mode: &dyn GameMode,
+        match_recorder: &mut MatchRecorder,
+        clients: &Mutex<HashMap<u32, client_session::ClientSessionHandler>>,
+        config: &ServerConfig,
+        map: Option<&MapFile>
+    ) -> Result<Vec<RoundPlayer>, StartGameError> {
+        log::info!("Game just started, mode={:?}!", game_mode.kind());
+        let mut rng = world.fork_rng();
 
-        let npcs_initial_positions = free_tiles.choose_multiple(rng, nps_count);
-        for &initial_position in npcs_initial_positions {
-            let _entity_id = world.create_entity_npc("NPC", initial_position, ENTITY_SIZE);
-        }
+        let mut clients_guard = clients.lock().unwrap();
 
-        Ok(())
-    }
+        let mut players: Vec<LobbyPlayer> = clients_guard.values()
+            .filter_map(|client| {
+                let client_data = client.data.lock().unwrap();
+                client_data.is_in_lobby()
+                    .then(|| client_data.get_name().map(|name| LobbyPlayer { client_id: client.id, name: name.to_string() }))
+                    .flatten()
+            })
+            .collect();
 
-    fn assign_world_entities_to_clients(
-        world: &mut World, clients: &Mutex<HashMap<u32, 
-        client_session::ClientSessionHandler>>, 
-        rng: &mut rand::prelude::ThreadRng,
-        generation_range: f32
-    ) -> Result<(), StartGameError> {
-        let mut clients_guard = clients.lock().unwrap();
+        // Sessions are kept in map, fixed order keeps seeded round reproducible
+        players.sort_by_key(|player| player.client_id);
 
-        let seeker_client_id = *clients_guard.keys().choose(rng).unwrap();
+        let assigned_entities = game_mode.setup(world, &players, &mut rng, config, map)?;
 
-        let free_tiles = world.get_free_tiles_positions(Vector2F::zero(), generation_range);
-        
-        // Need at least 1 spot for NPCs
-        if free_tiles.len() <= clients_guard.len() {
-            return Err(StartGameError::NoFreeTiles);
-        }
+        // Attach entities to clients
+        let mut round_players = vec![];
+        for (client_id, assigned_id) in assigned_entities {
+            let Some(client) = clients_guard.get_mut(&client_id) else {
+                continue;
+            };
+
+            if let Some(player) = players.iter().find(|player| player.client_id == client_id) {
+                match_recorder.add_player(assigned_id, player.name.as_str());
+
+                if let Some(role) = world.get_entity_by_id(assigned_id).and_then(|entity| entity.get_player_role()) {
+                    round_players.push(RoundPlayer { client_id, name: player.name.clone(), entity_id: assigned_id, role: *role });
+                }
+            }
 
-        // TODO spread hiders and seekers, some Voronoi can work
-        let mut initial_clients_positions = free_tiles.choose_multiple(rng, clients_guard.len());
-        
-        for (_, client) in clients_guard.iter_mut() {
             let mut client_data = client.data.lock().unwrap();
-            if let ClientSessionState::NameWasSet { name, ready_to_start, entity_player_id } = &mut client_data.state {
-                let intial_position = initial_clients_positions.next().expect("Client should have initial position selected");
-                let assigned_id = world.create_entity_player(&name, *intial_position, ENTITY_SIZE);
+            if let ClientSessionState::NameWasSet { name: _, ready_to_start, entity_player_id } = &mut client_data.state {
                 *ready_to_start = false;
                 *entity_player_id = Some(assigned_id);
-    
-                // Assign seeker role to one entity
-                if seeker_client_id == client.id {
-                    world.select_entity_as_seeker(assigned_id, SEEKING_MAX_TIME, SEEKING_MAX_TRIES)?;
-                }
-    
-                log::info!("Client '{name}' gets Entity assigned id={assigned_id}");
             }
         }
 
-        Ok(())
+        round_players.sort_by_key(|player| player.client_id);
+        Ok(round_players)
     }
 }
 
@@ -504,6 +829,11 @@ impl MultiplayerServerHandler {
         self.shutdown_sender.send(()).map_err(|_| MultiplayerServerError::ShutdownError)?;
         self.main_task_handler.await?;
         self.connection_task_handler.await?;
+        if let Some(config_watcher_task_handler) = self.config_watcher_task_handler {
+            config_watcher_task_handler.abort();
+        }
+        let server_context = self.server_context.clone();
+        tokio::task::spawn_blocking(move || server_context.events.flush()).await?;
         log::debug!("Server shut down successfully!");
         Ok(())
     }
@@ -516,6 +846,10 @@ impl MultiplayerServerHandler {
         self.notify_no_connection.notified().await
     }
 
+    pub fn current_tick(&self) -> u64 {
+        self.server_context.gameplay_snapshot().tick
+    }
+
     pub fn connections_count(&self) -> usize {
         let client_sessions_handlers_guard = self.server_context.client_sessions_handlers.lock().unwrap();
         client_sessions_handlers_guard.len()
@@ -523,11 +857,98 @@ impl MultiplayerServerHandler {
 }
 
 impl MultiplayerServerContext {
+    /// Latest published gameplay state, never waits for simulation
+    pub fn gameplay_snapshot(&self) -> Arc<GameplaySnapshot> {
+        self.gameplay_snapshot.borrow().clone()
+    }
+
+    /// Config currently used by gameplay
+    pub fn config(&self) -> Arc<ServerConfig> {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Config waiting to be applied after current round, replaces previously staged one
+    pub fn stage_config(&self, config: ServerConfig) {
+        *self.staged_config.lock().unwrap() = Some(config);
+    }
+
+    /// Replace config with staged one and notify clients on chat.
+    /// Returns new config if anything has changed.
+    pub fn apply_staged_config(&self) -> Option<Arc<ServerConfig>> {
+        let mut staged_config = self.staged_config.lock().unwrap().take()?;
+
+        let kept_fields = staged_config.keep_start_only_fields(&self.config());
+        if !kept_fields.is_empty() {
+            log::warn!("Ignored config changes of {kept_fields:?}, they are read only on server start");
+        }
+
+        let changes = self.config().changes(&staged_config);
+        if changes.is_empty() {
+            log::info!("Staged config has no changes");
+            return None;
+        }
+
+        let new_config = Arc::new(staged_config);
+        *self.config.lock().unwrap() = new_config.clone();
+        log::info!("Applied config changes: {changes:?}");
+        
+        self.chat.lock().unwrap().push(ChatMessage::new_from_server(
+            format!("Server config changed: {}", changes.join(", "))
+        ));
+
+        Some(new_config)
+    }
+
+    /// Publish event stamped with current tick
+    pub fn emit_event(&self, event: GameEvent) {
+        self.events.emit(self.gameplay_snapshot().tick, event);
+    }
+
+    /// Send command to main task and await its result
+    pub async fn request_gameplay<T>(
+        &self, 
+        build_command: impl FnOnce(gameplay::GameplayCommandReply<T>) -> GameplayCommand
+    ) -> Result<T, GameplayCommandError> {
+        let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+        self.gameplay_commands.send(build_command(reply_tx)).await
+            .map_err(|_| GameplayCommandError::GameplayTaskGone)?;
+        reply_rx.await
+            .map_err(|_| GameplayCommandError::GameplayTaskGone)?
+    }
+
     pub fn is_name_used(&self, name: &str) -> bool {
         let clients_guard = self.client_sessions_handlers.lock().unwrap();
         clients_guard.iter().any(|(_, v)| v.data.lock().unwrap().get_name() == Some(name))
     }
 
+    /// Authenticate just connected session unless other session uses the name.
+    /// Check and state change happen under sessions lock, so two sessions never get the same name.
+    pub fn try_authenticate_session(&self, session_data: &Mutex<ClientSessionData>, name: String, points: u32) -> bool {
+        let clients_guard = self.client_sessions_handlers.lock().unwrap();
+        if clients_guard.iter().any(|(_, v)| v.data.lock().unwrap().get_name() == Some(name.as_str())) {
+            return false;
+        }
+
+        let mut session_data_guard = session_data.lock().unwrap();
+        if session_data_guard.state != ClientSessionState::JustConnected {
+            return false;
+        }
+        session_data_guard.points = points;
+        session_data_guard.state = ClientSessionState::Authenticated { name };
+        true
+    }
+
+    /// Name belongs to account, guests cannot take it
+    pub fn is_name_registered(&self, name: &str) -> bool {
+        match self.profiles.load(name) {
+            Ok(profile) => profile.is_some_and(|profile| profile.is_registered()),
+            Err(e) => {
+                log::error!("Could not load profile of '{name}', reason={e}");
+                true
+            },
+        }
+    }
+
     pub fn get_connections_count(&self) -> usize {
         let clients_guard = self.client_sessions_handlers.lock().unwrap();
         clients_guard.len()
@@ -539,11 +960,85 @@ impl MultiplayerServerContext {
             let data_lock = client.data.lock().unwrap();
             match &data_lock.state {
                 client_session::ClientSessionState::JustConnected => false,
+                client_session::ClientSessionState::Authenticated { name: _ } => false,
                 client_session::ClientSessionState::NameWasSet { name: _, ready_to_start, entity_player_id: _ } => *ready_to_start,
             }
         })
     }
 
+    /// Registered account listed in config, guests cannot use registered names
+    pub fn is_admin(&self, name: &str) -> bool {
+        self.config().admin.accounts.iter().any(|account| account == name) && self.is_name_registered(name)
+    }
+
+    /// Add round points to clients controlling scored entities.
+    /// Profiles are updated at once, written to store in background as it may rewrite whole file.
+    pub fn credit_round_scores(&self, mut scores: HashMap<EntityId, RoundScore>, tick_rate: scheduler::TickRate) {
+        let mut profile_updates = vec![];
+        {
+            let clients_guard = self.client_sessions_handlers.lock().unwrap();
+            clients_guard.iter().for_each(|(_, client)| {
+                let mut client_data_guard = client.data.lock().unwrap();
+                let score = client_data_guard.get_entity_player_id()
+                    .and_then(|entity_id| scores.remove(&entity_id));
+
+                if let Some(score) = score {
+                    let points = score.total();
+                    client_data_guard.points += points;
+
+                    if let Some(name) = client_data_guard.get_name() {
+                        profile_updates.push((name.to_string(), score.clone()));
+                    }
+
+                    client_data_guard.last_round_score = Some(score);
+                    log::info!("Client {} gets {points} points, total={}", client.id, client_data_guard.points);
+                }
+            });
+        }
+
+        if profile_updates.is_empty() {
+            return;
+        }
+        for (name, score) in profile_updates {
+            match self.profiles.load_or_new(&name) {
+                Ok(mut profile) => {
+                    profile.record_round(&score, tick_rate);
+                    self.profiles.save_deferred(&profile);
+                },
+                Err(e) => log::error!("Could not update profile of '{name}', reason={e}"),
+            }
+        }
+
+        let profiles = self.profiles.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = profiles.persist() {
+                log::error!("Could not save profiles, reason={e}");
+            }
+        });
+    }
+
+    /// Clients with names of player entities control them, others stay detached
+    pub fn attach_clients_by_entity_names(&self, world: &World) -> Vec<RoundPlayer> {
+        let clients_guard = self.client_sessions_handlers.lock().unwrap();
+        let mut players: Vec<RoundPlayer> = clients_guard.values()
+            .filter_map(|client| {
+                let mut client_data = client.data.lock().unwrap();
+                let ClientSessionState::NameWasSet { name, ready_to_start, entity_player_id } = &mut client_data.state else {
+                    return None;
+                };
+
+                let entity = world.iter_entities().find(|entity| entity.name == *name)?;
+                let role = entity.get_player_role()?;
+                *ready_to_start = false;
+                *entity_player_id = Some(entity.id);
+                Some(RoundPlayer { client_id: client.id, name: name.clone(), entity_id: entity.id, role: *role })
+            })
+            .collect();
+
+        players.sort_by_key(|player| player.client_id);
+        players
+    }
+
     pub fn detach_entities_from_clients(&self) {
         let mut clients_guard = self.client_sessions_handlers.lock().unwrap();
         clients_guard.iter_mut().for_each(|(_, client)| {
@@ -564,24 +1059,27 @@ impl Default for GameplayState {
 }
 
 impl GameplayState {
-    pub fn try_transition_from_lobby_to_gamerunning(&mut self) -> Result<(), GameplayStateTransitionError> {
+    pub fn try_transition_from_lobby_to_gamerunning(
+        &mut self, 
+        world: World, 
+        game_mode: Box<dyn GameMode>, 
+        match_recorder: MatchRecorder
+    ) -> Result<(), GameplayStateTransitionError> {
         match self {
             GameplayState::Lobby { counting_to_start: _, last_result: _ } => {
-                *self = GameplayState::GameRunning { world: World::new() };
+                *self = GameplayState::GameRunning { world: Box::new(world), game_mode, match_recorder, replay: None };
                 Ok(())
             },
-            GameplayState::GameRunning { world: _ } => Err(GameplayStateTransitionError::AlreadyInState),
+            GameplayState::GameRunning { world: _, game_mode: _, match_recorder: _, replay: _ } => Err(GameplayStateTransitionError::AlreadyInState),
             GameplayState::Ending { countdown: _, result: _ } => Err(GameplayStateTransitionError::BadState),
         }
     }
     
-    pub fn try_transition_from_gamerunning_to_ending(&mut self, result: GameplayResult) -> Result<(), GameplayStateTransitionError> {
-        const ENDING_COUNTDOWN: u32 = 10;
-
+    pub fn try_transition_from_gamerunning_to_ending(&mut self, result: GameplayResult, countdown: u32) -> Result<(), GameplayStateTransitionError> {
         match self {
             GameplayState::Lobby { counting_to_start: _, last_result: _, } => Err(GameplayStateTransitionError::BadState),
-            GameplayState::GameRunning { world: _, } => {
-                *self = GameplayState::Ending { countdown: ENDING_COUNTDOWN, result };
+            GameplayState::GameRunning { world: _, game_mode: _, match_recorder: _, replay: _ } => {
+                *self = GameplayState::Ending { countdown, result };
                 Ok(())
             },
             GameplayState::Ending { countdown: _, result: _, } => Err(GameplayStateTransitionError::AlreadyInState),
@@ -592,7 +1090,7 @@ impl GameplayState {
     pub fn try_transition_from_ending_to_lobby(&mut self) -> Result<(), GameplayStateTransitionError> {
         match self {
             GameplayState::Lobby { counting_to_start: _, last_result: _ } => Err(GameplayStateTransitionError::AlreadyInState),
-            GameplayState::GameRunning { world: _ } => Err(GameplayStateTransitionError::BadState),
+            GameplayState::GameRunning { world: _, game_mode: _, match_recorder: _, replay: _ } => Err(GameplayStateTransitionError::BadState),
             GameplayState::Ending { countdown: _, result } => {
                 *self = GameplayState::Lobby { counting_to_start: None, last_result: Some(*result) };
                 Ok(())
@@ -609,6 +1107,7 @@ impl GameplayState {
 mod tests {
     use super::*;
     use crate::game::math::Vector2F;
+    use game_mode::GameModeKind;
 
     #[tokio::test]
     async fn test_server_creation() {
@@ -620,21 +1119,92 @@ mod tests {
         server_handler.shutdown().await.unwrap();
     }
     
+    #[test]
+    fn test_gameplay_snapshot_of_running_game() {
+        let mut gameplay_state = GameplayState::default();
+        let match_recorder = MatchRecorder::new(0, GameModeKind::default(), 0);
+        gameplay_state.try_transition_from_lobby_to_gamerunning(World::new(), GameModeKind::default().create(), match_recorder).unwrap();
+
+        if let GameplayState::GameRunning { world, game_mode: _, match_recorder: _, replay: _ } = &mut gameplay_state {
+            world.create_entity_npc("Tuna", Vector2F::new(10.5, 20.3), Vector2F::new(1.0, 1.0));
+        }
+
+        let snapshot = GameplaySnapshot::from_state(7, &gameplay_state);
+        assert_eq!(snapshot.tick, 7);
+        assert!(matches!(snapshot.state, crate::requests::GameplayStateBrief::GameRunning));
+        let world_snapshot = snapshot.world.unwrap();
+        assert_eq!(world_snapshot.entities.len(), 1);
+        assert!(world_snapshot.player_roles.is_empty());
+        assert_eq!(world_snapshot.movement_mode, world::MovementMode::Tiles);
+    }
+
+    #[test]
+    fn test_move_out_of_bounds_is_rejected() {
+        use crate::game::{math::Vector2I, tile_layer::TileBounds};
+
+        let mut world = World::new();
+        world.set_bounds(Some(TileBounds::new(Vector2I::new(0, 0), Vector2I::new(2, 1))));
+        let player = world.create_entity_player("Tuna", world::get_tiled_vec(0, 0), world::ENTITY_SIZE);
+
+        let result = MultiplayerServer::try_move_entity(&mut world, player, MoveDirection::Left);
+        assert!(matches!(result, Err(GameplayCommandError::MoveOutOfBounds { destination }) if destination == world::get_tiled_vec(-1, 0)));
+        let result = MultiplayerServer::try_move_entity(&mut world, player, MoveDirection::UpRight);
+        assert!(matches!(result, Err(GameplayCommandError::MoveOutOfBounds { destination }) if destination == world::get_tiled_vec(1, 1)));
+        let result = MultiplayerServer::try_move_entity(&mut world, player, MoveDirection::Right);
+        assert_eq!(result.unwrap(), Some((world::get_tiled_vec(0, 0), world::get_tiled_vec(1, 0))));
+    }
+
     #[tokio::test]
-    async fn test_server_adding_entities() {
+    async fn test_server_publishes_snapshots() {
         let server = MultiplayerServer::bind_any_local().await.unwrap();
         let server_handler = server.run().await.unwrap();
     
-        {
-            let mut gameplay_state_guard = server_handler.server_context.gameplay_state.lock().unwrap();
-            gameplay_state_guard.try_transition_from_lobby_to_gamerunning().unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let snapshot = server_handler.server_context.gameplay_snapshot();
+        assert!(snapshot.tick > 0);
+        assert!(snapshot.world.is_none());
+
+        let move_result = server_handler.server_context.request_gameplay(|reply| GameplayCommand::Move { 
+            entity_id: 0, 
+            dir: MoveDirection::Up, 
+            reply 
+        }).await;
+        assert!(matches!(move_result, Err(GameplayCommandError::BadState)));
+
+        server_handler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_staged_config_applied_with_chat_notice() {
+        let server = MultiplayerServer::bind_any_local().await.unwrap();
+        let server_handler = server.run().await.unwrap();
+        let server_context = &server_handler.server_context;
+
+        // Nothing staged or nothing changed
+        assert!(server_context.apply_staged_config().is_none());
+        server_context.stage_config(ServerConfig::default());
+        assert!(server_context.apply_staged_config().is_none());
+
+        // Start only fields are not reloaded
+        let mut new_config = ServerConfig::default();
+        new_config.storage.profiles_path = Some(PathBuf::from("profiles.json"));
+        server_context.stage_config(new_config);
+        assert!(server_context.apply_staged_config().is_none());
+        assert_eq!(server_context.config().storage.profiles_path, None);
+
+        let mut new_config = ServerConfig::default();
+        new_config.seeking.max_tries = 8;
+        server_context.stage_config(new_config.clone());
+        // Staged config is not active until applied
+        assert_eq!(server_context.config().seeking.max_tries, ServerConfig::default().seeking.max_tries);
+
+        let applied_config = server_context.apply_staged_config().unwrap();
+        assert_eq!(*applied_config, new_config);
+        assert_eq!(*server_context.config(), new_config);
+
+        let last_message = server_context.chat.lock().unwrap().last().unwrap().to_string();
+        assert!(last_message.ends_with("Server config changed: seeking.max_tries: 3 -> 8"), "{last_message}");
 
-            if let GameplayState::GameRunning { world } = &mut *gameplay_state_guard {
-                world.create_entity_npc("Tuna", Vector2F::new(10.5, 20.3), Vector2F::new(1.0, 1.0));
-            }
-        }
-    
-        tokio::time::sleep(Duration::from_millis(3000)).await;
         server_handler.shutdown().await.unwrap();
     }
 }
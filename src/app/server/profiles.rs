@@ -0,0 +1,364 @@
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf
+    },
+    sync::Mutex
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::game::world::PlayerRole;
+
+use super::{
+    game_mode::{
+        RoundScore,
+        ScoreReason
+    },
+    scheduler::TickRate
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileStoreError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("SerializationError, reason='{0}'")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+/// Progress of player kept between sessions, keyed by player name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub total_points: u32,
+    pub games_played: u32,
+    pub games_as_seeker: u32,
+    pub games_as_hider: u32,
+    pub wins_as_seeker: u32,
+    pub wins_as_hider: u32,
+    pub hiders_found: u32,
+    pub wrong_guesses: u32,
+    pub longest_survival_secs: u32,
+    /// Set for registered accounts, guest profiles have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+}
+
+impl PlayerProfile {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    pub fn record_round(&mut self, score: &RoundScore, tick_rate: TickRate) {
+        self.total_points += score.total();
+        self.games_played += 1;
+
+        let won = score.entries.iter().any(|entry| entry.reason == ScoreReason::Win);
+        match score.role {
+            PlayerRole::Seeker { stats } => {
+                self.games_as_seeker += 1;
+                self.wins_as_seeker += won as u32;
+                self.hiders_found += stats.hiders_found as u32;
+                self.wrong_guesses += stats.max_failures.saturating_sub(stats.remaining_failures) as u32;
+            },
+            PlayerRole::Hider { stats } => {
+                self.games_as_hider += 1;
+                self.wins_as_hider += won as u32;
+                let survival_secs = stats.survived_ticks / tick_rate.ticks_per_second();
+                self.longest_survival_secs = self.longest_survival_secs.max(survival_secs);
+            },
+        }
+    }
+}
+
+pub trait ProfileStore: Send + Sync {
+    fn load(&self, name: &str) -> Result<Option<PlayerProfile>, ProfileStoreError>;
+
+    fn save(&self, profile: &PlayerProfile) -> Result<(), ProfileStoreError>;
+
+    /// Change profile in memory only, `load` and `all` see it at once. Written by next `persist`.
+    fn save_deferred(&self, profile: &PlayerProfile);
+
+    /// Write profiles changed by `save_deferred`, may be slow
+    fn persist(&self) -> Result<(), ProfileStoreError>;
+
+    fn all(&self) -> Result<Vec<PlayerProfile>, ProfileStoreError>;
+
+    /// Turn guest or missing profile into account with `password_hash` in one step.
+    /// `None` if account of that name already exists.
+    fn create_if_unregistered(&self, name: &str, password_hash: String) -> Result<Option<PlayerProfile>, ProfileStoreError>;
+
+    /// Existing profile or fresh one, fresh profile is not saved
+    fn load_or_new(&self, name: &str) -> Result<PlayerProfile, ProfileStoreError> {
+        Ok(self.load(name)?.unwrap_or_else(|| PlayerProfile::new(name)))
+    }
+}
+
+/// Guest profile keeps its progress, `None` for already registered one
+fn registered_profile(existing: Option<&PlayerProfile>, name: &str, password_hash: String) -> Option<PlayerProfile> {
+    let mut profile = existing.cloned().unwrap_or_else(|| PlayerProfile::new(name));
+    if profile.is_registered() {
+        return None;
+    }
+    profile.password_hash = Some(password_hash);
+    Some(profile)
+}
+
+/// Profiles lost on restart, used when no profiles file is configured
+#[derive(Debug, Default)]
+pub struct InMemoryProfileStore {
+    profiles: Mutex<HashMap<String, PlayerProfile>>,
+}
+
+impl ProfileStore for InMemoryProfileStore {
+    fn load(&self, name: &str) -> Result<Option<PlayerProfile>, ProfileStoreError> {
+        Ok(self.profiles.lock().unwrap().get(name).cloned())
+    }
+
+    fn save(&self, profile: &PlayerProfile) -> Result<(), ProfileStoreError> {
+        self.profiles.lock().unwrap().insert(profile.name.clone(), profile.clone());
+        Ok(())
+    }
+
+    fn save_deferred(&self, profile: &PlayerProfile) {
+        self.profiles.lock().unwrap().insert(profile.name.clone(), profile.clone());
+    }
+
+    fn persist(&self) -> Result<(), ProfileStoreError> {
+        Ok(())
+    }
+
+    fn all(&self) -> Result<Vec<PlayerProfile>, ProfileStoreError> {
+        Ok(self.profiles.lock().unwrap().values().cloned().collect())
+    }
+
+    fn create_if_unregistered(&self, name: &str, password_hash: String) -> Result<Option<PlayerProfile>, ProfileStoreError> {
+        let mut profiles_guard = self.profiles.lock().unwrap();
+        let profile = registered_profile(profiles_guard.get(name), name, password_hash);
+        if let Some(profile) = &profile {
+            profiles_guard.insert(profile.name.clone(), profile.clone());
+        }
+        Ok(profile)
+    }
+}
+
+/// All profiles kept in single JSON file, rewritten on every save
+#[derive(Debug)]
+pub struct JsonFileProfileStore {
+    path: PathBuf,
+    profiles: Mutex<HashMap<String, PlayerProfile>>,
+    /// Held while file is written, always taken before `profiles`
+    file_lock: Mutex<()>,
+}
+
+impl JsonFileProfileStore {
+    /// Read profiles from file, missing file means no profiles yet
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ProfileStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let profiles = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str::<Vec<PlayerProfile>>(&content)?
+                .into_iter()
+                .map(|profile| (profile.name.clone(), profile))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        log::info!("Loaded {} player profiles from {path:?}", profiles.len());
+        Ok(Self {
+            path,
+            profiles: Mutex::new(profiles),
+            file_lock: Mutex::new(())
+        })
+    }
+
+    fn write_file(&self, profiles: &HashMap<String, PlayerProfile>) -> Result<(), ProfileStoreError> {
+        let mut sorted_profiles: Vec<_> = profiles.values().collect();
+        sorted_profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Replace file at once, crash during write must not corrupt profiles
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(&sorted_profiles)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl ProfileStore for JsonFileProfileStore {
+    fn load(&self, name: &str) -> Result<Option<PlayerProfile>, ProfileStoreError> {
+        Ok(self.profiles.lock().unwrap().get(name).cloned())
+    }
+
+    fn save(&self, profile: &PlayerProfile) -> Result<(), ProfileStoreError> {
+        let _file_guard = self.file_lock.lock().unwrap();
+        let mut profiles_guard = self.profiles.lock().unwrap();
+        profiles_guard.insert(profile.name.clone(), profile.clone());
+        self.write_file(&profiles_guard)
+    }
+
+    fn save_deferred(&self, profile: &PlayerProfile) {
+        self.profiles.lock().unwrap().insert(profile.name.clone(), profile.clone());
+    }
+
+    fn persist(&self) -> Result<(), ProfileStoreError> {
+        // Profiles stay unlocked during write, so deferred saves never wait for disk
+        let _file_guard = self.file_lock.lock().unwrap();
+        let profiles = self.profiles.lock().unwrap().clone();
+        self.write_file(&profiles)
+    }
+
+    fn all(&self) -> Result<Vec<PlayerProfile>, ProfileStoreError> {
+        Ok(self.profiles.lock().unwrap().values().cloned().collect())
+    }
+
+    fn create_if_unregistered(&self, name: &str, password_hash: String) -> Result<Option<PlayerProfile>, ProfileStoreError> {
+        let _file_guard = self.file_lock.lock().unwrap();
+        let mut profiles_guard = self.profiles.lock().unwrap();
+        let Some(profile) = registered_profile(profiles_guard.get(name), name, password_hash) else {
+            return Ok(None);
+        };
+
+        let previous = profiles_guard.insert(profile.name.clone(), profile.clone());
+        if let Err(e) = self.write_file(&profiles_guard) {
+            // Account exists only if it was written
+            match previous {
+                Some(previous) => profiles_guard.insert(previous.name.clone(), previous),
+                None => profiles_guard.remove(name),
+            };
+            return Err(e);
+        }
+        Ok(Some(profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::world::{
+        HiderStats,
+        SeekerStats
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_profile_records_round() {
+        let mut profile = PlayerProfile::new("Goose_1");
+
+        let tick_rate = TickRate::new(2);
+
+        let mut seeker_score = RoundScore::new(PlayerRole::Seeker { 
+            stats: SeekerStats { remaining_ticks: 0, max_ticks: 10, remaining_failures: 1, max_failures: 3, hiders_found: 1 } 
+        });
+        seeker_score.add(ScoreReason::Win, 5);
+        seeker_score.add(ScoreReason::HidersFound { count: 1 }, 2);
+        profile.record_round(&seeker_score, tick_rate);
+
+        let mut hider_score = RoundScore::new(PlayerRole::Hider { 
+            stats: HiderStats { covered: false, survived_ticks: 40 } 
+        });
+        hider_score.add(ScoreReason::Survived { secs: 20 }, 1);
+        profile.record_round(&hider_score, tick_rate);
+
+        assert_eq!(profile.total_points, 8);
+        assert_eq!(profile.games_played, 2);
+        assert_eq!(profile.games_as_seeker, 1);
+        assert_eq!(profile.games_as_hider, 1);
+        assert_eq!(profile.wins_as_seeker, 1);
+        assert_eq!(profile.wins_as_hider, 0);
+        assert_eq!(profile.hiders_found, 1);
+        assert_eq!(profile.wrong_guesses, 2);
+        assert_eq!(profile.longest_survival_secs, 20);
+    }
+
+    #[test]
+    fn test_json_store_persists_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+
+        {
+            let store = JsonFileProfileStore::open(&path).unwrap();
+            assert!(store.load("Pig_7").unwrap().is_none());
+
+            let mut profile = store.load_or_new("Pig_7").unwrap();
+            profile.total_points = 12;
+            store.save(&profile).unwrap();
+            store.save(&PlayerProfile::new("Cat_3")).unwrap();
+        }
+
+        // Reopen as after server restart
+        let store = JsonFileProfileStore::open(&path).unwrap();
+        assert_eq!(store.load("Pig_7").unwrap().unwrap().total_points, 12);
+        assert!(store.load("Cat_3").unwrap().is_some());
+        assert_eq!(store.all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_only_first_account_of_name_is_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut guest_profile = PlayerProfile::new("Pig_7");
+        guest_profile.total_points = 12;
+
+        let stores: [Box<dyn ProfileStore>; 2] = [
+            Box::new(InMemoryProfileStore::default()),
+            Box::new(JsonFileProfileStore::open(&path).unwrap()),
+        ];
+        for store in stores {
+            store.save(&guest_profile).unwrap();
+
+            let profile = store.create_if_unregistered("Pig_7", "first".to_string()).unwrap().unwrap();
+            assert_eq!(profile.total_points, 12);
+            assert!(store.create_if_unregistered("Pig_7", "second".to_string()).unwrap().is_none());
+            assert_eq!(store.load("Pig_7").unwrap().unwrap().password_hash.as_deref(), Some("first"));
+        }
+    }
+
+    #[test]
+    fn test_deferred_save_is_seen_before_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let store = JsonFileProfileStore::open(&path).unwrap();
+
+        store.save_deferred(&PlayerProfile { total_points: 5, ..PlayerProfile::new("Pig_7") });
+        assert_eq!(store.load("Pig_7").unwrap().unwrap().total_points, 5);
+        assert!(!path.exists());
+
+        store.persist().unwrap();
+        let reopened_store = JsonFileProfileStore::open(&path).unwrap();
+        assert_eq!(reopened_store.load("Pig_7").unwrap().unwrap().total_points, 5);
+    }
+
+    #[test]
+    fn test_json_store_reads_profiles_missing_new_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        std::fs::write(&path, r#"[{"name": "Pig_7", "total_points": 12, "games_played": 3}]"#).unwrap();
+
+        let store = JsonFileProfileStore::open(&path).unwrap();
+        let profile = store.load("Pig_7").unwrap().unwrap();
+        assert_eq!(profile.total_points, 12);
+        assert_eq!(profile.longest_survival_secs, 0);
+    }
+
+    #[test]
+    fn test_json_store_rejects_corrupted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        std::fs::write(&path, "not a json").unwrap();
+
+        assert!(matches!(JsonFileProfileStore::open(&path), Err(ProfileStoreError::SerializationError(_))));
+    }
+}
@@ -1,12 +1,12 @@
-use std::sync::{Arc, Mutex};
+use std::{path::PathBuf, sync::{Arc, Mutex}};
 
 use rand::{seq::IndexedRandom, Rng};
 
-use crate::{game::{math::Vector2F, world::{self, EntityId, PlayerRole, World}}, requests::{ClientRequest, ClientResponse, EntityCheckData, MoveDirection, SetNameError, UncoverResult}};
+use crate::{game::{math::Vector2F, world::EntityId}, requests::{AdminCommand, AdminError, AuthError, ClientRequest, ClientResponse, GameplayStateBrief, MoveDirection, SetNameError}};
 
-use super::{chat::ChatMessage, client_session::{ClientSessionData, ClientSessionId, ClientSessionState}, MultiplayerServerContext};
+use super::{auth, chat::ChatMessage, events::GameEvent, leaderboard::{self, LeaderboardSort, PlayerStats}, match_history::{MatchId, MAX_MATCH_HISTORY_SIZE}, saved_world, client_session::{ClientSessionData, ClientSessionId, ClientSessionState}, game_mode::{PlayerAction, PlayerActionResult}, gameplay::{GameplayCommand, GameplayCommandError}, MultiplayerServerContext};
 
-pub fn route_client_request(
+pub async fn route_client_request(
     server_context: Arc<MultiplayerServerContext>,
     client_session_id: ClientSessionId, 
     clieant_session_data: Arc<Mutex<ClientSessionData>>,
@@ -35,23 +35,41 @@ pub fn route_client_request(
                 ClientResponse::GetClientSessionData { data: sessiod_data_guard.clone() }
             },
             ClientRequest::SetName { new_name } => {
-                set_name_route(server_context, clieant_session_data, new_name)
+                set_name_route(server_context, client_session_id, clieant_session_data, new_name)
+            },
+            ClientRequest::Register { name, password } => {
+                register_route(server_context, clieant_session_data, name, password).await
+            },
+            ClientRequest::Login { name, password } => {
+                login_route(server_context, clieant_session_data, name, password).await
+            },
+            ClientRequest::JoinLobby => {
+                join_lobby_route(server_context, client_session_id, clieant_session_data)
             },
             ClientRequest::SetReady { ready: set_to_ready } => {
-                set_ready_route(set_to_ready, clieant_session_data)
+                set_ready_route(set_to_ready, client_session_id, clieant_session_data, server_context)
             },
             ClientRequest::GetEntityId => {
                 let sessiod_data_guard = clieant_session_data.lock().unwrap();
                 ClientResponse::GetEntityId { id: sessiod_data_guard.get_entity_player_id() }
             },
             ClientRequest::WorldCheck => {
-                world_check_route(server_context)
+                world_check_route(clieant_session_data, server_context)
+            },
+            ClientRequest::GetMap => {
+                get_map_route(server_context)
             },
             ClientRequest::ServerCheck => {
                 server_check_route(server_context)
             },
             ClientRequest::Move{dir} => {
-                move_route(dir, clieant_session_data, server_context)
+                move_route(dir, clieant_session_data, server_context).await
+            },
+            ClientRequest::MoveTo{target} => {
+                move_to_route(target, clieant_session_data, server_context).await
+            },
+            ClientRequest::SetVelocity{direction} => {
+                set_velocity_route(direction, clieant_session_data, server_context).await
             },
             ClientRequest::CheckGameplayState => {
                 gameplay_state_route(server_context)
@@ -63,8 +81,33 @@ pub fn route_client_request(
                 get_countdown_time_route(server_context)
             },
             ClientRequest::TryUncover { id } => {
-                try_uncover_route(server_context, clieant_session_data, id)
-            }
+                try_uncover_route(server_context, clieant_session_data, id).await
+            },
+            ClientRequest::GetRoundScore => {
+                let sessiod_data_guard = clieant_session_data.lock().unwrap();
+                ClientResponse::GetRoundScore { score: sessiod_data_guard.last_round_score.clone() }
+            },
+            ClientRequest::GetLeaderboard { top_n, sort_by } => {
+                get_leaderboard_route(server_context, top_n, sort_by)
+            },
+            ClientRequest::GetPlayerStats { name } => {
+                get_player_stats_route(server_context, name)
+            },
+            ClientRequest::GetMatchHistory { limit } => {
+                get_match_history_route(server_context, limit)
+            },
+            ClientRequest::GetMatch { id } => {
+                get_match_route(server_context, id)
+            },
+            ClientRequest::Admin { command } => {
+                admin_route(server_context, clieant_session_data, command).await
+            },
+            ClientRequest::GetServerTick => {
+                ClientResponse::GetServerTick { 
+                    tick: server_context.gameplay_snapshot().tick,
+                    ticks_per_second: server_context.config().ticks_per_second
+                }
+            },
         },
         Err(e) => ClientResponse::BadRequest { err: format!("request={request_str}, reason={e}") },
     };
@@ -90,7 +133,7 @@ fn try_generate_name(server_context: Arc<MultiplayerServerContext>) -> Option<St
         let name_num: i32 = rng.random_range(0..255);
         let new_name = format!("{core_name}_{name_num}");
     
-        if !server_context.is_name_used(&new_name) {
+        if !server_context.is_name_used(&new_name) && !server_context.is_name_registered(&new_name) {
             return Some(new_name);
         } 
         
@@ -104,18 +147,23 @@ fn try_generate_name(server_context: Arc<MultiplayerServerContext>) -> Option<St
 
 fn set_name_route(
     server_context: Arc<MultiplayerServerContext>,
+    client_session_id: ClientSessionId,
     clieant_session_data: Arc<Mutex<ClientSessionData>>,
     new_name: Option<String>
 ) -> ClientResponse {
+    if server_context.config().auth.enabled {
+        return ClientResponse::SetName { result: Err(SetNameError::GuestModeDisabled) };
+    }
+
     let new_name = if let Some(new_name) = new_name {
         if new_name.is_empty() {
             return ClientResponse::SetName { result: Err(SetNameError::NameEmpty) };
-        } else if server_context.is_name_used(&new_name) {
+        } else if server_context.is_name_used(&new_name) || server_context.is_name_registered(&new_name) {
             return ClientResponse::SetName { result: Err(SetNameError::NameAlreadyUsed) };
         } else {
             new_name
         }
-    } else if let Some(new_name) = try_generate_name(server_context) {
+    } else if let Some(new_name) = try_generate_name(server_context.clone()) {
         new_name
     } else {
         return ClientResponse::SetName { result: Err(SetNameError::NameGenerateExhausted) };
@@ -123,13 +171,230 @@ fn set_name_route(
 
     let mut sessiod_data_guard = clieant_session_data.lock().unwrap();
     if sessiod_data_guard.state == ClientSessionState::JustConnected {
-        sessiod_data_guard.state = ClientSessionState::NameWasSet { name: new_name, ready_to_start: false, entity_player_id: None };
+        // Continue progress of returning player
+        match server_context.profiles.load(&new_name) {
+            Ok(profile) => {
+                sessiod_data_guard.points = profile.map(|profile| profile.total_points).unwrap_or_default();
+            },
+            Err(e) => {
+                log::error!("Could not load profile of '{new_name}', reason={e}");
+            },
+        }
+
+        sessiod_data_guard.state = ClientSessionState::NameWasSet { name: new_name.clone(), ready_to_start: false, entity_player_id: None };
+        server_context.emit_event(GameEvent::PlayerJoined { client_id: client_session_id, name: new_name });
         ClientResponse::SetName { result: Ok(()) }
     } else {
         ClientResponse::BadState
     }
 }
 
+async fn register_route(
+    server_context: Arc<MultiplayerServerContext>,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    name: String,
+    password: String
+) -> ClientResponse {
+    if clieant_session_data.lock().unwrap().state != ClientSessionState::JustConnected {
+        return ClientResponse::BadState;
+    }
+
+    let min_password_length = server_context.config().auth.min_password_length;
+    if name.is_empty() {
+        return ClientResponse::Register { result: Err(AuthError::NameEmpty) };
+    } else if password.chars().count() < min_password_length {
+        return ClientResponse::Register { result: Err(AuthError::PasswordTooShort { min_length: min_password_length }) };
+    } else if server_context.is_name_used(&name) {
+        return ClientResponse::Register { result: Err(AuthError::NameAlreadyUsed) };
+    }
+
+    // Cheap check before hashing, account is claimed atomically afterwards
+    match server_context.profiles.load(&name) {
+        Ok(Some(profile)) if profile.is_registered() => {
+            return ClientResponse::Register { result: Err(AuthError::NameAlreadyUsed) };
+        },
+        Ok(_) => {},
+        Err(e) => {
+            log::error!("Could not load profile of '{name}', reason={e}");
+            return ClientResponse::Register { result: Err(AuthError::StorageFailed) };
+        },
+    }
+
+    // Hashing is expensive, keep it away from async workers
+    let password_hash = match tokio::task::spawn_blocking(move || auth::hash_password(&password)).await {
+        Ok(Ok(password_hash)) => password_hash,
+        Ok(Err(e)) => {
+            log::error!("Could not hash password of '{name}', reason={e}");
+            return ClientResponse::Register { result: Err(AuthError::StorageFailed) };
+        },
+        Err(e) => {
+            log::error!("Password hashing task failed, reason={e}");
+            return ClientResponse::Register { result: Err(AuthError::StorageFailed) };
+        },
+    };
+
+    // Guest profile of that name is claimed, keeping its progress
+    let profile = match server_context.profiles.create_if_unregistered(&name, password_hash) {
+        Ok(Some(profile)) => profile,
+        Ok(None) => {
+            return ClientResponse::Register { result: Err(AuthError::NameAlreadyUsed) };
+        },
+        Err(e) => {
+            log::error!("Could not save profile of '{name}', reason={e}");
+            return ClientResponse::Register { result: Err(AuthError::StorageFailed) };
+        },
+    };
+
+    log::info!("Registered account '{name}'");
+    if !server_context.try_authenticate_session(&clieant_session_data, name, profile.total_points) {
+        return ClientResponse::Register { result: Err(AuthError::NameAlreadyUsed) };
+    }
+    ClientResponse::Register { result: Ok(()) }
+}
+
+async fn login_route(
+    server_context: Arc<MultiplayerServerContext>,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    name: String,
+    password: String
+) -> ClientResponse {
+    if clieant_session_data.lock().unwrap().state != ClientSessionState::JustConnected {
+        return ClientResponse::BadState;
+    }
+
+    let profile = match server_context.profiles.load(&name) {
+        Ok(Some(profile)) => profile,
+        Ok(None) => {
+            return ClientResponse::Login { result: Err(AuthError::InvalidCredentials) };
+        },
+        Err(e) => {
+            log::error!("Could not load profile of '{name}', reason={e}");
+            return ClientResponse::Login { result: Err(AuthError::StorageFailed) };
+        },
+    };
+
+    let Some(password_hash) = profile.password_hash.clone() else {
+        // Guest profile, nobody can log in
+        return ClientResponse::Login { result: Err(AuthError::InvalidCredentials) };
+    };
+
+    let password_matches = tokio::task::spawn_blocking(move || auth::verify_password(&password, &password_hash)).await
+        .unwrap_or(false);
+    if !password_matches {
+        log::warn!("Failed login attempt to '{name}'");
+        return ClientResponse::Login { result: Err(AuthError::InvalidCredentials) };
+    }
+
+    if !server_context.try_authenticate_session(&clieant_session_data, name, profile.total_points) {
+        return ClientResponse::Login { result: Err(AuthError::AlreadyLoggedIn) };
+    }
+    ClientResponse::Login { result: Ok(()) }
+}
+
+fn join_lobby_route(
+    server_context: Arc<MultiplayerServerContext>,
+    client_session_id: ClientSessionId,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>
+) -> ClientResponse {
+    let mut sessiod_data_guard = clieant_session_data.lock().unwrap();
+    match &sessiod_data_guard.state {
+        ClientSessionState::Authenticated { name } => {
+            let name = name.clone();
+            sessiod_data_guard.state = ClientSessionState::NameWasSet { name: name.clone(), ready_to_start: false, entity_player_id: None };
+            server_context.emit_event(GameEvent::PlayerJoined { client_id: client_session_id, name });
+            ClientResponse::JoinLobby { joined: true }
+        },
+        _ => ClientResponse::BadState,
+    }
+}
+
+fn get_leaderboard_route(
+    server_context: Arc<MultiplayerServerContext>,
+    top_n: usize,
+    sort_by: LeaderboardSort
+) -> ClientResponse {
+    match server_context.profiles.all() {
+        Ok(profiles) => ClientResponse::GetLeaderboard { 
+            entries: leaderboard::build_leaderboard(&profiles, top_n, sort_by) 
+        },
+        Err(e) => ClientResponse::OtherError { err: e.to_string() },
+    }
+}
+
+fn get_player_stats_route(
+    server_context: Arc<MultiplayerServerContext>,
+    name: String
+) -> ClientResponse {
+    match server_context.profiles.load(&name) {
+        Ok(profile) => ClientResponse::GetPlayerStats { 
+            stats: profile.as_ref().map(PlayerStats::from) 
+        },
+        Err(e) => ClientResponse::OtherError { err: e.to_string() },
+    }
+}
+
+fn get_match_history_route(
+    server_context: Arc<MultiplayerServerContext>,
+    limit: usize
+) -> ClientResponse {
+    match server_context.match_history.recent(limit.min(MAX_MATCH_HISTORY_SIZE)) {
+        Ok(matches) => ClientResponse::GetMatchHistory { matches },
+        Err(e) => ClientResponse::OtherError { err: e.to_string() },
+    }
+}
+
+fn get_match_route(
+    server_context: Arc<MultiplayerServerContext>,
+    id: MatchId
+) -> ClientResponse {
+    match server_context.match_history.get(id) {
+        Ok(record) => ClientResponse::GetMatch { record },
+        Err(e) => ClientResponse::OtherError { err: e.to_string() },
+    }
+}
+
+async fn admin_route(
+    server_context: Arc<MultiplayerServerContext>,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    command: AdminCommand
+) -> ClientResponse {
+    let name = clieant_session_data.lock().unwrap().get_name().map(str::to_string);
+    if !name.is_some_and(|name| server_context.is_admin(&name)) {
+        return ClientResponse::Admin { result: Err(AdminError::NotAdmin) };
+    }
+
+    log::info!("Admin command {command:?}");
+    match command {
+        AdminCommand::SetNextRoundSeed { seed } => {
+            *server_context.next_round_seed.lock().unwrap() = Some(seed);
+            ClientResponse::Admin { result: Ok(()) }
+        },
+        AdminCommand::SaveWorld { name } => {
+            let result = match saved_world_path_of(&server_context, &name) {
+                Ok(path) => server_context.request_gameplay(|reply| GameplayCommand::SaveWorld { path, reply }).await
+                    .map_err(AdminError::from),
+                Err(e) => Err(e),
+            };
+            ClientResponse::Admin { result }
+        },
+        AdminCommand::RestoreWorld { name } => {
+            let result = match saved_world_path_of(&server_context, &name) {
+                Ok(path) => server_context.request_gameplay(|reply| GameplayCommand::RestoreWorld { path, reply }).await
+                    .map(|_| ())
+                    .map_err(AdminError::from),
+                Err(e) => Err(e),
+            };
+            ClientResponse::Admin { result }
+        },
+    }
+}
+
+fn saved_world_path_of(server_context: &MultiplayerServerContext, name: &str) -> Result<PathBuf, AdminError> {
+    let config = server_context.config();
+    let dir = config.storage.saved_worlds_dir.as_ref().ok_or(AdminError::SavedWorldsDisabled)?;
+    saved_world::saved_world_path(dir, name).map_err(|e| AdminError::Failed { reason: e.to_string() })
+}
+
 fn send_message_route(
     msg: String, 
     client_session_id: ClientSessionId, 
@@ -178,34 +443,94 @@ fn read_chat_messages_route(
 
 fn set_ready_route(
     set_to_ready: bool,
+    client_session_id: ClientSessionId,
     clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    server_context: Arc<MultiplayerServerContext>
 ) -> ClientResponse {
     let mut sessiod_data_guard = clieant_session_data.lock().unwrap();
     match &mut sessiod_data_guard.state {
         ClientSessionState::JustConnected => ClientResponse::BadState,
+        ClientSessionState::Authenticated { name: _ } => ClientResponse::BadState,
         ClientSessionState::NameWasSet { name: _, ready_to_start, entity_player_id: _ } => {
             // set ready
+            if *ready_to_start != set_to_ready {
+                server_context.emit_event(GameEvent::ReadyToggled { client_id: client_session_id, ready: set_to_ready });
+            }
             *ready_to_start = set_to_ready;
             ClientResponse::SetReady { was_set: set_to_ready }
         },
     }
 }
 
+impl From<GameplayCommandError> for AdminError {
+    fn from(value: GameplayCommandError) -> Self {
+        match value {
+            GameplayCommandError::BadState => AdminError::BadState,
+            _ => AdminError::Failed { reason: value.to_string() },
+        }
+    }
+}
+
+impl From<GameplayCommandError> for ClientResponse {
+    fn from(value: GameplayCommandError) -> Self {
+        match value {
+            GameplayCommandError::BadState => ClientResponse::BadState,
+            GameplayCommandError::EntityNotFound { id } => ClientResponse::EntityNotFound { id },
+            GameplayCommandError::ActionNotSupported => ClientResponse::OtherError { err: value.to_string() },
+            GameplayCommandError::NoPathFound => ClientResponse::MoveTo { steps: None },
+            GameplayCommandError::EntityNotSeeker { id: _ } => ClientResponse::OtherError { err: value.to_string() },
+            GameplayCommandError::MoveOutOfBounds { destination } => ClientResponse::MoveOutOfBounds { destination },
+            GameplayCommandError::GameplayTaskGone => ClientResponse::OtherError { err: value.to_string() },
+            GameplayCommandError::SavedWorldError(_) => ClientResponse::OtherError { err: value.to_string() },
+            GameplayCommandError::WorldError(_) => ClientResponse::OtherError { err: value.to_string() },
+        }
+    }
+}
+
 fn gameplay_state_route(server_context: Arc<MultiplayerServerContext>) -> ClientResponse {
-    let server_context_guard = server_context.gameplay_state.lock().unwrap();
-    ClientResponse::CheckGameplayState { state: (&*server_context_guard).into() }
+    let snapshot = server_context.gameplay_snapshot();
+    ClientResponse::CheckGameplayState { state: snapshot.state.clone() }
 }
 
-fn world_check_route(server_context: Arc<MultiplayerServerContext>) -> ClientResponse {
-    let gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-    match &*gameplay_state_guard {
-        super::GameplayState::Lobby { counting_to_start: _, last_result:_ } => ClientResponse::BadState,
-        super::GameplayState::GameRunning { world } => {
-            ClientResponse::WorldCheck { 
-                entities: EntityCheckData::vec_from_iter(world.iter_entities())
-            }
+/// Seeker gets only entities in its sight
+fn world_check_route(
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    server_context: Arc<MultiplayerServerContext>
+) -> ClientResponse {
+    let player_entity_id = clieant_session_data.lock().unwrap().get_entity_player_id();
+    let snapshot = server_context.gameplay_snapshot();
+    let Some(world_snapshot) = &snapshot.world else {
+        return ClientResponse::BadState;
+    };
+
+    let seeker_view = player_entity_id.and_then(|id| world_snapshot.seeker_views.get(&id));
+    match seeker_view {
+        Some(seeker_view) => ClientResponse::WorldCheck { 
+            entities: seeker_view.entities.clone(),
+            map_id: world_snapshot.map.id,
+            visible_tiles: Some(seeker_view.visible_tiles.clone()),
+            movement_mode: world_snapshot.movement_mode
+        },
+        None => ClientResponse::WorldCheck { 
+            entities: world_snapshot.entities.clone(),
+            map_id: world_snapshot.map.id,
+            visible_tiles: None,
+            movement_mode: world_snapshot.movement_mode
         },
-        super::GameplayState::Ending { countdown: _, result: _ } => ClientResponse::BadState,
+    }
+}
+
+/// Obstacles never move, clients fetch them once per map
+fn get_map_route(server_context: Arc<MultiplayerServerContext>) -> ClientResponse {
+    let snapshot = server_context.gameplay_snapshot();
+    let Some(world_snapshot) = &snapshot.world else {
+        return ClientResponse::BadState;
+    };
+
+    ClientResponse::GetMap { 
+        map_id: world_snapshot.map.id, 
+        obstacles: world_snapshot.map.obstacles.clone(), 
+        bounds: world_snapshot.map.bounds 
     }
 }
 
@@ -217,7 +542,7 @@ fn server_check_route(server_context: Arc<MultiplayerServerContext>) -> ClientRe
     }
 }
 
-fn move_route(
+async fn move_route(
     dir: MoveDirection,
     clieant_session_data: Arc<Mutex<ClientSessionData>>,
     server_context: Arc<MultiplayerServerContext>
@@ -229,36 +554,64 @@ fn move_route(
             return ClientResponse::BadState;
         },
     };
-    let mut gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-
-    match &mut *gameplay_state_guard {
-        super::GameplayState::Lobby { counting_to_start: _, last_result:_ } => ClientResponse::BadState,
-        super::GameplayState::Ending { countdown: _ , result: _ } => ClientResponse::BadState,
-        super::GameplayState::GameRunning { world } => {
-            let player_info = world
-                .get_entity_by_id(player_entity_id)
-                .map(|player| (player.position, player.is_moving()));
-            
-            let was_moved = if let Some((player_pos, player_moving)) = player_info {
-                if player_moving {
-                    // Can move only after not moving
-                    false
-                } else {
-                    let next_player_pos = player_pos + match dir {
-                        MoveDirection::Up => Vector2F::new(0.0, 1.0),
-                        MoveDirection::Down => Vector2F::new(0.0, -1.0),
-                        MoveDirection::Left => Vector2F::new(-1.0, 0.0),
-                        MoveDirection::Right => Vector2F::new(1.0, 0.0),
-                    } * world::TILE_SIZE;
-                    
-                    world.try_start_move_entity_to(player_entity_id, next_player_pos).is_ok()
-                }
-            } else {
-                false
-            };
 
-            ClientResponse::Move { started: was_moved }
+    let move_result = server_context.request_gameplay(|reply| GameplayCommand::Move { 
+        entity_id: player_entity_id, 
+        dir, 
+        reply 
+    }).await;
+
+    match move_result {
+        Ok(was_moved) => ClientResponse::Move { started: was_moved },
+        Err(e) => e.into(),
+    }
+}
+
+async fn move_to_route(
+    target: Vector2F,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    server_context: Arc<MultiplayerServerContext>
+) -> ClientResponse {
+    let player_entity_id = match clieant_session_data.lock().unwrap().get_entity_player_id() {
+        Some(id) => id,
+        None => {
+            return ClientResponse::BadState;
+        },
+    };
+
+    let move_to_result = server_context.request_gameplay(|reply| GameplayCommand::MoveTo { 
+        entity_id: player_entity_id, 
+        target, 
+        reply 
+    }).await;
+
+    match move_to_result {
+        Ok(steps) => ClientResponse::MoveTo { steps: Some(steps) },
+        Err(e) => e.into(),
+    }
+}
+
+async fn set_velocity_route(
+    direction: Vector2F,
+    clieant_session_data: Arc<Mutex<ClientSessionData>>,
+    server_context: Arc<MultiplayerServerContext>
+) -> ClientResponse {
+    let player_entity_id = match clieant_session_data.lock().unwrap().get_entity_player_id() {
+        Some(id) => id,
+        None => {
+            return ClientResponse::BadState;
         },
+    };
+
+    let set_velocity_result = server_context.request_gameplay(|reply| GameplayCommand::SetVelocity { 
+        entity_id: player_entity_id, 
+        direction, 
+        reply 
+    }).await;
+
+    match set_velocity_result {
+        Ok(()) => ClientResponse::SetVelocity { was_set: true },
+        Err(e) => e.into(),
     }
 }
 
@@ -278,106 +631,59 @@ fn get_role_route(
         }
     };
     
-    let gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-    match &*gameplay_state_guard {
-        super::GameplayState::Lobby { counting_to_start: _, last_result:_ } => ClientResponse::BadState,
-        super::GameplayState::GameRunning { world } => {
-            match world.get_entity_by_id(entity_id) {
-                Some(e) => {
-                    // Rather should not happen this player will not be PlayerEntity type
-                    match e.get_player_role() {
-                        Some(player_role) => ClientResponse::GetRole { role: *player_role},
-                        None => ClientResponse::EntityNotPlayer { id: entity_id }
-                    }
-                },
+    let snapshot = server_context.gameplay_snapshot();
+    match &snapshot.world {
+        Some(world_snapshot) => {
+            match world_snapshot.player_roles.get(&entity_id) {
+                Some(player_role) => ClientResponse::GetRole { role: *player_role },
+                // Rather should not happen this player will not be PlayerEntity type
+                None if world_snapshot.entities.iter().any(|e| e.id == entity_id) => ClientResponse::EntityNotPlayer { id: entity_id },
                 None => ClientResponse::EntityNotFound { id: entity_id }
             }
         },
-        super::GameplayState::Ending { countdown: _ , result: _ } => ClientResponse::BadState,
+        None => ClientResponse::BadState,
     }
 }
 
 fn get_countdown_time_route(server_context: Arc<MultiplayerServerContext>) -> ClientResponse {
-    let gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-    match &*gameplay_state_guard {
-        super::GameplayState::Lobby { counting_to_start, last_result:_ } => {
+    let snapshot = server_context.gameplay_snapshot();
+    match &snapshot.state {
+        GameplayStateBrief::Lobby { counting_to_start, last_result:_ } => {
             ClientResponse::GetStartCountdownTime { time: *counting_to_start }
         },
-        super::GameplayState::GameRunning { world: _ } => ClientResponse::BadState,
-        super::GameplayState::Ending { countdown: _ , result: _ } => ClientResponse::BadState,
+        GameplayStateBrief::GameRunning => ClientResponse::BadState,
+        GameplayStateBrief::Ending { countdown: _ , result: _ } => ClientResponse::BadState,
     }
 }
 
-fn try_uncover_route(
+async fn try_uncover_route(
     server_context: Arc<MultiplayerServerContext>,
     clieant_session_data: Arc<Mutex<ClientSessionData>>,
     uncovering_entity_id: EntityId
 ) -> ClientResponse {
-    let mut gameplay_state_guard = server_context.gameplay_state.lock().unwrap();
-    match &mut *gameplay_state_guard {
-        super::GameplayState::GameRunning { world } => {
-            // Find entity of the client
-            let client_entity_id = {
-                let client_data_guard = clieant_session_data.lock().unwrap();
-                match client_data_guard.get_entity_player_id() {
-                    Some(entitiy_id) => entitiy_id,
-                    None => {
-                        return ClientResponse::OtherError { err: "Client handler no entity attached".to_string() };
-                    }
-                }
-            };
+    if !matches!(server_context.gameplay_snapshot().state, GameplayStateBrief::GameRunning) {
+        return ClientResponse::BadState;
+    }
 
-            // Cannot uncover self
-            if client_entity_id == uncovering_entity_id {
-                return ClientResponse::TryUncover { 
-                    uncover_result: UncoverResult { was_hider: None } 
-                };
+    // Find entity of the client
+    let client_entity_id = {
+        let client_data_guard = clieant_session_data.lock().unwrap();
+        match client_data_guard.get_entity_player_id() {
+            Some(entitiy_id) => entitiy_id,
+            None => {
+                return ClientResponse::OtherError { err: "Client handler no entity attached".to_string() };
             }
+        }
+    };
 
-            // Check if can uncover
-            let can_uncover = {
-                let (client_entity, other_entity) = match (world.get_entity_by_id(client_entity_id), world.get_entity_by_id(uncovering_entity_id)) {
-                    (Some(entity_c), Some(entity_o)) => (entity_c, entity_o),
-                    (None, _) => {
-                        return ClientResponse::EntityNotFound { id: client_entity_id }
-                    }, 
-                    (_, None) => {
-                        return ClientResponse::EntityNotFound { id: uncovering_entity_id }
-                    }, 
-                };
-
-                World::is_entity_inrange(client_entity.position, other_entity.position)
-            };
+    let action_result = server_context.request_gameplay(|reply| GameplayCommand::PlayerAction { 
+        entity_id: client_entity_id, 
+        action: PlayerAction::Uncover { target_entity_id: uncovering_entity_id }, 
+        reply 
+    }).await;
 
-            // Can use unwrap, both entities are proved now
-            let was_hider = if can_uncover {
-                let was_hider = world.get_entity_by_id(uncovering_entity_id).unwrap()
-                    .get_player_role()
-                    .is_some_and(|role| matches!(role, PlayerRole::Hider { stats: _ }));
-
-                if was_hider {
-                    // Uncover hider player do not remove
-                    let hider_entity = world.get_entity_by_id_mut(uncovering_entity_id).unwrap();
-                    hider_entity.set_hider_covered(false).unwrap();
-                    Some(true)
-
-                } else {
-                    // Remove NPC and punish seeker
-                    world.remove_entity(uncovering_entity_id).unwrap();
-
-                    let seeker_entity = world.get_entity_by_id_mut(client_entity_id).unwrap();
-                    seeker_entity.punish_seeker().unwrap();
-                    Some(false)
-                }
-            } else {
-                // Notify not in range
-                None
-            };
-            
-            ClientResponse::TryUncover { 
-                uncover_result: UncoverResult { was_hider } 
-            }
-        },
-        _ => ClientResponse::BadState,
+    match action_result {
+        Ok(PlayerActionResult::Uncover(uncover_result)) => ClientResponse::TryUncover { uncover_result },
+        Err(e) => e.into(),
     }
-}
\ No newline at end of file
+}
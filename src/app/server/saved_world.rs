@@ -0,0 +1,137 @@
+use std::{
+    path::{
+        Path,
+        PathBuf
+    },
+    time::SystemTime
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use crate::game::world::World;
+
+use super::game_mode::GameModeKind;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SavedWorldError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("SerializationError, reason='{0}'")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("InvalidName, name='{0}'")]
+    InvalidName(String),
+}
+
+/// Complete state of running round, world keeps its tick and RNG state
+#[derive(Debug, Deserialize)]
+pub struct SavedWorld {
+    pub game_mode: GameModeKind,
+    pub saved_at: SystemTime,
+    pub world: World,
+}
+
+/// Same layout as `SavedWorld`, world stays owned by running round
+#[derive(Serialize)]
+struct SavedWorldRef<'a> {
+    game_mode: GameModeKind,
+    saved_at: SystemTime,
+    world: &'a World,
+}
+
+impl SavedWorld {
+    pub fn load(path: &Path) -> Result<Self, SavedWorldError> {
+        let content = std::fs::read_to_string(path)?;
+        let mut saved_world: Self = serde_json::from_str(&content)?;
+        saved_world.world.rebuild_spatial_index();
+        Ok(saved_world)
+    }
+
+    /// Pretty printed, so saved worlds can be edited by hand into test fixtures
+    pub fn save(path: &Path, game_mode: GameModeKind, world: &World) -> Result<(), SavedWorldError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let saved_world = SavedWorldRef { game_mode, saved_at: SystemTime::now(), world };
+        std::fs::write(path, serde_json::to_string_pretty(&saved_world)?)?;
+        Ok(())
+    }
+}
+
+/// File of saved world inside `dir`, name can not escape the directory
+pub fn saved_world_path(dir: &Path, name: &str) -> Result<PathBuf, SavedWorldError> {
+    let is_valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !is_valid {
+        return Err(SavedWorldError::InvalidName(name.to_string()));
+    }
+
+    Ok(dir.join(format!("{name}.json")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        game::{
+            math::Vector2F,
+            tile_layer::ObstacleKind,
+            world::{
+                get_tiled_vec,
+                WorldSettings,
+                ENTITY_SIZE,
+                TILE_SIZE
+            }
+        },
+        requests::EntityCheckData
+    };
+
+    #[test]
+    fn test_restored_world_continues_the_same() {
+        let mut world = World::with_seed(WorldSettings::default(), 11);
+        world.create_entity_player("Tuna", Vector2F::zero(), ENTITY_SIZE);
+        world.place_obstacle(get_tiled_vec(-2, 1), ObstacleKind::Crate).unwrap();
+        for i in 0..5 {
+            world.create_entity_npc(format!("Npc{i}"), Vector2F::new(i as f32 * 10.0, 0.0), ENTITY_SIZE);
+        }
+        for _ in 0..10 {
+            world.tick(Duration::from_millis(50));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = saved_world_path(dir.path(), "roaming").unwrap();
+        SavedWorld::save(&path, GameModeKind::HideAndSeek, &world).unwrap();
+
+        let mut original = world;
+        let mut restored = SavedWorld::load(&path).unwrap().world;
+        assert_eq!(restored.ticks(), original.ticks());
+        assert_eq!(restored.obstacles(), original.obstacles());
+        assert_eq!(restored.get_free_tiles_positions(Vector2F::zero(), TILE_SIZE * 4.0), original.get_free_tiles_positions(Vector2F::zero(), TILE_SIZE * 4.0));
+
+        for _ in 0..100 {
+            original.tick(Duration::from_millis(50));
+            restored.tick(Duration::from_millis(50));
+        }
+
+        let positions = |world: &World| EntityCheckData::vec_from_iter(world.iter_entities())
+            .into_iter()
+            .map(|entity| (entity.id, entity.position))
+            .collect::<Vec<_>>();
+        assert_eq!(positions(&original), positions(&restored));
+    }
+
+    #[test]
+    fn test_saved_world_name_stays_in_dir() {
+        let dir = Path::new("saved_worlds");
+        assert_eq!(saved_world_path(dir, "seeker_cornered").unwrap(), dir.join("seeker_cornered.json"));
+        assert!(saved_world_path(dir, "").is_err());
+        assert!(saved_world_path(dir, "../config").is_err());
+        assert!(saved_world_path(dir, "a/b").is_err());
+    }
+}
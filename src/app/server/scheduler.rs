@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use tokio::time::{
+    Instant,
+    Interval,
+    MissedTickBehavior
+};
+
+pub const DEFAULT_TICKS_PER_SECOND: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickRate {
+    ticks_per_second: u32,
+}
+
+impl TickRate {
+    pub fn new(ticks_per_second: u32) -> Self {
+        assert!(ticks_per_second > 0);
+        Self { ticks_per_second }
+    }
+
+    pub fn ticks_per_second(&self) -> u32 {
+        self.ticks_per_second
+    }
+
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.ticks_per_second as f64)
+    }
+
+    /// Number of whole ticks covering given duration, at least 1 for non zero duration
+    pub fn ticks_in(&self, duration: Duration) -> u32 {
+        (duration.as_secs_f64() * self.ticks_per_second as f64).ceil() as u32
+    }
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICKS_PER_SECOND)
+    }
+}
+
+/// Fixed-timestep scheduler. Ticks are scheduled at constant points in time,
+/// so time spent on work or waiting for locks does not delay following ticks.
+/// Ticks missed for short while are catched up in burst, if lag grows
+/// bigger than `MAX_CATCH_UP_LAG` missed ticks are dropped.
+#[derive(Debug)]
+pub struct TickScheduler {
+    interval: Interval,
+    tick_rate: TickRate,
+    tick: u64,
+}
+
+impl TickScheduler {
+    const MAX_CATCH_UP_LAG: Duration = Duration::from_millis(250);
+
+    pub fn new(tick_rate: TickRate) -> Self {
+        let mut interval = tokio::time::interval(tick_rate.tick_duration());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+        Self {
+            interval,
+            tick_rate,
+            tick: 0
+        }
+    }
+
+    pub fn tick_rate(&self) -> TickRate {
+        self.tick_rate
+    }
+
+    /// Change rate starting from next tick, tick numbering continues
+    pub fn set_tick_rate(&mut self, tick_rate: TickRate) {
+        let tick_duration = tick_rate.tick_duration();
+        self.interval = tokio::time::interval_at(Instant::now() + tick_duration, tick_duration);
+        self.interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+        self.tick_rate = tick_rate;
+    }
+
+    /// Completes when next tick is due, returns its monotonic number. Cancel safe.
+    pub async fn next_tick(&mut self) -> u64 {
+        let scheduled = self.interval.tick().await;
+
+        let lag = Instant::now().saturating_duration_since(scheduled);
+        if lag > Self::MAX_CATCH_UP_LAG {
+            log::warn!("Tick {} is {lag:?} behind schedule, skipping missed ticks", self.tick);
+            self.interval.reset();
+        }
+
+        let tick = self.tick;
+        self.tick += 1;
+        tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_rate_conversions() {
+        let tick_rate = TickRate::new(20);
+        assert_eq!(tick_rate.tick_duration(), Duration::from_millis(50));
+        assert_eq!(tick_rate.ticks_in(Duration::from_secs(2)), 40);
+        assert_eq!(tick_rate.ticks_in(Duration::from_millis(10)), 1);
+        assert_eq!(tick_rate.ticks_in(Duration::ZERO), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_keeps_steady_rate_despite_work() {
+        let tick_rate = TickRate::new(10);
+        let mut scheduler = TickScheduler::new(tick_rate);
+        let start = Instant::now();
+
+        for expected_tick in 0..10 {
+            let tick = scheduler.next_tick().await;
+            assert_eq!(tick, expected_tick);
+            // Work shorter than tick duration must not shift schedule
+            tokio::time::sleep(Duration::from_millis(30)).await;
+        }
+
+        // First tick completes immediately, 9 more ticks at 100ms each, plus last work
+        assert_eq!(start.elapsed(), Duration::from_millis(930));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_catches_up_short_lag() {
+        let mut scheduler = TickScheduler::new(TickRate::new(10));
+        scheduler.next_tick().await;
+
+        // Stall for 2 ticks, they should be delivered in burst
+        tokio::time::sleep(Duration::from_millis(210)).await;
+        let stalled_at = Instant::now();
+        scheduler.next_tick().await;
+        scheduler.next_tick().await;
+        assert_eq!(stalled_at.elapsed(), Duration::ZERO);
+        assert_eq!(scheduler.next_tick().await, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_tick_rate_change_keeps_numbering() {
+        let mut scheduler = TickScheduler::new(TickRate::new(10));
+        scheduler.next_tick().await;
+        scheduler.next_tick().await;
+
+        scheduler.set_tick_rate(TickRate::new(50));
+        let changed_at = Instant::now();
+        assert_eq!(scheduler.next_tick().await, 2);
+        assert_eq!(scheduler.next_tick().await, 3);
+        assert_eq!(changed_at.elapsed(), Duration::from_millis(40));
+    }
+}
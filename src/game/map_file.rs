@@ -0,0 +1,411 @@
+use std::{
+    collections::{
+        HashMap,
+        VecDeque
+    },
+    path::Path
+};
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use super::{
+    map_layout::{
+        MapLayout,
+        NpcRegion
+    },
+    math::{
+        Vector2F,
+        Vector2I
+    },
+    tile_layer::{
+        ObstacleKind,
+        TileBounds
+    },
+    world::{
+        get_tiled_vec,
+        World,
+        WorldError
+    }
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MapFileError {
+    #[error("IoError, reason='{0}'")]
+    IoError(#[from] std::io::Error),
+
+    #[error("ParseError, reason='{0}'")]
+    ParseError(#[from] toml::de::Error),
+
+    #[error("EmptyGrid")]
+    EmptyGrid,
+
+    #[error("BadLegendSymbol, symbol='{0}', reason='must be single character'")]
+    BadLegendSymbol(String),
+
+    #[error("RaggedRow, row={row}, width={width}, expected_width={expected_width}")]
+    RaggedRow {
+        row: usize,
+        width: usize,
+        expected_width: usize,
+    },
+
+    #[error("UnknownTile, symbol='{symbol}', row={row}, column={column}")]
+    UnknownTile {
+        symbol: char,
+        row: usize,
+        column: usize,
+    },
+
+    #[error("RectOutsideGrid, field='{field}'")]
+    RectOutsideGrid {
+        field: String,
+    },
+
+    #[error("BadDensity, field='{field}', density={density}, reason='must be in (0, 1]'")]
+    BadDensity {
+        field: String,
+        density: f32,
+    },
+
+    #[error("NoFloor")]
+    NoFloor,
+
+    #[error("FloorOutsideBounds, row={row}, column={column}")]
+    FloorOutsideBounds {
+        row: usize,
+        column: usize,
+    },
+
+    #[error("UnreachableFloor, row={row}, column={column}")]
+    UnreachableFloor {
+        row: usize,
+        column: usize,
+    },
+}
+
+/// Meaning of grid symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegendTile {
+    Floor,
+    /// Floor where players spawn, whole floor is used if map has none
+    Spawn,
+    Wall,
+    Rock,
+    Crate,
+}
+
+/// Rectangle in grid coordinates, `x` is column and `y` is row of top left tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NpcRegionDef {
+    pub area: GridRect,
+    /// Part of free floor in area taken by NPCs
+    pub density: f32,
+}
+
+/// Map file as written by level designer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MapFileContent {
+    name: String,
+    /// Rows of symbols, first row is top of the map
+    grid: String,
+    /// Play area, whole grid if not set
+    bounds: Option<GridRect>,
+    #[serde(default = "default_legend")]
+    legend: HashMap<String, LegendTile>,
+    #[serde(default)]
+    npc_regions: Vec<NpcRegionDef>,
+}
+
+/// Validated hand-authored map, grid is centered at world origin
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapFile {
+    pub name: String,
+    width: usize,
+    height: usize,
+    tiles: Vec<LegendTile>,
+    bounds: GridRect,
+    npc_regions: Vec<NpcRegionDef>,
+}
+
+fn default_legend() -> HashMap<String, LegendTile> {
+    HashMap::from([
+        (".".to_string(), LegendTile::Floor),
+        ("S".to_string(), LegendTile::Spawn),
+        ("#".to_string(), LegendTile::Wall),
+        ("R".to_string(), LegendTile::Rock),
+        ("C".to_string(), LegendTile::Crate),
+    ])
+}
+
+impl LegendTile {
+    fn is_floor(&self) -> bool {
+        matches!(self, LegendTile::Floor | LegendTile::Spawn)
+    }
+}
+
+impl GridRect {
+    fn contains(&self, column: usize, row: usize) -> bool {
+        (self.x..self.x + self.width).contains(&column) && (self.y..self.y + self.height).contains(&row)
+    }
+
+    fn fits_in(&self, width: usize, height: usize) -> bool {
+        self.width > 0 && self.height > 0 && self.x + self.width <= width && self.y + self.height <= height
+    }
+}
+
+impl MapFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MapFileError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// Parse and validate, broken map is rejected with first problem found
+    pub fn parse(content: &str) -> Result<Self, MapFileError> {
+        let content: MapFileContent = toml::from_str(content)?;
+
+        let mut legend = HashMap::new();
+        for (symbol, tile) in content.legend.iter() {
+            let mut chars = symbol.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => legend.insert(c, *tile),
+                _ => return Err(MapFileError::BadLegendSymbol(symbol.clone())),
+            };
+        }
+
+        let rows: Vec<&str> = content.grid.lines()
+            .map(str::trim_end)
+            .skip_while(|row| row.is_empty())
+            .collect();
+        let rows_count = rows.iter().rposition(|row| !row.is_empty()).map(|last| last + 1).unwrap_or_default();
+        let rows = &rows[..rows_count];
+
+        let width = rows.first().map(|row| row.chars().count()).unwrap_or_default();
+        if width == 0 {
+            return Err(MapFileError::EmptyGrid);
+        }
+
+        let mut tiles = Vec::with_capacity(width * rows.len());
+        for (row_index, row) in rows.iter().enumerate() {
+            let row_width = row.chars().count();
+            if row_width != width {
+                return Err(MapFileError::RaggedRow { row: row_index, width: row_width, expected_width: width });
+            }
+
+            for (column, symbol) in row.chars().enumerate() {
+                let tile = legend.get(&symbol)
+                    .ok_or(MapFileError::UnknownTile { symbol, row: row_index, column })?;
+                tiles.push(*tile);
+            }
+        }
+
+        let height = rows.len();
+        let bounds = content.bounds.unwrap_or(GridRect { x: 0, y: 0, width, height });
+        if !bounds.fits_in(width, height) {
+            return Err(MapFileError::RectOutsideGrid { field: "bounds".to_string() });
+        }
+
+        for (index, region) in content.npc_regions.iter().enumerate() {
+            if !region.area.fits_in(width, height) {
+                return Err(MapFileError::RectOutsideGrid { field: format!("npc_regions[{index}].area") });
+            }
+            if !(region.density > 0.0 && region.density <= 1.0) {
+                return Err(MapFileError::BadDensity { field: format!("npc_regions[{index}].density"), density: region.density });
+            }
+        }
+
+        let map = Self {
+            name: content.name,
+            width,
+            height,
+            tiles,
+            bounds,
+            npc_regions: content.npc_regions,
+        };
+        map.validate_floor()?;
+        Ok(map)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Grid tile at given column and row
+    pub fn tile(&self, column: usize, row: usize) -> Option<LegendTile> {
+        (column < self.width && row < self.height).then(|| self.tiles[row * self.width + column])
+    }
+
+    /// First row is top of the map, world y grows upwards
+    pub fn world_tile(&self, column: usize, row: usize) -> Vector2I {
+        Vector2I::new(
+            column as i32 - (self.width / 2) as i32,
+            (self.height - 1 - row) as i32 - (self.height / 2) as i32
+        )
+    }
+
+    fn world_position(&self, column: usize, row: usize) -> Vector2F {
+        let tile = self.world_tile(column, row);
+        get_tiled_vec(tile.x, tile.y)
+    }
+
+    fn positions_where(&self, predicate: impl Fn(usize, usize, LegendTile) -> bool) -> Vec<Vector2F> {
+        (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |column| (column, row)))
+            .filter(|(column, row)| predicate(*column, *row, self.tiles[row * self.width + column]))
+            .map(|(column, row)| self.world_position(column, row))
+            .collect()
+    }
+
+    /// Floor must exist, stay inside bounds and be reachable from any other floor
+    fn validate_floor(&self) -> Result<(), MapFileError> {
+        let start = self.tiles.iter().position(LegendTile::is_floor).ok_or(MapFileError::NoFloor)?;
+
+        if let Some(index) = (0..self.tiles.len()).find(|index| self.tiles[*index].is_floor() && !self.bounds.contains(index % self.width, index / self.width)) {
+            return Err(MapFileError::FloorOutsideBounds { row: index / self.width, column: index % self.width });
+        }
+
+        let mut reached = vec![false; self.tiles.len()];
+        let mut queue = VecDeque::from([start]);
+        reached[start] = true;
+        while let Some(index) = queue.pop_front() {
+            let (column, row) = (index % self.width, index / self.width);
+            let neighbours = [
+                column.checked_sub(1).map(|column| (column, row)),
+                (column + 1 < self.width).then_some((column + 1, row)),
+                row.checked_sub(1).map(|row| (column, row)),
+                (row + 1 < self.height).then_some((column, row + 1)),
+            ];
+
+            for (column, row) in neighbours.into_iter().flatten() {
+                let neighbour = row * self.width + column;
+                if !reached[neighbour] && self.tiles[neighbour].is_floor() {
+                    reached[neighbour] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        match (0..self.tiles.len()).find(|index| self.tiles[*index].is_floor() && !reached[*index]) {
+            Some(index) => Err(MapFileError::UnreachableFloor { row: index / self.width, column: index % self.width }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MapLayout for MapFile {
+    fn apply_to(&self, world: &mut World) -> Result<(), WorldError> {
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let kind = match self.tiles[row * self.width + column] {
+                    LegendTile::Wall => ObstacleKind::Wall,
+                    LegendTile::Rock => ObstacleKind::Rock,
+                    LegendTile::Crate => ObstacleKind::Crate,
+                    LegendTile::Floor | LegendTile::Spawn => continue,
+                };
+                world.place_obstacle(self.world_position(column, row), kind)?;
+            }
+        }
+        world.set_bounds(Some(self.bounds()));
+        world.set_points_of_interest(self.points_of_interest());
+        Ok(())
+    }
+
+    /// Bottom left corner of bounds is in their last grid row
+    fn bounds(&self) -> TileBounds {
+        let bottom_left = self.world_tile(self.bounds.x, self.bounds.y + self.bounds.height - 1);
+        TileBounds::new(bottom_left, Vector2I::new(self.bounds.width as i32, self.bounds.height as i32))
+    }
+
+    fn floor_positions(&self) -> Vec<Vector2F> {
+        self.positions_where(|_, _, tile| tile.is_floor())
+    }
+
+    fn player_spawn_positions(&self) -> Vec<Vector2F> {
+        let spawns = self.positions_where(|_, _, tile| tile == LegendTile::Spawn);
+        if spawns.is_empty() {
+            self.floor_positions()
+        } else {
+            spawns
+        }
+    }
+
+    fn npc_regions(&self) -> Vec<NpcRegion> {
+        self.npc_regions.iter()
+            .map(|region| NpcRegion {
+                positions: self.positions_where(|column, row, tile| tile.is_floor() && region.area.contains(column, row)),
+                density: region.density,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_grid(grid: &str) -> Result<MapFile, MapFileError> {
+        MapFile::parse(&format!("name = \"Test\"\ngrid = \"\"\"\n{grid}\n\"\"\""))
+    }
+
+    #[test]
+    fn test_example_map_is_valid() {
+        let map = MapFile::parse(include_str!("../../res/maps/warehouse.toml")).unwrap();
+        assert!(!map.player_spawn_positions().is_empty());
+        assert!(!map.npc_regions().is_empty());
+    }
+
+    #[test]
+    fn test_grid_is_centered_with_first_row_on_top() {
+        let map = parse_grid("S..\n...\n..#").unwrap();
+        assert_eq!((map.width(), map.height()), (3, 3));
+        assert_eq!(map.world_tile(0, 0), Vector2I::new(-1, 1));
+        assert_eq!(map.world_tile(2, 2), Vector2I::new(1, -1));
+        assert_eq!(map.player_spawn_positions(), vec![get_tiled_vec(-1, 1)]);
+        assert_eq!(map.bounds(), TileBounds::new(Vector2I::new(-1, -1), Vector2I::new(3, 3)));
+
+        let mut world = World::new();
+        map.apply_to(&mut world).unwrap();
+        assert!(world.is_tile_blocked(&get_tiled_vec(1, -1)));
+        assert_eq!(world.obstacles().len(), 1);
+    }
+
+    #[test]
+    fn test_broken_maps_are_rejected() {
+        assert!(matches!(parse_grid(""), Err(MapFileError::EmptyGrid)));
+        assert!(matches!(parse_grid("...\n.."), Err(MapFileError::RaggedRow { row: 1, width: 2, expected_width: 3 })));
+        assert!(matches!(parse_grid("..x"), Err(MapFileError::UnknownTile { symbol: 'x', row: 0, column: 2 })));
+        assert!(matches!(parse_grid("###"), Err(MapFileError::NoFloor)));
+        assert!(matches!(parse_grid("..#.."), Err(MapFileError::UnreachableFloor { row: 0, column: 3 })));
+    }
+
+    #[test]
+    fn test_bad_regions_are_rejected() {
+        let with_extra = |extra: &str| MapFile::parse(&format!("name = \"Test\"\ngrid = \"\"\"\n...\n...\n\"\"\"\n{extra}"));
+
+        assert!(matches!(with_extra("bounds = { x = 1, y = 0, width = 3, height = 2 }"), Err(MapFileError::RectOutsideGrid { field: _ })));
+        assert!(matches!(with_extra("bounds = { x = 1, y = 0, width = 2, height = 2 }"), Err(MapFileError::FloorOutsideBounds { row: 0, column: 0 })));
+        assert!(matches!(
+            with_extra("[[npc_regions]]\narea = { x = 0, y = 0, width = 2, height = 2 }\ndensity = 1.5"),
+            Err(MapFileError::BadDensity { field: _, density: _ })
+        ));
+        assert!(matches!(with_extra("[legend]\n\"ab\" = \"floor\""), Err(MapFileError::BadLegendSymbol(_))));
+    }
+}
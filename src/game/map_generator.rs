@@ -0,0 +1,459 @@
+use std::collections::VecDeque;
+
+use rand::{
+    seq::{
+        IndexedRandom,
+        SliceRandom
+    },
+    Rng
+};
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use super::{
+    map_layout::MapLayout,
+    math::{
+        Vector2F,
+        Vector2I
+    },
+    tile_layer::{
+        ObstacleKind,
+        TileBounds
+    },
+    world::{
+        get_tiled_vec,
+        World,
+        WorldError
+    }
+};
+
+/// Maps smaller than that have no space for rooms
+pub const MIN_MAP_SIZE: u32 = 16;
+pub const MAX_MAP_SIZE: u32 = 128;
+
+/// Layout of generated map
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapStyle {
+    /// Rectangular rooms from binary space partitioning, joined by corridors
+    #[default]
+    Rooms,
+    /// Organic caves grown with cellular automata
+    Caves,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapCell {
+    Floor,
+    Blocked(ObstacleKind),
+}
+
+/// Square grid of tiles centered at world origin.
+/// All floor tiles are reachable from each other.
+#[derive(Debug, Clone)]
+pub struct GeneratedMap {
+    size: u32,
+    cells: Vec<MapCell>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Room {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+const NEIGHBOURS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BSP_MIN_LEAF_SIZE: u32 = 8;
+const BSP_MIN_ROOM_SIZE: u32 = 3;
+const CAVES_INITIAL_WALLS_CHANCE: f64 = 0.42;
+const CAVES_SMOOTHING_STEPS: usize = 4;
+const CAVES_MIN_FLOOR_PART: f32 = 0.3;
+const CAVES_MAX_ATTEMPTS: usize = 8;
+const SCENERY_KINDS: [ObstacleKind; 2] = [ObstacleKind::Rock, ObstacleKind::Crate];
+
+impl Room {
+    fn center(&self) -> (u32, u32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+impl GeneratedMap {
+    /// Side of map in tiles, grows with number of hiders
+    pub fn size_for(hiders_count: usize, mapsize_generation_factor: usize) -> u32 {
+        let size = 2 * hiders_count.max(1) * mapsize_generation_factor;
+        (size as u32).clamp(MIN_MAP_SIZE, MAX_MAP_SIZE)
+    }
+
+    /// Same rng state gives the same map. `scenery_density` is part of floor covered with rocks and crates,
+    /// placed only where they do not cut off any floor.
+    pub fn generate<R: Rng + ?Sized>(style: MapStyle, size: u32, scenery_density: f32, rng: &mut R) -> Self {
+        let size = size.clamp(MIN_MAP_SIZE, MAX_MAP_SIZE);
+        let mut map = match style {
+            MapStyle::Rooms => Self::generate_rooms(size, rng),
+            MapStyle::Caves => Self::generate_caves(size, rng),
+        };
+        map.scatter_scenery(scenery_density, rng);
+
+        debug_assert!(map.is_connected());
+        map
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn cell(&self, tile: Vector2I) -> Option<MapCell> {
+        self.index_of(tile).map(|index| self.cells[index])
+    }
+
+    /// Tiles entities can stand on, all reachable from each other
+    pub fn floor_tiles(&self) -> Vec<Vector2I> {
+        (0..self.cells.len())
+            .filter(|index| self.cells[*index] == MapCell::Floor)
+            .map(|index| self.tile_of(index))
+            .collect()
+    }
+
+    /// Every floor tile reachable from first one
+    pub fn is_connected(&self) -> bool {
+        let floor_count = self.cells.iter().filter(|cell| **cell == MapCell::Floor).count();
+        match self.cells.iter().position(|cell| *cell == MapCell::Floor) {
+            Some(start) => self.flood_fill(start).iter().filter(|reached| **reached).count() == floor_count,
+            None => true,
+        }
+    }
+
+    fn filled(size: u32) -> Self {
+        Self {
+            size,
+            cells: vec![MapCell::Blocked(ObstacleKind::Wall); (size * size) as usize],
+        }
+    }
+
+    fn generate_rooms<R: Rng + ?Sized>(size: u32, rng: &mut R) -> Self {
+        let mut map = Self::filled(size);
+
+        // Outer ring stays wall
+        let mut rooms = vec![];
+        map.split_and_carve(Room { x: 1, y: 1, width: size - 2, height: size - 2 }, rng, &mut rooms);
+        map
+    }
+
+    /// Returns room of this subtree used to join it with sibling
+    fn split_and_carve<R: Rng + ?Sized>(&mut self, area: Room, rng: &mut R, rooms: &mut Vec<Room>) -> Room {
+        let can_split_vertically = area.width >= 2 * BSP_MIN_LEAF_SIZE;
+        let can_split_horizontally = area.height >= 2 * BSP_MIN_LEAF_SIZE;
+
+        let split_vertically = match (can_split_vertically, can_split_horizontally) {
+            (false, false) => {
+                let room = self.carve_room_in(area, rng);
+                rooms.push(room);
+                return room;
+            },
+            (true, false) => true,
+            (false, true) => false,
+            (true, true) => area.width > area.height || (area.width == area.height && rng.random_bool(0.5)),
+        };
+
+        let (first, second) = if split_vertically {
+            let split = rng.random_range(BSP_MIN_LEAF_SIZE..=area.width - BSP_MIN_LEAF_SIZE);
+            (
+                Room { width: split, ..area },
+                Room { x: area.x + split, width: area.width - split, ..area }
+            )
+        } else {
+            let split = rng.random_range(BSP_MIN_LEAF_SIZE..=area.height - BSP_MIN_LEAF_SIZE);
+            (
+                Room { height: split, ..area },
+                Room { y: area.y + split, height: area.height - split, ..area }
+            )
+        };
+
+        let first_room = self.split_and_carve(first, rng, rooms);
+        let second_room = self.split_and_carve(second, rng, rooms);
+        self.carve_corridor(first_room.center(), second_room.center(), rng);
+
+        if rng.random_bool(0.5) { first_room } else { second_room }
+    }
+
+    /// Room keeps 1 tile margin inside its leaf, so rooms of neighbour leaves never merge
+    fn carve_room_in<R: Rng + ?Sized>(&mut self, leaf: Room, rng: &mut R) -> Room {
+        let max_width = (leaf.width - 2).max(BSP_MIN_ROOM_SIZE);
+        let max_height = (leaf.height - 2).max(BSP_MIN_ROOM_SIZE);
+        let width = rng.random_range(BSP_MIN_ROOM_SIZE..=max_width);
+        let height = rng.random_range(BSP_MIN_ROOM_SIZE..=max_height);
+        let x = leaf.x + 1 + rng.random_range(0..=max_width - width);
+        let y = leaf.y + 1 + rng.random_range(0..=max_height - height);
+
+        let room = Room { x, y, width, height };
+        for iy in y..y + height {
+            for ix in x..x + width {
+                self.set_floor(ix, iy);
+            }
+        }
+        room
+    }
+
+    /// L-shaped, bend side is random
+    fn carve_corridor<R: Rng + ?Sized>(&mut self, from: (u32, u32), to: (u32, u32), rng: &mut R) {
+        let corner = if rng.random_bool(0.5) { (to.0, from.1) } else { (from.0, to.1) };
+        for (start, end) in [(from, corner), (corner, to)] {
+            for iy in start.1.min(end.1)..=start.1.max(end.1) {
+                for ix in start.0.min(end.0)..=start.0.max(end.0) {
+                    self.set_floor(ix, iy);
+                }
+            }
+        }
+    }
+
+    fn generate_caves<R: Rng + ?Sized>(size: u32, rng: &mut R) -> Self {
+        for _ in 0..CAVES_MAX_ATTEMPTS {
+            let mut map = Self::filled(size);
+            for iy in 1..size - 1 {
+                for ix in 1..size - 1 {
+                    if !rng.random_bool(CAVES_INITIAL_WALLS_CHANCE) {
+                        map.set_floor(ix, iy);
+                    }
+                }
+            }
+
+            for _ in 0..CAVES_SMOOTHING_STEPS {
+                map = map.smoothed();
+            }
+            map.keep_largest_region();
+
+            let floor_count = map.cells.iter().filter(|cell| **cell == MapCell::Floor).count();
+            if floor_count as f32 >= map.cells.len() as f32 * CAVES_MIN_FLOOR_PART {
+                return map;
+            }
+        }
+
+        // Unlucky caves, rooms always have space
+        log::warn!("Could not grow caves big enough, generating rooms");
+        Self::generate_rooms(size, rng)
+    }
+
+    /// Tile becomes wall when most of its 8 neighbours are walls
+    fn smoothed(&self) -> Self {
+        let mut next = Self::filled(self.size);
+        for iy in 1..self.size - 1 {
+            for ix in 1..self.size - 1 {
+                let walls_around = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                    .filter(|(dx, dy)| (*dx, *dy) != (0, 0))
+                    .filter(|(dx, dy)| {
+                        let index = ((iy as i32 + dy) as u32 * self.size + (ix as i32 + dx) as u32) as usize;
+                        self.cells[index] != MapCell::Floor
+                    })
+                    .count();
+
+                if walls_around < 5 {
+                    next.set_floor(ix, iy);
+                }
+            }
+        }
+        next
+    }
+
+    /// Fill every floor region except biggest one
+    fn keep_largest_region(&mut self) {
+        let mut visited = vec![false; self.cells.len()];
+        let mut largest: Option<Vec<bool>> = None;
+        let mut largest_count = 0;
+
+        for start in 0..self.cells.len() {
+            if visited[start] || self.cells[start] != MapCell::Floor {
+                continue;
+            }
+
+            let region = self.flood_fill(start);
+            let count = region.iter().filter(|reached| **reached).count();
+            region.iter().enumerate()
+                .filter(|(_, reached)| **reached)
+                .for_each(|(index, _)| visited[index] = true);
+
+            if count > largest_count {
+                largest_count = count;
+                largest = Some(region);
+            }
+        }
+
+        let Some(largest) = largest else {
+            return;
+        };
+        for (index, cell) in self.cells.iter_mut().enumerate() {
+            if *cell == MapCell::Floor && !largest[index] {
+                *cell = MapCell::Blocked(ObstacleKind::Wall);
+            }
+        }
+    }
+
+    fn scatter_scenery<R: Rng + ?Sized>(&mut self, density: f32, rng: &mut R) {
+        let mut candidates: Vec<usize> = (0..self.cells.len())
+            .filter(|index| self.cells[*index] == MapCell::Floor)
+            .collect();
+        let expected_count = (candidates.len() as f32 * density) as usize;
+        candidates.shuffle(rng);
+
+        let mut placed_count = 0;
+        for index in candidates {
+            if placed_count >= expected_count {
+                break;
+            }
+
+            self.cells[index] = MapCell::Blocked(*SCENERY_KINDS.choose(rng).unwrap());
+            if self.is_connected() {
+                placed_count += 1;
+            } else {
+                // Would cut off part of map
+                self.cells[index] = MapCell::Floor;
+            }
+        }
+    }
+
+    /// Floor cells reachable from `start`
+    fn flood_fill(&self, start: usize) -> Vec<bool> {
+        let mut reached = vec![false; self.cells.len()];
+        let mut queue = VecDeque::from([start]);
+        reached[start] = true;
+
+        while let Some(index) = queue.pop_front() {
+            let tile = self.tile_of(index);
+            for (dx, dy) in NEIGHBOURS {
+                let neighbour = self.index_of(Vector2I::new(tile.x + dx, tile.y + dy));
+                if let Some(neighbour) = neighbour {
+                    if !reached[neighbour] && self.cells[neighbour] == MapCell::Floor {
+                        reached[neighbour] = true;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    fn touches_floor(&self, tile: Vector2I) -> bool {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Vector2I::new(tile.x + dx, tile.y + dy)))
+            .any(|neighbour| self.cell(neighbour) == Some(MapCell::Floor))
+    }
+
+    fn set_floor(&mut self, x: u32, y: u32) {
+        let index = (y * self.size + x) as usize;
+        self.cells[index] = MapCell::Floor;
+    }
+
+    fn half_size(&self) -> i32 {
+        (self.size / 2) as i32
+    }
+
+    fn tile_of(&self, index: usize) -> Vector2I {
+        let index = index as u32;
+        Vector2I::new(
+            (index % self.size) as i32 - self.half_size(),
+            (index / self.size) as i32 - self.half_size()
+        )
+    }
+
+    fn index_of(&self, tile: Vector2I) -> Option<usize> {
+        let x = tile.x + self.half_size();
+        let y = tile.y + self.half_size();
+        let size = self.size as i32;
+        (0..size).contains(&x)
+            .then_some(())
+            .filter(|_| (0..size).contains(&y))
+            .map(|_| (y * size + x) as usize)
+    }
+}
+
+impl MapLayout for GeneratedMap {
+    /// Blocked tiles not touching floor are skipped, nothing can reach them anyway
+    fn apply_to(&self, world: &mut World) -> Result<(), WorldError> {
+        for index in 0..self.cells.len() {
+            if let MapCell::Blocked(kind) = self.cells[index] {
+                let tile = self.tile_of(index);
+                if self.touches_floor(tile) {
+                    world.place_obstacle(get_tiled_vec(tile.x, tile.y), kind)?;
+                }
+            }
+        }
+        world.set_bounds(Some(self.bounds()));
+        world.set_points_of_interest(self.points_of_interest());
+        Ok(())
+    }
+
+    /// Whole square, its border is always blocked
+    fn bounds(&self) -> TileBounds {
+        let size = self.size as i32;
+        TileBounds::new(Vector2I::new(-self.half_size(), -self.half_size()), Vector2I::new(size, size))
+    }
+
+    fn floor_positions(&self) -> Vec<Vector2F> {
+        self.floor_tiles().into_iter()
+            .map(|tile| get_tiled_vec(tile.x, tile.y))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_generated_maps_are_connected() {
+        for style in [MapStyle::Rooms, MapStyle::Caves] {
+            for seed in 0..20 {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let map = GeneratedMap::generate(style, 40, 0.1, &mut rng);
+                assert!(map.is_connected(), "style={style:?}, seed={seed}");
+                assert!(map.floor_tiles().len() > 40, "style={style:?}, seed={seed}");
+                assert!(map.floor_tiles().into_iter().all(|tile| map.bounds().contains_tile(tile)), "style={style:?}, seed={seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_border_is_blocked() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let map = GeneratedMap::generate(MapStyle::Caves, 20, 0.0, &mut rng);
+        let half = map.size() as i32 / 2;
+        for i in -half..half {
+            for tile in [Vector2I::new(i, -half), Vector2I::new(i, half - 1), Vector2I::new(-half, i), Vector2I::new(half - 1, i)] {
+                assert!(matches!(map.cell(tile), Some(MapCell::Blocked(_))), "tile={tile}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_map() {
+        let generate = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            GeneratedMap::generate(MapStyle::Rooms, 32, 0.1, &mut rng).cells
+        };
+        assert_eq!(generate(5), generate(5));
+        assert_ne!(generate(5), generate(6));
+    }
+
+    #[test]
+    fn test_disconnected_map_is_detected() {
+        let mut map = GeneratedMap::filled(MIN_MAP_SIZE);
+        map.set_floor(2, 2);
+        assert!(map.is_connected());
+        map.set_floor(5, 5);
+        assert!(!map.is_connected());
+    }
+
+    #[test]
+    fn test_map_size_grows_with_hiders() {
+        assert_eq!(GeneratedMap::size_for(0, 5), MIN_MAP_SIZE);
+        assert_eq!(GeneratedMap::size_for(4, 5), 40);
+        assert_eq!(GeneratedMap::size_for(1000, 5), MAX_MAP_SIZE);
+    }
+}
@@ -0,0 +1,54 @@
+use super::{
+    math::{
+        Vector2F,
+        Vector2I
+    },
+    tile_layer::{
+        TileBounds,
+        TileLayer
+    },
+    world::{
+        World,
+        WorldError
+    }
+};
+
+/// Points of interest are picked from floor tiles on grid with that spacing, in tiles
+const POINTS_OF_INTEREST_SPACING: i32 = 6;
+
+/// Part of map where NPCs spawn, `density` is part of its free floor they take
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpcRegion {
+    pub positions: Vec<Vector2F>,
+    pub density: f32,
+}
+
+/// Map put into fresh world at round start, generated or loaded from file
+pub trait MapLayout: std::fmt::Debug {
+    /// Place obstacles of map into world and limit world to map bounds
+    fn apply_to(&self, world: &mut World) -> Result<(), WorldError>;
+
+    /// Play area, every floor tile is inside
+    fn bounds(&self) -> TileBounds;
+
+    /// World positions of floor tiles, all reachable from each other
+    fn floor_positions(&self) -> Vec<Vector2F>;
+
+    /// Candidates for player entities
+    fn player_spawn_positions(&self) -> Vec<Vector2F> {
+        self.floor_positions()
+    }
+
+    /// NPCs spread over whole floor if map has no regions
+    fn npc_regions(&self) -> Vec<NpcRegion> {
+        vec![]
+    }
+
+    /// Tiles NPCs walk between, spread evenly over floor by default
+    fn points_of_interest(&self) -> Vec<Vector2I> {
+        self.floor_positions().iter()
+            .map(TileLayer::tile_of)
+            .filter(|tile| tile.x.rem_euclid(POINTS_OF_INTEREST_SPACING) == 0 && tile.y.rem_euclid(POINTS_OF_INTEREST_SPACING) == 0)
+            .collect()
+    }
+}
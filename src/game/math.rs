@@ -168,6 +168,72 @@ where
             && point.x < self.pos.x + self.size.x
             && point.y < self.pos.y + self.size.y
     }
+
+    /// Overlap with positive area, rectangles touching by edge do not intersect
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.pos.x < other.pos.x + other.size.x
+            && other.pos.x < self.pos.x + self.size.x
+            && self.pos.y < other.pos.y + other.size.y
+            && other.pos.y < self.pos.y + self.size.y
+    }
+}
+
+/// First contact of moving rectangle with another one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Part of displacement done before contact, in 0.0..=1.0
+    pub time: f32,
+    /// Normal of the side which was hit, points against movement
+    pub normal: Vector2F,
+}
+
+impl Rect2F {
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Self) -> Self {
+        let min_x = self.pos.x.min(other.pos.x);
+        let min_y = self.pos.y.min(other.pos.y);
+        let max_x = (self.pos.x + self.size.x).max(other.pos.x + other.size.x);
+        let max_y = (self.pos.y + self.size.y).max(other.pos.y + other.size.y);
+        Self::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Swept AABB test of rectangle moved by `displacement` against still `other`.
+    /// `None` if it never touches `other` or already intersects it.
+    pub fn sweep(&self, displacement: Vector2F, other: &Self) -> Option<SweepHit> {
+        if self.intersects(other) {
+            return None;
+        }
+
+        // Times of entering and leaving `other` slab on one axis
+        fn axis_times(min: f32, size: f32, other_min: f32, other_size: f32, displacement: f32) -> Option<(f32, f32)> {
+            let max = min + size;
+            let other_max = other_min + other_size;
+            if displacement > 0.0 {
+                Some(((other_min - max) / displacement, (other_max - min) / displacement))
+            } else if displacement < 0.0 {
+                Some(((other_max - min) / displacement, (other_min - max) / displacement))
+            } else if max > other_min && min < other_max {
+                Some((f32::NEG_INFINITY, f32::INFINITY))
+            } else {
+                None
+            }
+        }
+
+        let (entry_x, exit_x) = axis_times(self.pos.x, self.size.x, other.pos.x, other.size.x, displacement.x)?;
+        let (entry_y, exit_y) = axis_times(self.pos.y, self.size.y, other.pos.y, other.size.y, displacement.y)?;
+        let entry = entry_x.max(entry_y);
+        let exit = exit_x.min(exit_y);
+        if entry > exit || !(0.0..=1.0).contains(&entry) {
+            return None;
+        }
+
+        let normal = if entry_x > entry_y {
+            Vector2F::new(-displacement.x.signum(), 0.0)
+        } else {
+            Vector2F::new(0.0, -displacement.y.signum())
+        };
+        Some(SweepHit { time: entry, normal })
+    }
 }
 
 #[test]
@@ -254,4 +320,33 @@ fn test_rect_containing() {
     assert!(!rect.contains(&p3_not_inside));
     assert!(!rect.contains(&p4_not_inside));
     assert!(rect.contains(&p5_inside));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_rect_intersection() {
+    let rect = Rect2F::new(0.0, 0.0, 2.0, 2.0);
+    assert!(rect.intersects(&Rect2F::new(1.0, 1.0, 2.0, 2.0)));
+    assert!(rect.intersects(&Rect2F::new(0.5, 0.5, 1.0, 1.0)));
+    assert!(!rect.intersects(&Rect2F::new(2.0, 0.0, 2.0, 2.0)));
+    assert!(!rect.intersects(&Rect2F::new(0.0, -3.0, 2.0, 2.0)));
+    assert_eq!(rect.union(&Rect2F::new(3.0, -1.0, 1.0, 1.0)), Rect2F::new(0.0, -1.0, 4.0, 3.0));
+}
+
+#[test]
+fn test_rect_sweep() {
+    let rect = Rect2F::new(0.0, 0.0, 1.0, 1.0);
+    let wall = Rect2F::new(3.0, -2.0, 1.0, 5.0);
+
+    let hit = rect.sweep(Vector2F::new(4.0, 1.0), &wall).unwrap();
+    assert_eq!(hit.time, 0.5);
+    assert_eq!(hit.normal, Vector2F::new(-1.0, 0.0));
+
+    // Too short, moving away, passing by and sliding along touching wall
+    assert!(rect.sweep(Vector2F::new(1.0, 0.0), &wall).is_none());
+    assert!(rect.sweep(Vector2F::new(-4.0, 0.0), &wall).is_none());
+    assert!(rect.sweep(Vector2F::new(4.0, 8.0), &wall).is_none());
+    let touching = Rect2F::new(2.0, 0.0, 1.0, 1.0);
+    assert!(touching.sweep(Vector2F::new(0.0, 3.0), &wall).is_none());
+    assert_eq!(touching.sweep(Vector2F::new(1.0, 1.0), &wall).map(|hit| hit.time), Some(0.0));
+}
+
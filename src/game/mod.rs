@@ -1,2 +1,10 @@
 pub mod world;
-pub mod math;
\ No newline at end of file
+pub mod math;
+pub mod tile_layer;
+pub mod map_generator;
+pub mod map_layout;
+pub mod map_file;
+pub mod spatial_index;
+pub mod pathfinding;
+pub mod npc_behavior;
+pub mod visibility;
\ No newline at end of file
@@ -0,0 +1,504 @@
+use std::{
+    collections::VecDeque,
+    time::Duration
+};
+
+use rand::{
+    seq::IndexedRandom,
+    Rng
+};
+use rand_chacha::ChaCha8Rng;
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use super::{
+    math::{
+        Vector2F,
+        Vector2I
+    },
+    pathfinding,
+    world::{
+        get_tiled_vec,
+        random_npc_direction_selection_delay,
+        EntityId,
+        MovementMode,
+        TILE_SIZE
+    }
+};
+
+/// Diagonal steps take longer and can not cut corners, world checks both
+const DIRECTIONS: [Vector2I; 8] = [
+    Vector2I { x: 1, y: 0 },
+    Vector2I { x: -1, y: 0 },
+    Vector2I { x: 0, y: 1 },
+    Vector2I { x: 0, y: -1 },
+    Vector2I { x: 1, y: 1 },
+    Vector2I { x: -1, y: 1 },
+    Vector2I { x: 1, y: -1 },
+    Vector2I { x: -1, y: -1 },
+];
+
+/// Players hold arrow key or press it again right after arriving
+const STEP_IN_RUN_MILLIS: std::ops::Range<u64> = 0..120;
+/// Players stop to look around between runs
+const PAUSE_BETWEEN_RUNS_MILLIS: std::ops::Range<u64> = 300..2500;
+/// Wanderers turn back home when they get further than that, in tiles
+const WANDER_RADIUS: i32 = 8;
+/// Followers pick target from entities that close, in tiles
+const FOLLOW_RADIUS: i32 = 10;
+/// Followers stop when they get that close to target, in tiles
+const FOLLOW_DISTANCE: i32 = 3;
+/// Path walkers without points of interest walk to random tiles that far, in tiles
+const WALK_RADIUS: i32 = 8;
+
+/// Chebyshev distance, diagonal step counts as one like in pathfinding
+fn tiles_distance(a: Vector2I, b: Vector2I) -> i32 {
+    (a.x - b.x).abs().max((a.y - b.y).abs())
+}
+
+fn random_millis(rng: &mut ChaCha8Rng, range: std::ops::Range<u64>) -> Duration {
+    Duration::from_millis(rng.random_range(range))
+}
+
+/// Unit vector at any angle, so walking NPCs do not end on tile grid
+fn random_direction(rng: &mut ChaCha8Rng) -> Vector2F {
+    let angle = rng.random_range(0.0..std::f32::consts::TAU);
+    Vector2F::new(angle.cos(), angle.sin())
+}
+
+fn tiles_offset(from: Vector2I, to: Vector2I) -> Vector2F {
+    Vector2F::new((to.x - from.x) as f32, (to.y - from.y) as f32)
+}
+
+/// What NPC does once its delay ran out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NpcAction {
+    /// Move to adjacent tile, world checks it is still free
+    Step(Vector2I),
+    /// Walk in unit `direction` for time it takes to cross `tiles`, stopping anywhere.
+    /// Free movement mode only, world resolves collisions like for players.
+    Walk { direction: Vector2F, tiles: f32 },
+    Wait,
+}
+
+/// World as seen by deciding NPC
+pub struct NpcContext<'a> {
+    pub entity_id: EntityId,
+    pub tile: Vector2I,
+    pub spawn_tile: Vector2I,
+    /// Units, limit of `Roamer`
+    pub roaming_range: Option<f32>,
+    /// Tiles of all entities at tick start, ordered by id
+    pub entities: &'a [(EntityId, Vector2I)],
+    pub points_of_interest: &'a [Vector2I],
+    /// Inside bounds, without obstacle and not reserved by other entity
+    pub is_walkable: &'a dyn Fn(Vector2I) -> bool,
+    /// Entities taking tiles of square with given half side around tile, looked up in spatial index
+    pub entities_around: &'a dyn Fn(Vector2I, i32) -> Vec<EntityId>,
+    /// Wandering behaviors walk instead of stepping in free mode, path followers keep stepping
+    pub movement_mode: MovementMode,
+}
+
+impl NpcContext<'_> {
+    fn entity_tile(&self, entity_id: EntityId) -> Option<Vector2I> {
+        self.entities.binary_search_by_key(&entity_id, |(id, _)| *id)
+            .ok()
+            .map(|index| self.entities[index].1)
+    }
+
+    /// Other entities at most `radius` tiles away, ordered by id
+    fn entities_near(&self, radius: i32) -> Vec<(EntityId, Vector2I)> {
+        (self.entities_around)(self.tile, radius).into_iter()
+            .filter(|id| *id != self.entity_id)
+            .filter_map(|id| self.entity_tile(id).map(|tile| (id, tile)))
+            .filter(|(_, tile)| tiles_distance(*tile, self.tile) <= radius)
+            .collect()
+    }
+
+    /// Path to `goal`, which may be taken by entity NPC walks to
+    fn path_to(&self, goal: Vector2I) -> Option<VecDeque<Vector2I>> {
+        pathfinding::find_path(self.tile, goal, |tile| tile == goal || (self.is_walkable)(tile))
+    }
+}
+
+/// Decides where idle NPC goes next
+pub trait NpcBehavior {
+    /// Called when NPC is idle and its delay ran out. Returned delay counts down
+    /// only while NPC is idle, so for step or walk it is the pause after arriving.
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration);
+
+    /// World did not let NPC take the step, tile was taken in the meantime
+    fn step_rejected(&mut self) {}
+}
+
+/// Original behavior, random single steps around spawnpoint within roaming range
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Roamer;
+
+/// Walks in runs of several steps with pauses between, slowly drifts away from spawn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Wanderer {
+    direction: Option<Vector2I>,
+    steps_left: u32,
+}
+
+/// Trails other entity, players and NPCs alike, keeping some distance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Follower {
+    target: Option<EntityId>,
+    decisions_left: u32,
+    path: VecDeque<Vector2I>,
+}
+
+/// Mostly stands, sometimes shuffles a step and comes back
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Idler;
+
+/// Walks between points of interest, lingering at each
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathWalker {
+    path: VecDeque<Vector2I>,
+}
+
+/// Behavior with its state, saved with world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NpcPersonality {
+    Roamer(Roamer),
+    Wanderer(Wanderer),
+    Follower(Follower),
+    Idler(Idler),
+    PathWalker(PathWalker),
+}
+
+impl Default for NpcPersonality {
+    fn default() -> Self {
+        Self::Roamer(Roamer)
+    }
+}
+
+impl NpcPersonality {
+    pub fn wanderer() -> Self {
+        Self::Wanderer(Wanderer::default())
+    }
+
+    pub fn follower() -> Self {
+        Self::Follower(Follower::default())
+    }
+
+    pub fn idler() -> Self {
+        Self::Idler(Idler)
+    }
+
+    pub fn path_walker() -> Self {
+        Self::PathWalker(PathWalker::default())
+    }
+
+    pub fn behavior_mut(&mut self) -> &mut dyn NpcBehavior {
+        match self {
+            Self::Roamer(roamer) => roamer,
+            Self::Wanderer(wanderer) => wanderer,
+            Self::Follower(follower) => follower,
+            Self::Idler(idler) => idler,
+            Self::PathWalker(path_walker) => path_walker,
+        }
+    }
+}
+
+impl NpcBehavior for Roamer {
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration) {
+        let Some(range) = context.roaming_range else {
+            return (NpcAction::Wait, Duration::MAX);
+        };
+
+        let spawnpoint = get_tiled_vec(context.spawn_tile.x, context.spawn_tile.y);
+        let (action, end) = match context.movement_mode {
+            MovementMode::Tiles => {
+                let next_tile = context.tile + *DIRECTIONS.choose(rng).unwrap();
+                (NpcAction::Step(next_tile), get_tiled_vec(next_tile.x, next_tile.y))
+            },
+            MovementMode::Free => {
+                let direction = random_direction(rng);
+                let end = get_tiled_vec(context.tile.x, context.tile.y) + direction * TILE_SIZE;
+                (NpcAction::Walk { direction, tiles: 1.0 }, end)
+            },
+        };
+        if (end - spawnpoint).length_squared() > range.powi(2) {
+            // Try another direction next tick
+            return (NpcAction::Wait, Duration::ZERO);
+        }
+        (action, random_npc_direction_selection_delay(rng))
+    }
+}
+
+impl NpcBehavior for Wanderer {
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration) {
+        let direction = match self.direction {
+            Some(direction) if self.steps_left > 0 => direction,
+            previous_direction => {
+                // New run, never straight back where it came from, heading home when too far
+                let home_distance = tiles_distance(context.tile, context.spawn_tile);
+                let candidates: Vec<Vector2I> = DIRECTIONS.iter()
+                    .copied()
+                    .filter(|direction| previous_direction.is_none_or(|previous| *direction != -previous))
+                    .filter(|direction| {
+                        let next_home_distance = tiles_distance(context.tile + *direction, context.spawn_tile);
+                        next_home_distance <= WANDER_RADIUS || next_home_distance < home_distance
+                    })
+                    .collect();
+                self.direction = candidates.choose(rng).copied().or(previous_direction.map(|previous| -previous));
+                self.steps_left = rng.random_range(1..=6);
+                return (NpcAction::Wait, random_millis(rng, PAUSE_BETWEEN_RUNS_MILLIS));
+            },
+        };
+
+        if context.movement_mode == MovementMode::Free {
+            // Whole run at once, ending somewhere between tiles
+            let tiles = self.steps_left as f32 - rng.random_range(0.0..1.0);
+            self.steps_left = 0;
+            let direction = tiles_offset(Vector2I::zero(), direction).normal();
+            return (NpcAction::Walk { direction, tiles }, random_millis(rng, STEP_IN_RUN_MILLIS));
+        }
+
+        let next_tile = context.tile + direction;
+        if !(context.is_walkable)(next_tile) {
+            self.steps_left = 0;
+            return (NpcAction::Wait, random_millis(rng, STEP_IN_RUN_MILLIS));
+        }
+        self.steps_left -= 1;
+        (NpcAction::Step(next_tile), random_millis(rng, STEP_IN_RUN_MILLIS))
+    }
+
+    fn step_rejected(&mut self) {
+        self.steps_left = 0;
+    }
+}
+
+impl NpcBehavior for Follower {
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration) {
+        let target_tile = self.target.and_then(|target| context.entity_tile(target));
+        let target_tile = match target_tile {
+            Some(tile) if self.decisions_left > 0 => tile,
+            _ => {
+                let candidates = context.entities_near(FOLLOW_RADIUS);
+                let chosen = candidates.choose(rng).copied();
+                self.target = chosen.map(|(id, _)| id);
+                self.decisions_left = rng.random_range(20..60);
+                self.path.clear();
+                return (NpcAction::Wait, random_millis(rng, PAUSE_BETWEEN_RUNS_MILLIS));
+            },
+        };
+        self.decisions_left -= 1;
+
+        if tiles_distance(context.tile, target_tile) <= FOLLOW_DISTANCE {
+            self.path.clear();
+            return (NpcAction::Wait, random_millis(rng, PAUSE_BETWEEN_RUNS_MILLIS));
+        }
+
+        // Target moved away from end of planned path
+        let path_is_stale = self.path.back().is_none_or(|end| tiles_distance(*end, target_tile) > 2);
+        if path_is_stale {
+            self.path = context.path_to(target_tile).unwrap_or_default();
+            self.path.pop_back();
+        }
+
+        match self.path.pop_front() {
+            Some(next_tile) => (NpcAction::Step(next_tile), random_millis(rng, STEP_IN_RUN_MILLIS)),
+            None => (NpcAction::Wait, random_millis(rng, PAUSE_BETWEEN_RUNS_MILLIS)),
+        }
+    }
+
+    fn step_rejected(&mut self) {
+        self.path.clear();
+    }
+}
+
+impl NpcBehavior for Idler {
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration) {
+        let idle_delay = random_millis(rng, 1500..6000);
+        if context.movement_mode == MovementMode::Free {
+            if context.tile != context.spawn_tile {
+                let back = tiles_offset(context.tile, context.spawn_tile);
+                return (NpcAction::Walk { direction: back.normal(), tiles: back.length() }, idle_delay);
+            } else if rng.random_bool(0.3) {
+                return (NpcAction::Walk { direction: random_direction(rng), tiles: rng.random_range(0.2..1.0) }, idle_delay);
+            }
+            return (NpcAction::Wait, idle_delay);
+        }
+
+        if context.tile != context.spawn_tile {
+            // Shuffle back to favourite spot
+            let back = context.path_to(context.spawn_tile).and_then(|mut path| path.pop_front());
+            if let Some(next_tile) = back.filter(|tile| (context.is_walkable)(*tile)) {
+                return (NpcAction::Step(next_tile), idle_delay);
+            }
+        } else if rng.random_bool(0.3) {
+            let next_tile = context.tile + *DIRECTIONS.choose(rng).unwrap();
+            return (NpcAction::Step(next_tile), idle_delay);
+        }
+        (NpcAction::Wait, idle_delay)
+    }
+}
+
+impl NpcBehavior for PathWalker {
+    fn decide(&mut self, context: &NpcContext, rng: &mut ChaCha8Rng) -> (NpcAction, Duration) {
+        if let Some(next_tile) = self.path.pop_front() {
+            // Pause a little at the end of the path
+            let delay = if self.path.is_empty() {
+                random_millis(rng, 1000..4000)
+            } else {
+                random_millis(rng, STEP_IN_RUN_MILLIS)
+            };
+            return (NpcAction::Step(next_tile), delay);
+        }
+
+        let nearby_points: Vec<Vector2I> = context.points_of_interest.iter()
+            .copied()
+            .filter(|point| *point != context.tile && tiles_distance(*point, context.tile) <= WALK_RADIUS * 3)
+            .collect();
+        let goal = nearby_points.choose(rng).copied().unwrap_or_else(|| {
+            context.tile + Vector2I::new(rng.random_range(-WALK_RADIUS..=WALK_RADIUS), rng.random_range(-WALK_RADIUS..=WALK_RADIUS))
+        });
+
+        if (context.is_walkable)(goal) {
+            self.path = context.path_to(goal).unwrap_or_default();
+        }
+        (NpcAction::Wait, random_millis(rng, PAUSE_BETWEEN_RUNS_MILLIS))
+    }
+
+    fn step_rejected(&mut self) {
+        self.path.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::game::{
+        tile_layer::TileBounds,
+        world::{
+            World,
+            WorldSettings,
+            ENTITY_SIZE,
+            TILE_SIZE
+        }
+    };
+
+    const AREA_SIDE: i32 = 30;
+    const TICK: Duration = Duration::from_millis(33);
+
+    fn open_world(seed: u64) -> World {
+        let mut world = World::with_seed(WorldSettings::default(), seed);
+        world.set_bounds(Some(TileBounds::new(Vector2I::new(0, 0), Vector2I::new(AREA_SIDE, AREA_SIDE))));
+        world.set_points_of_interest((0..AREA_SIDE).step_by(6)
+            .flat_map(|y| (0..AREA_SIDE).step_by(6).map(move |x| Vector2I::new(x, y)))
+            .collect());
+        world
+    }
+
+    fn entity_tile(world: &World, entity_id: EntityId) -> Vector2I {
+        let position = world.get_entity_by_id(entity_id).unwrap().position;
+        Vector2I::new((position.x / TILE_SIZE).round() as i32, (position.y / TILE_SIZE).round() as i32)
+    }
+
+    /// Part of time NPCs spend moving and their mean distance from spawn in tiles after minute
+    fn movement_statistics(personality: fn() -> NpcPersonality, seed: u64) -> (f32, f32) {
+        let mut world = open_world(seed);
+        let spawns: Vec<Vector2I> = (0..36).map(|i| Vector2I::new(2 + (i % 6) * 5, 2 + (i / 6) * 5)).collect();
+        let npcs: Vec<EntityId> = spawns.iter()
+            .map(|spawn| world.create_entity_npc_with_personality("NPC", get_tiled_vec(spawn.x, spawn.y), ENTITY_SIZE, personality()))
+            .collect();
+
+        let ticks = 30 * 60;
+        let mut moving_ticks = 0;
+        for _ in 0..ticks {
+            world.tick(TICK);
+            moving_ticks += world.iter_entities().filter(|e| e.is_moving()).count();
+        }
+        let distance: i32 = npcs.iter().zip(&spawns)
+            .map(|(npc, spawn)| tiles_distance(entity_tile(&world, *npc), *spawn))
+            .sum();
+        (moving_ticks as f32 / (ticks * npcs.len()) as f32, distance as f32 / npcs.len() as f32)
+    }
+
+    #[test]
+    fn test_personalities_movement_statistics() {
+        let (roamer_moving, roamer_distance) = movement_statistics(NpcPersonality::default, 1);
+        let (wanderer_moving, wanderer_distance) = movement_statistics(NpcPersonality::wanderer, 1);
+        let (idler_moving, idler_distance) = movement_statistics(NpcPersonality::idler, 1);
+        let (walker_moving, walker_distance) = movement_statistics(NpcPersonality::path_walker, 1);
+
+        // Roamers never leave roaming range, the rest explore map like players do
+        assert!(roamer_distance <= 3.0, "{roamer_distance}");
+        assert!(wanderer_distance > 3.0, "{wanderer_distance}");
+        assert!(walker_distance > 6.0, "{walker_distance}");
+        assert!(idler_distance < 1.0, "{idler_distance}");
+
+        // Walking with pauses, nobody walks all the time
+        for moving in [roamer_moving, wanderer_moving, walker_moving] {
+            assert!((0.1..0.6).contains(&moving), "{moving}");
+        }
+        assert!(idler_moving < 0.1, "{idler_moving}");
+
+        assert_eq!(movement_statistics(NpcPersonality::wanderer, 1), (wanderer_moving, wanderer_distance));
+    }
+
+    #[test]
+    fn test_follower_trails_player() {
+        let mut world = open_world(4);
+        let player = world.create_entity_player("Player", get_tiled_vec(5, 5), ENTITY_SIZE);
+        let follower = world.create_entity_npc_with_personality("Follower", get_tiled_vec(5, 12), ENTITY_SIZE, NpcPersonality::follower());
+
+        world.set_entity_path_to(player, get_tiled_vec(20, 5)).unwrap();
+        for _ in 0..30 * 10 {
+            world.tick(TICK);
+        }
+        assert_eq!(entity_tile(&world, player), Vector2I::new(20, 5));
+        let distance = tiles_distance(entity_tile(&world, follower), Vector2I::new(20, 5));
+        assert!(distance <= FOLLOW_DISTANCE, "Follower stayed {distance} tiles behind");
+    }
+
+    #[test]
+    fn test_follower_notices_diagonal_entities() {
+        // Too far by Manhattan distance, within radius by diagonal steps
+        let mut world = open_world(4);
+        world.create_entity_player("Player", get_tiled_vec(5, 5), ENTITY_SIZE);
+        let follower = world.create_entity_npc_with_personality("Follower", get_tiled_vec(5 + FOLLOW_RADIUS, 5 + FOLLOW_RADIUS), ENTITY_SIZE, NpcPersonality::follower());
+
+        for _ in 0..30 * 10 {
+            world.tick(TICK);
+        }
+        let distance = tiles_distance(entity_tile(&world, follower), Vector2I::new(5, 5));
+        assert!(distance <= FOLLOW_DISTANCE, "Follower stayed {distance} tiles away");
+    }
+
+    #[test]
+    fn test_follower_ignores_far_entities() {
+        let mut world = open_world(4);
+        world.create_entity_player("Player", get_tiled_vec(5, 5), ENTITY_SIZE);
+        let follower = world.create_entity_npc_with_personality("Follower", get_tiled_vec(5, 5 + FOLLOW_RADIUS + 1), ENTITY_SIZE, NpcPersonality::follower());
+
+        for _ in 0..30 * 10 {
+            world.tick(TICK);
+        }
+        assert_eq!(entity_tile(&world, follower), Vector2I::new(5, 5 + FOLLOW_RADIUS + 1));
+    }
+
+    #[test]
+    fn test_path_walker_reaches_point_of_interest() {
+        let mut world = open_world(2);
+        world.set_points_of_interest(vec![Vector2I::new(15, 15)]);
+        let walker = world.create_entity_npc_with_personality("Walker", get_tiled_vec(5, 5), ENTITY_SIZE, NpcPersonality::path_walker());
+
+        let mut visited = false;
+        for _ in 0..30 * 10 {
+            world.tick(TICK);
+            visited |= entity_tile(&world, walker) == Vector2I::new(15, 15);
+        }
+        assert!(visited);
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    cmp::Reverse,
+    collections::{
+        BinaryHeap,
+        HashMap,
+        VecDeque
+    }
+};
+
+use super::math::Vector2I;
+
+/// Search gives up after visiting that many tiles, unbounded worlds have no other limit
+pub const MAX_SEARCHED_TILES: usize = 10_000;
+
+const NEIGHBOURS: [Vector2I; 8] = [
+    Vector2I { x: 1, y: 0 },
+    Vector2I { x: -1, y: 0 },
+    Vector2I { x: 0, y: 1 },
+    Vector2I { x: 0, y: -1 },
+    Vector2I { x: 1, y: 1 },
+    Vector2I { x: -1, y: 1 },
+    Vector2I { x: 1, y: -1 },
+    Vector2I { x: -1, y: -1 },
+];
+
+/// Step costs scaled by 10, diagonal step is sqrt(2) longer
+const STRAIGHT_COST: i32 = 10;
+const DIAGONAL_COST: i32 = 14;
+
+/// Cost of shortest path on open grid
+fn octile_distance(a: Vector2I, b: Vector2I) -> i32 {
+    let dx = (a.x - b.x).abs();
+    let dy = (a.y - b.y).abs();
+    STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+}
+
+/// Tiles entity takes besides its own while stepping, diagonal step cuts both corner tiles
+pub fn step_tiles(from_tile: Vector2I, to_tile: Vector2I) -> Vec<Vector2I> {
+    let offset = to_tile - from_tile;
+    if offset.x.abs() == 1 && offset.y.abs() == 1 {
+        vec![to_tile, Vector2I::new(to_tile.x, from_tile.y), Vector2I::new(from_tile.x, to_tile.y)]
+    } else {
+        vec![to_tile]
+    }
+}
+
+/// A* over tile grid with 8 neighbours, diagonal step needs both corner tiles walkable.
+/// Path starts with first step after `start` and ends with `goal`, empty when `start` is `goal`.
+/// Start tile does not have to be walkable.
+pub fn find_path(start: Vector2I, goal: Vector2I, is_walkable: impl Fn(Vector2I) -> bool) -> Option<VecDeque<Vector2I>> {
+    if start == goal {
+        return Some(VecDeque::new());
+    }
+    if !is_walkable(goal) {
+        return None;
+    }
+
+    // Ties broken by lower heuristic, then by tile, so the same grid always gives the same path
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Vector2I, Vector2I> = HashMap::new();
+    let mut cost: HashMap<Vector2I, i32> = HashMap::from([(start, 0)]);
+    open.push(Reverse((octile_distance(start, goal), octile_distance(start, goal), start)));
+
+    let mut searched_tiles = 0;
+    while let Some(Reverse((_, _, tile))) = open.pop() {
+        if tile == goal {
+            let mut path = VecDeque::from([goal]);
+            let mut current = goal;
+            while let Some(previous) = came_from.get(&current).copied().filter(|previous| *previous != start) {
+                path.push_front(previous);
+                current = previous;
+            }
+            return Some(path);
+        }
+
+        searched_tiles += 1;
+        if searched_tiles > MAX_SEARCHED_TILES {
+            return None;
+        }
+
+        for offset in NEIGHBOURS {
+            let neighbour = tile + offset;
+            let step_cost = if offset.x != 0 && offset.y != 0 { DIAGONAL_COST } else { STRAIGHT_COST };
+            let next_cost = cost[&tile] + step_cost;
+            if !step_tiles(tile, neighbour).into_iter().all(&is_walkable) || cost.get(&neighbour).is_some_and(|known| *known <= next_cost) {
+                continue;
+            }
+
+            cost.insert(neighbour, next_cost);
+            came_from.insert(neighbour, tile);
+            let heuristic = octile_distance(neighbour, goal);
+            open.push(Reverse((next_cost + heuristic, heuristic, neighbour)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_goes_around_wall() {
+        // Wall at x = 1 from y = -2 to y = 2
+        let is_walkable = |tile: Vector2I| !(tile.x == 1 && (-2..=2).contains(&tile.y));
+        let path = find_path(Vector2I::new(0, 0), Vector2I::new(2, 0), is_walkable).unwrap();
+
+        assert_eq!(path.len(), 2 + 3 + 3);
+        assert_eq!(path.back(), Some(&Vector2I::new(2, 0)));
+        assert!(path.iter().all(|tile| is_walkable(*tile)));
+        let mut previous = Vector2I::new(0, 0);
+        for tile in path {
+            assert_eq!(octile_distance(previous, tile), STRAIGHT_COST);
+            previous = tile;
+        }
+    }
+
+    #[test]
+    fn test_path_goes_diagonally_without_cutting_corners() {
+        let path = find_path(Vector2I::new(0, 0), Vector2I::new(3, 2), |_| true).unwrap();
+        assert_eq!(path.len(), 3);
+
+        // Corner of rock at (1, 0) can not be cut
+        let is_walkable = |tile: Vector2I| tile != Vector2I::new(1, 0);
+        let path = find_path(Vector2I::new(0, 0), Vector2I::new(2, 1), is_walkable).unwrap();
+        assert_eq!(path, VecDeque::from([Vector2I::new(0, 1), Vector2I::new(1, 1), Vector2I::new(2, 1)]));
+
+        let path = find_path(Vector2I::new(0, 0), Vector2I::new(4, 4), is_walkable).unwrap();
+        assert_eq!(path.len(), 5);
+        let mut previous = Vector2I::new(0, 0);
+        for tile in path {
+            assert!(step_tiles(previous, tile).into_iter().all(is_walkable));
+            previous = tile;
+        }
+    }
+
+    #[test]
+    fn test_no_path_to_enclosed_goal() {
+        let goal = Vector2I::new(5, 5);
+        let is_walkable = |tile: Vector2I| tile == goal || octile_distance(tile, goal) > DIAGONAL_COST;
+        assert!(find_path(Vector2I::new(0, 0), goal, is_walkable).is_none());
+        assert!(find_path(Vector2I::new(0, 0), Vector2I::new(1, 0), |_| false).is_none());
+        assert_eq!(find_path(goal, goal, |_| false), Some(VecDeque::new()));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use super::{
+    math::Vector2I,
+    world::EntityId
+};
+
+/// Entities indexed by tiles they take, kept in sync by `World` on every entity change.
+/// Moving entity holds reservation of both its source and destination tile.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    tiles: HashMap<Vector2I, Vec<EntityId>>,
+}
+
+impl SpatialIndex {
+    pub fn insert(&mut self, entity_id: EntityId, tiles: &[Vector2I]) {
+        for tile in tiles {
+            let entities = self.tiles.entry(*tile).or_default();
+            if !entities.contains(&entity_id) {
+                entities.push(entity_id);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, entity_id: EntityId, tiles: &[Vector2I]) {
+        for tile in tiles {
+            if let Some(entities) = self.tiles.get_mut(tile) {
+                entities.retain(|id| *id != entity_id);
+                if entities.is_empty() {
+                    self.tiles.remove(tile);
+                }
+            }
+        }
+    }
+
+    /// Claims free tile for entity, nothing can take it between the check and the claim
+    pub fn try_reserve(&mut self, entity_id: EntityId, tile: Vector2I) -> bool {
+        if self.is_occupied(tile) {
+            return false;
+        }
+        self.tiles.insert(tile, vec![entity_id]);
+        true
+    }
+
+    /// Claims all tiles or none of them, tiles already held by the entity count as free
+    pub fn try_reserve_all(&mut self, entity_id: EntityId, tiles: &[Vector2I]) -> bool {
+        if tiles.iter().any(|tile| self.entities_at(*tile).iter().any(|id| *id != entity_id)) {
+            return false;
+        }
+        self.insert(entity_id, tiles);
+        true
+    }
+
+    /// Tile stays taken if other entity holds it too
+    pub fn release(&mut self, entity_id: EntityId, tile: Vector2I) {
+        self.remove(entity_id, &[tile]);
+    }
+
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+    }
+
+    pub fn is_occupied(&self, tile: Vector2I) -> bool {
+        self.tiles.contains_key(&tile)
+    }
+
+    pub fn entities_at(&self, tile: Vector2I) -> &[EntityId] {
+        self.tiles.get(&tile).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Entities taking any tile of square around `center`, each listed once
+    pub fn entities_around(&self, center: Vector2I, tiles_range: i32) -> Vec<EntityId> {
+        let mut entities: Vec<EntityId> = (-tiles_range..=tiles_range)
+            .flat_map(|y| (-tiles_range..=tiles_range).map(move |x| Vector2I::new(center.x + x, center.y + y)))
+            .flat_map(|tile| self.entities_at(tile).iter().copied())
+            .collect();
+        entities.sort_unstable();
+        entities.dedup();
+        entities
+    }
+
+    /// Number of occupied tiles
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_on_two_tiles_listed_once() {
+        let mut index = SpatialIndex::default();
+        index.insert(1, &[Vector2I::new(0, 0), Vector2I::new(1, 0)]);
+        index.insert(2, &[Vector2I::new(3, 3)]);
+
+        assert_eq!(index.entities_around(Vector2I::new(0, 0), 1), vec![1]);
+        assert_eq!(index.entities_around(Vector2I::new(2, 2), 1), vec![2]);
+        assert_eq!(index.entities_around(Vector2I::new(1, 1), 2), vec![1, 2]);
+
+        index.remove(1, &[Vector2I::new(0, 0)]);
+        assert!(!index.is_occupied(Vector2I::new(0, 0)));
+        assert_eq!(index.entities_at(Vector2I::new(1, 0)), &[1]);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_reserved_tile_can_not_be_claimed_again() {
+        let mut index = SpatialIndex::default();
+        assert!(index.try_reserve(1, Vector2I::new(2, 0)));
+        assert!(!index.try_reserve(2, Vector2I::new(2, 0)));
+        assert!(!index.try_reserve(1, Vector2I::new(2, 0)));
+
+        index.release(1, Vector2I::new(2, 0));
+        assert!(index.try_reserve(2, Vector2I::new(2, 0)));
+        assert_eq!(index.entities_at(Vector2I::new(2, 0)), &[2]);
+    }
+}
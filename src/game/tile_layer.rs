@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize,
+    Serialize
+};
+
+use super::{
+    math::{
+        Rect2F,
+        Vector2F,
+        Vector2I
+    },
+    world::{
+        get_tiled_vec,
+        TILE_SIZE
+    }
+};
+
+/// Static scenery blocking whole tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ObstacleKind {
+    Wall,
+    Rock,
+    Crate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Obstacle {
+    pub tile: Vector2I,
+    pub kind: ObstacleKind,
+}
+
+/// Rectangle of tiles entities can stand on, `min` is bottom left tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TileBounds {
+    pub min: Vector2I,
+    pub size: Vector2I,
+}
+
+/// Obstacles indexed by tile coordinates, serialized as list ordered by tile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<Obstacle>", into = "Vec<Obstacle>")]
+pub struct TileLayer {
+    obstacles: HashMap<Vector2I, ObstacleKind>,
+}
+
+impl Obstacle {
+    /// World position of tile bottom left corner, same as entities standing on it
+    pub fn position(&self) -> Vector2F {
+        get_tiled_vec(self.tile.x, self.tile.y)
+    }
+}
+
+impl TileBounds {
+    pub fn new(min: Vector2I, size: Vector2I) -> Self {
+        Self { min, size }
+    }
+
+    pub fn contains_tile(&self, tile: Vector2I) -> bool {
+        tile.x >= self.min.x
+            && tile.y >= self.min.y
+            && tile.x < self.min.x + self.size.x
+            && tile.y < self.min.y + self.size.y
+    }
+
+    /// Checks tile containing given world position
+    pub fn contains(&self, position: &Vector2F) -> bool {
+        self.contains_tile(TileLayer::tile_of(position))
+    }
+
+    /// Play area in world units
+    pub fn rect(&self) -> Rect2F {
+        let pos = get_tiled_vec(self.min.x, self.min.y);
+        let size = get_tiled_vec(self.size.x, self.size.y);
+        Rect2F::new(pos.x, pos.y, size.x, size.y)
+    }
+}
+
+impl TileLayer {
+    /// Tile containing given world position
+    pub fn tile_of(position: &Vector2F) -> Vector2I {
+        Vector2I::new(
+            (position.x / TILE_SIZE).floor() as i32,
+            (position.y / TILE_SIZE).floor() as i32
+        )
+    }
+
+    /// Replaces obstacle already placed on the tile
+    pub fn set(&mut self, tile: Vector2I, kind: ObstacleKind) {
+        self.obstacles.insert(tile, kind);
+    }
+
+    pub fn remove(&mut self, tile: Vector2I) -> Option<ObstacleKind> {
+        self.obstacles.remove(&tile)
+    }
+
+    pub fn get(&self, tile: Vector2I) -> Option<ObstacleKind> {
+        self.obstacles.get(&tile).copied()
+    }
+
+    pub fn is_blocked(&self, position: &Vector2F) -> bool {
+        self.obstacles.contains_key(&Self::tile_of(position))
+    }
+
+    pub fn len(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+
+    /// Ordered by tile, same layer always gives the same list
+    pub fn obstacles(&self) -> Vec<Obstacle> {
+        let mut obstacles: Vec<Obstacle> = self.obstacles.iter()
+            .map(|(tile, kind)| Obstacle { tile: *tile, kind: *kind })
+            .collect();
+        obstacles.sort_by_key(|obstacle| obstacle.tile);
+        obstacles
+    }
+}
+
+impl From<Vec<Obstacle>> for TileLayer {
+    fn from(value: Vec<Obstacle>) -> Self {
+        Self {
+            obstacles: value.into_iter()
+                .map(|obstacle| (obstacle.tile, obstacle.kind))
+                .collect()
+        }
+    }
+}
+
+impl From<TileLayer> for Vec<Obstacle> {
+    fn from(value: TileLayer) -> Self {
+        value.obstacles()
+    }
+}
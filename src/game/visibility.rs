@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use super::math::Vector2I;
+
+/// Tiles seen from one tile, computed by symmetric shadowcasting over tile grid
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sight {
+    tiles: HashSet<Vector2I>,
+}
+
+/// Row of quadrant `depth` tiles away from origin, seen between two slopes
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    depth: i32,
+    start_slope: f32,
+    end_slope: f32,
+}
+
+impl Row {
+    fn columns(&self) -> std::ops::RangeInclusive<i32> {
+        // Ties rounded towards the row center, so walls do not leak through corners
+        let min_column = (self.depth as f32 * self.start_slope + 0.5).floor() as i32;
+        let max_column = (self.depth as f32 * self.end_slope - 0.5).ceil() as i32;
+        min_column..=max_column
+    }
+
+    fn next(&self) -> Self {
+        Self { depth: self.depth + 1, ..*self }
+    }
+
+    /// Floor tile is seen only if origin is seen from it too
+    fn is_symmetric(&self, column: i32) -> bool {
+        column as f32 >= self.depth as f32 * self.start_slope && column as f32 <= self.depth as f32 * self.end_slope
+    }
+}
+
+/// Slope of left edge of tile
+fn slope(depth: i32, column: i32) -> f32 {
+    (2 * column - 1) as f32 / (2 * depth) as f32
+}
+
+impl Sight {
+    /// Tiles at most `radius` tiles away from `origin` and not hidden behind opaque tiles.
+    /// Opaque tiles are seen themselves, origin is always seen.
+    pub fn compute(origin: Vector2I, radius: u32, is_opaque: impl Fn(Vector2I) -> bool) -> Self {
+        // Squared distances overflow i32 for large radius, fit u64 for any `u32`
+        let radius = u64::from(radius);
+        let mut tiles = HashSet::from([origin]);
+
+        // Quadrants looking up, down, right and left, `(depth, column)` to world tile
+        let quadrants: [fn(Vector2I, i32, i32) -> Vector2I; 4] = [
+            |origin, depth, column| Vector2I::new(origin.x + column, origin.y + depth),
+            |origin, depth, column| Vector2I::new(origin.x + column, origin.y - depth),
+            |origin, depth, column| Vector2I::new(origin.x + depth, origin.y + column),
+            |origin, depth, column| Vector2I::new(origin.x - depth, origin.y + column),
+        ];
+
+        for transform in quadrants {
+            let mut rows = vec![Row { depth: 1, start_slope: -1.0, end_slope: 1.0 }];
+            while let Some(mut row) = rows.pop() {
+                if u64::from(row.depth.unsigned_abs()) > radius {
+                    continue;
+                }
+
+                let mut previous_opaque = None;
+                for column in row.columns() {
+                    let tile = transform(origin, row.depth, column);
+                    let opaque = is_opaque(tile);
+                    let in_radius = u64::from(row.depth.unsigned_abs()).pow(2) + u64::from(column.unsigned_abs()).pow(2) <= radius.pow(2) + radius;
+
+                    if in_radius && (opaque || row.is_symmetric(column)) {
+                        tiles.insert(tile);
+                    }
+                    if previous_opaque == Some(true) && !opaque {
+                        row.start_slope = slope(row.depth, column);
+                    }
+                    if previous_opaque == Some(false) && opaque {
+                        let mut next_row = row.next();
+                        next_row.end_slope = slope(row.depth, column);
+                        rows.push(next_row);
+                    }
+                    previous_opaque = Some(opaque);
+                }
+                if previous_opaque == Some(false) {
+                    rows.push(row.next());
+                }
+            }
+        }
+
+        Self { tiles }
+    }
+
+    pub fn contains(&self, tile: Vector2I) -> bool {
+        self.tiles.contains(&tile)
+    }
+
+    /// Seen tiles in no particular order
+    pub fn tiles(&self) -> impl Iterator<Item = Vector2I> + '_ {
+        self.tiles.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_area_is_seen_within_radius() {
+        let sight = Sight::compute(Vector2I::new(0, 0), 4, |_| false);
+
+        assert!(sight.contains(Vector2I::new(0, 0)));
+        assert!(sight.contains(Vector2I::new(4, 0)));
+        assert!(sight.contains(Vector2I::new(-3, -2)));
+        assert!(!sight.contains(Vector2I::new(5, 0)));
+        assert!(!sight.contains(Vector2I::new(4, 4)));
+        assert!(sight.tiles().all(|tile| tile.x.pow(2) + tile.y.pow(2) <= 4 * 4 + 4));
+    }
+
+    #[test]
+    fn test_wall_hides_tiles_behind_it() {
+        // Wall at x = 2 from y = -1 to y = 1
+        let is_opaque = |tile: Vector2I| tile.x == 2 && (-1..=1).contains(&tile.y);
+        let sight = Sight::compute(Vector2I::new(0, 0), 8, is_opaque);
+
+        assert!(sight.contains(Vector2I::new(2, 0)));
+        assert!(!sight.contains(Vector2I::new(3, 0)));
+        assert!(!sight.contains(Vector2I::new(6, 1)));
+        assert!(sight.contains(Vector2I::new(0, 5)));
+
+        // Symmetric, hidden tile does not see origin either
+        let from_behind = Sight::compute(Vector2I::new(3, 0), 8, is_opaque);
+        assert!(!from_behind.contains(Vector2I::new(0, 0)));
+    }
+
+    #[test]
+    fn test_huge_radius_does_not_overflow() {
+        // Closed room from -3 to 3
+        let is_opaque = |tile: Vector2I| tile.x.abs() >= 3 || tile.y.abs() >= 3;
+        let sight = Sight::compute(Vector2I::new(0, 0), u32::MAX, is_opaque);
+
+        assert_eq!(sight.len(), 7 * 7);
+    }
+}
@@ -1,9 +1,44 @@
-use super::math::{
-    Rect2F,
-    Vector2F
+use std::{
+    collections::VecDeque,
+    hash::{
+        DefaultHasher,
+        Hash,
+        Hasher
+    },
+    sync::{
+        Arc,
+        OnceLock
+    },
+    time::Duration
+};
+
+use super::{
+    math::{
+        Rect2F,
+        Vector2F,
+        Vector2I
+    },
+    npc_behavior::{
+        NpcAction,
+        NpcContext,
+        NpcPersonality
+    },
+    pathfinding,
+    spatial_index::SpatialIndex,
+    visibility::Sight,
+    tile_layer::{
+        Obstacle,
+        ObstacleKind,
+        TileBounds,
+        TileLayer
+    }
 };
 
-use rand::seq::IndexedRandom;
+use rand::{
+    Rng, 
+    SeedableRng
+};
+use rand_chacha::ChaCha8Rng;
 use serde::{
     Deserialize, 
     Serialize
@@ -45,6 +80,31 @@ pub enum WorldError {
 
     #[error("EntityNotSeeker")]
     EntityNotSeeker,
+
+    #[error("TileOccupied")]
+    TileOccupied,
+
+    #[error("EntityAlreadyMoving")]
+    EntityAlreadyMoving,
+
+    #[error("NoPathFound")]
+    NoPathFound,
+
+    #[error("OutOfBounds, position={0}")]
+    OutOfBounds(Vector2F),
+
+    #[error("WrongMovementMode, mode={0:?}")]
+    WrongMovementMode(MovementMode),
+}
+
+/// Obstacles with bounds, built once and shared until any of them changes
+#[derive(Debug, Default, PartialEq)]
+pub struct WorldMap {
+    /// Same obstacles and bounds always give the same id
+    pub id: u64,
+    /// Ordered by tile
+    pub obstacles: Vec<Obstacle>,
+    pub bounds: Option<TileBounds>,
 }
 
 pub struct SeekerHidersSummary {
@@ -52,13 +112,70 @@ pub struct SeekerHidersSummary {
     pub hiders: Vec<(EntityId, HiderStats)>,
 }
 
-#[derive(Debug)]
+/// Movement finished during tick: step arrived at its destination
+/// or free moving entity got to another tile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Movement {
+    pub entity_id: EntityId,
+    pub from: Vector2F,
+    pub to: Vector2F,
+}
+
+/// How entities move through the world
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementMode {
+    /// Tile by tile, positions snap to tile grid
+    #[default]
+    Tiles,
+    /// Players and wandering NPCs walk in any direction with velocity intents, collisions are resolved
+    /// with swept AABB. Entities following planned path still step between tiles, their steps are reserved.
+    Free,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldSettings {
+    /// Units per second
+    pub player_movement_speed: f32,
+    /// Units per second
+    pub npc_movement_speed: f32,
+    /// Tiles, seeker sees whole world if not set
+    #[serde(default)]
+    pub seeker_view_radius: Option<u32>,
+    #[serde(default)]
+    pub movement_mode: MovementMode,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct World {
     new_entity_id: EntityId,
+    /// Number of simulation steps done
+    ticks: u64,
+    /// Ordered by id, new entities get higher ids and removal keeps order
     entities: Vec<Entity>,
+    /// Tiles taken by entities, rebuilt from entities after load
+    #[serde(skip)]
+    index: SpatialIndex,
+    /// Obstacles blocking tiles, never move
+    #[serde(default)]
+    tiles: TileLayer,
+    /// Entities can not leave them, `None` for unbounded world
+    #[serde(default)]
+    bounds: Option<TileBounds>,
+    /// Built from tiles and bounds on first use after they changed
+    #[serde(skip)]
+    map: OnceLock<Arc<WorldMap>>,
+    /// Tiles NPCs walk between, set by map
+    #[serde(default)]
+    points_of_interest: Vec<Vector2I>,
+    settings: WorldSettings,
+    /// Same seed and same inputs give the same world
+    seed: u64,
+    /// Boxed, generator state is much bigger than rest of world
+    rng: Box<ChaCha8Rng>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum EntityState {
     Idle,
     Moving {
@@ -67,24 +184,37 @@ pub enum EntityState {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct NpcController {
     spawnpoint: Vector2F,
     roaming_range: Option<f32>,
-    change_destination_counter: u32,
+    change_destination_delay: Duration,
+    /// Older worlds have only roamers
+    #[serde(default)]
+    personality: NpcPersonality,
+    /// Direction of walking in free movement mode, zero when standing
+    #[serde(default)]
+    velocity: Vector2F,
+    /// Walking with velocity lasts that long, delay counts down after it
+    #[serde(default)]
+    walk_left: Duration,
 }
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct HiderStats {
     pub covered: bool,
+    pub survived_ticks: u32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SeekerStats {
     pub remaining_ticks: u32,
+    pub max_ticks: u32,
     pub remaining_failures: usize,
+    pub max_failures: usize,
+    pub hiders_found: usize,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PlayerRole {
     Hider {
         stats: HiderStats
@@ -96,16 +226,25 @@ pub enum PlayerRole {
 
 impl Default for PlayerRole {
     fn default() -> Self {
-        Self::Hider { stats: HiderStats { covered: true } }
+        Self::Hider { stats: HiderStats { covered: true, survived_ticks: 0 } }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PlayerController {
-    role: PlayerRole
+    role: PlayerRole,
+    /// Tiles left to walk, first one is next step
+    #[serde(default)]
+    path: VecDeque<Vector2I>,
+    /// Ticks next step of path stayed blocked
+    #[serde(default)]
+    path_blocked_ticks: u32,
+    /// Direction of walking in free movement mode, zero when standing
+    #[serde(default)]
+    velocity: Vector2F,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum EntityController {
     Npc(NpcController),
     Player(PlayerController),
@@ -113,15 +252,17 @@ pub enum EntityController {
 
 pub type EntityId = u32;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EntityStats {
+    /// Units per second
     movement_speed: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Entity {
     pub id: u32,
     pub name: String,
+    /// Changed only by world, so its spatial index stays in sync
     pub position: Vector2F,
     pub color: [u8; 3],
     pub size: Vector2F,
@@ -130,9 +271,197 @@ pub struct Entity {
     controller: EntityController,
 }
 
-const PLAYER_MOVEMENT_SPEED: f32 = 0.9;
-const NPC_MOVEMENT_SPEED: f32 = 0.3;
-const NPC_DIRECTION_SELECTION_TICKS_RANGE: std::ops::Range<u32> = 5..40;
+const PLAYER_MOVEMENT_SPEED: f32 = 28.0;
+/// Same as players, otherwise hiders stand out by their speed
+const NPC_MOVEMENT_SPEED: f32 = PLAYER_MOVEMENT_SPEED;
+const NPC_DIRECTION_SELECTION_MILLIS_RANGE: std::ops::Range<u64> = 160..1280;
+const SEEKER_VIEW_RADIUS: u32 = 8;
+/// Player stops following path blocked for that long
+const PATH_BLOCKED_TICKS_LIMIT: u32 = 20;
+
+pub(super) fn random_npc_direction_selection_delay<R: Rng>(rng: &mut R) -> Duration {
+    Duration::from_millis(rng.random_range(NPC_DIRECTION_SELECTION_MILLIS_RANGE))
+}
+
+/// Tile inside bounds, without obstacle and not reserved by other entity
+fn is_tile_walkable_for(entity_id: EntityId, tile: Vector2I, tiles: &TileLayer, index: &SpatialIndex, bounds: Option<TileBounds>) -> bool {
+    bounds.is_none_or(|bounds| bounds.contains_tile(tile))
+        && tiles.get(tile).is_none()
+        && index.entities_at(tile).iter().all(|id| *id == entity_id)
+}
+
+/// Tiles overlapped by rectangle, its right and top edges excluded
+fn rect_tiles(rect: &Rect2F) -> Vec<Vector2I> {
+    let min = TileLayer::tile_of(&rect.pos);
+    let max_x = ((rect.pos.x + rect.size.x) / TILE_SIZE).ceil() as i32 - 1;
+    let max_y = ((rect.pos.y + rect.size.y) / TILE_SIZE).ceil() as i32 - 1;
+    (min.y..=max_y.max(min.y))
+        .flat_map(|y| (min.x..=max_x.max(min.x)).map(move |x| Vector2I::new(x, y)))
+        .collect()
+}
+
+/// Entity as seen by free moving ones, ordered by id
+struct EntityBody {
+    id: EntityId,
+    rect: Rect2F,
+    /// Tiles reserved by its step, solid for others before it gets there
+    step_tiles: Vec<Vector2I>,
+}
+
+impl EntityBody {
+    fn of(e: &Entity) -> Self {
+        Self {
+            id: e.id,
+            rect: e.rect(),
+            step_tiles: e.step_tiles(),
+        }
+    }
+
+    fn update(bodies: &mut [EntityBody], e: &Entity) {
+        if let Ok(index) = bodies.binary_search_by_key(&e.id, |body| body.id) {
+            bodies[index] = Self::of(e);
+        }
+    }
+}
+
+/// Moves entity towards its destination or along its velocity, as far as obstacles, bounds, other
+/// entities and their reserved steps let it. Blocked movement slides along the hit side.
+/// Step which can not continue is ended.
+fn move_freely(e: &mut Entity, dt: Duration, tiles: &TileLayer, index: &mut SpatialIndex, bounds: Option<TileBounds>, bodies: &mut [EntityBody]) -> Option<Movement> {
+    let max_distance = e.stats.movement_speed * dt.as_secs_f32();
+    let (displacement, reaches_destination) = match (&e.state, &e.controller) {
+        (EntityState::Moving { from_position: _, destination }, _) => {
+            let to_destination = *destination - e.position;
+            if to_destination.length() <= max_distance {
+                (to_destination, true)
+            } else {
+                (to_destination.normal() * max_distance, false)
+            }
+        },
+        (EntityState::Idle, EntityController::Player(player_controller)) => (player_controller.velocity * max_distance, false),
+        (EntityState::Idle, EntityController::Npc(npc_controller)) => (npc_controller.velocity * max_distance, false),
+    };
+    if displacement == Vector2F::zero() && !e.is_moving() {
+        return None;
+    }
+
+    // Everything solid around path of entity, tiles out of bounds included
+    let rect = e.rect();
+    let swept_area = rect.union(&Rect2F { pos: rect.pos + displacement, size: rect.size });
+    let mut colliders = vec![];
+    for tile in rect_tiles(&swept_area) {
+        let tile_rect = Rect2F { pos: get_tiled_vec(tile.x, tile.y), size: Vector2F::new(TILE_SIZE, TILE_SIZE) };
+        if tiles.get(tile).is_some() || bounds.is_some_and(|bounds| !bounds.contains_tile(tile)) {
+            colliders.push(tile_rect);
+        }
+        for other_id in index.entities_at(tile).iter().filter(|id| **id != e.id) {
+            if let Ok(other_index) = bodies.binary_search_by_key(other_id, |body| body.id) {
+                let other = &bodies[other_index];
+                colliders.push(other.rect);
+                if other.step_tiles.contains(&tile) {
+                    colliders.push(tile_rect);
+                }
+            }
+        }
+    }
+    let moved = resolve_sweep(rect, displacement, &colliders);
+
+    index.remove(e.id, &e.occupied_tiles());
+    let previous_position = e.position;
+    e.position += moved;
+    let mut movement = None;
+    if let EntityState::Moving { from_position, destination } = e.state {
+        let arrived = reaches_destination && moved == displacement;
+        if arrived {
+            e.position = destination;
+            movement = Some(Movement { entity_id: e.id, from: from_position, to: destination });
+        }
+        if arrived || moved == Vector2F::zero() {
+            log::debug!("   {} stopped at {} go IDLE", e.name, e.position);
+            e.state = EntityState::Idle;
+        }
+    } else if TileLayer::tile_of(&previous_position) != TileLayer::tile_of(&e.position) {
+        movement = Some(Movement { entity_id: e.id, from: previous_position, to: e.position });
+    }
+    index.insert(e.id, &e.occupied_tiles());
+
+    EntityBody::update(bodies, e);
+    movement
+}
+
+/// Part of `displacement` rectangle can do before hitting any of `colliders`,
+/// remainder slides along the side which was hit
+fn resolve_sweep(rect: Rect2F, displacement: Vector2F, colliders: &[Rect2F]) -> Vector2F {
+    /// Entities stop that far before contact, so rounding never makes them overlap
+    const SWEEP_SKIN: f32 = 0.001;
+
+    let mut rect = rect;
+    let mut moved = Vector2F::zero();
+    let mut remaining = displacement;
+    // Once per axis at most
+    for _ in 0..2 {
+        let first_hit = colliders.iter()
+            .filter_map(|collider| rect.sweep(remaining, collider))
+            .min_by(|a, b| a.time.total_cmp(&b.time));
+        let Some(hit) = first_hit else {
+            moved += remaining;
+            break;
+        };
+
+        let time = (hit.time - SWEEP_SKIN / remaining.length()).max(0.0);
+        let step = remaining * time;
+        moved += step;
+        rect.pos += step;
+
+        // Drop part of movement going into hit side
+        remaining = remaining * (1.0 - time);
+        if hit.normal.x != 0.0 {
+            remaining.x = 0.0;
+        } else {
+            remaining.y = 0.0;
+        }
+        if remaining == Vector2F::zero() {
+            break;
+        }
+    }
+    moved
+}
+
+/// Reserves destination with corners of diagonal step, nothing is reserved if any of them is not walkable
+fn try_reserve_step(entity_id: EntityId, from_tile: Vector2I, to_tile: Vector2I, tiles: &TileLayer, index: &mut SpatialIndex, bounds: Option<TileBounds>) -> bool {
+    let step = pathfinding::step_tiles(from_tile, to_tile);
+    step.iter().all(|tile| is_tile_walkable_for(entity_id, *tile, tiles, index, bounds)) && index.try_reserve_all(entity_id, &step)
+}
+
+impl WorldMap {
+    fn new(obstacles: Vec<Obstacle>, bounds: Option<TileBounds>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        obstacles.hash(&mut hasher);
+        bounds.hash(&mut hasher);
+        Self {
+            id: hasher.finish(),
+            obstacles,
+            bounds,
+        }
+    }
+}
+
+impl Default for WorldSettings {
+    fn default() -> Self {
+        Self {
+            player_movement_speed: PLAYER_MOVEMENT_SPEED,
+            npc_movement_speed: NPC_MOVEMENT_SPEED,
+            seeker_view_radius: Some(SEEKER_VIEW_RADIUS),
+            movement_mode: MovementMode::default()
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl World {
     pub fn get_grid_aligned_position(pos: &Vector2F) -> Vector2F {
@@ -147,13 +476,44 @@ impl World {
     }
 
     pub fn new() -> Self {
-        log::info!("World created");
+        Self::with_settings(WorldSettings::default())
+    }
+
+    /// World with random seed
+    pub fn with_settings(settings: WorldSettings) -> Self {
+        Self::with_seed(settings, rand::random())
+    }
+
+    pub fn with_seed(settings: WorldSettings, seed: u64) -> Self {
+        log::info!("World created, seed={seed}");
         Self {
             new_entity_id: 0,
+            ticks: 0,
             entities: vec![],
+            index: SpatialIndex::default(),
+            tiles: TileLayer::default(),
+            bounds: None,
+            map: OnceLock::new(),
+            points_of_interest: vec![],
+            settings,
+            seed,
+            rng: Box::new(ChaCha8Rng::seed_from_u64(seed)),
         }
     }
 
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Generator derived from world one, for generation code which mutates world at the same time
+    pub fn fork_rng(&mut self) -> ChaCha8Rng {
+        ChaCha8Rng::from_rng(self.rng.as_mut())
+    }
+
     pub fn create_entity_player<S: AsRef<str>>(&mut self, name: S, intial_position: Vector2F, size: Vector2F) -> EntityId {
         let intial_position = Self::get_grid_aligned_position(&intial_position);
         // let colors = [
@@ -166,7 +526,7 @@ impl World {
         // ];
         // let mut rng = rand::rng();
         // let color = *colors.choose(&mut rng).unwrap();
-        let channel = rand::random_range(35..150);
+        let channel = self.rng.random_range(35..150);
         let color = [channel, channel, channel];
         self.create_entity(
             name, 
@@ -174,28 +534,36 @@ impl World {
             size,
             color,
             EntityStats {
-                movement_speed: PLAYER_MOVEMENT_SPEED
+                movement_speed: self.settings.player_movement_speed
             }, 
             EntityController::Player(PlayerController::default())
         )
     }
 
     pub fn create_entity_npc<S: AsRef<str>>(&mut self, name: S, intial_position: Vector2F, size: Vector2F) -> EntityId {
+        self.create_entity_npc_with_personality(name, intial_position, size, NpcPersonality::default())
+    }
+
+    pub fn create_entity_npc_with_personality<S: AsRef<str>>(&mut self, name: S, intial_position: Vector2F, size: Vector2F, personality: NpcPersonality) -> EntityId {
         let intial_position = Self::get_grid_aligned_position(&intial_position);
-        let channel = rand::random_range(35..150);
+        let channel = self.rng.random_range(35..150);
         let color = [channel, channel, channel];
+        let change_destination_delay = random_npc_direction_selection_delay(&mut self.rng);
         self.create_entity(
             name, 
             intial_position, 
             size,
             color,
             EntityStats {
-                movement_speed: NPC_MOVEMENT_SPEED
+                movement_speed: self.settings.npc_movement_speed
             }, 
             EntityController::Npc(NpcController {
                 spawnpoint: intial_position,
                 roaming_range: Some(TILE_SIZE * 2.5),
-                change_destination_counter: rand::random_range(NPC_DIRECTION_SELECTION_TICKS_RANGE)
+                change_destination_delay,
+                personality,
+                velocity: Vector2F::zero(),
+                walk_left: Duration::ZERO,
             })
         )
     }
@@ -216,18 +584,42 @@ impl World {
             controller
         };
 
+        self.index.insert(new_id, &entity.occupied_tiles());
         self.entities.push(entity);
         new_id
     }
 
     pub fn remove_entity(&mut self, entity_id: EntityId) -> Result<(), WorldError> {
-        let position = self.entities.iter()
-            .position(|e| e.id == entity_id)
-            .ok_or(WorldError::EntityNotExist)?;
-        let _ = self.entities.remove(position);
+        let position = self.entity_index_of(entity_id).ok_or(WorldError::EntityNotExist)?;
+        let entity = self.entities.remove(position);
+        self.index.remove(entity_id, &entity.occupied_tiles());
+        Ok(())
+    }
+
+    /// Index is not saved with world, it has to be rebuilt after world was loaded
+    pub fn rebuild_spatial_index(&mut self) {
+        self.index.clear();
+        for entity in self.entities.iter() {
+            self.index.insert(entity.id, &entity.occupied_tiles());
+        }
+    }
+
+    /// Place idle entity on another tile, even occupied one
+    pub fn teleport_entity(&mut self, entity_id: EntityId, position: Vector2F) -> Result<(), WorldError> {
+        let entity_index = self.entity_index_of(entity_id).ok_or(WorldError::EntityNotExist)?;
+        let entity = &mut self.entities[entity_index];
+        self.index.remove(entity_id, &entity.occupied_tiles());
+        entity.position = Self::get_grid_aligned_position(&position);
+        entity.state = EntityState::Idle;
+        entity.clear_path();
+        self.index.insert(entity_id, &entity.occupied_tiles());
         Ok(())
     }
 
+    fn entity_index_of(&self, entity_id: EntityId) -> Option<usize> {
+        self.entities.binary_search_by_key(&entity_id, |e| e.id).ok()
+    }
+
     pub fn select_entity_as_seeker(&mut self, entity_id: EntityId, remaining_ticks: u32, remaining_failures: usize) -> Result<(), WorldError> {
         assert!(remaining_ticks > 0);
         let entity = match self.get_entity_by_id_mut(entity_id) {
@@ -242,35 +634,111 @@ impl World {
                 Err(WorldError::EntityCannotBecameSeeker)
             },
             EntityController::Player(player_controller) => {
-                player_controller.role = PlayerRole::Seeker { stats: SeekerStats { remaining_ticks, remaining_failures } };
+                player_controller.role = PlayerRole::Seeker { stats: SeekerStats { 
+                    remaining_ticks, 
+                    max_ticks: remaining_ticks, 
+                    remaining_failures, 
+                    max_failures: remaining_failures,
+                    hiders_found: 0
+                } };
                 Ok(())
             },
         }
     }
 
     pub fn get_entity_by_id(&self, entity_id: EntityId) -> Option<&Entity> {
-        self.entities.iter().find(|e| e.id == entity_id)
+        self.entity_index_of(entity_id).map(|index| &self.entities[index])
     }
 
     pub fn get_entity_by_id_mut(&mut self, entity_id: EntityId) -> Option<&mut Entity> {
-        self.entities.iter_mut().find(|e| e.id == entity_id)
+        self.entity_index_of(entity_id).map(|index| &mut self.entities[index])
     }
 
-    pub fn is_tile_occupied(&self, tile_position: &Vector2F) -> bool {
-        let checked_tile = Rect2F::new(tile_position.x, tile_position.y, TILE_SIZE, TILE_SIZE);
-        for entity in self.entities.iter() {
-            let is_colliding = match entity.state {
-                EntityState::Idle => checked_tile.contains(&entity.position),
-                EntityState::Moving { from_position, destination } => {
-                    checked_tile.contains(&from_position) || checked_tile.contains(&destination)
-                },
-            };
+    /// Entities with position closer than `range` to `center`, ordered by id
+    pub fn get_entities_in_range(&self, center: Vector2F, range: f32) -> Vec<&Entity> {
+        // Moving entity is somewhere between its two tiles, one more tile covers it
+        let tiles_range = (range / TILE_SIZE).ceil() as i32 + 1;
+        self.index.entities_around(TileLayer::tile_of(&center), tiles_range)
+            .into_iter()
+            .filter_map(|entity_id| self.get_entity_by_id(entity_id))
+            .filter(|entity| (entity.position - center).length_squared() < range.powi(2))
+            .collect()
+    }
 
-            if is_colliding {
-                return true;
-            }
+    /// Tile can not be occupied by entity or other obstacle
+    pub fn place_obstacle(&mut self, tile_position: Vector2F, kind: ObstacleKind) -> Result<(), WorldError> {
+        if self.is_tile_occupied(&tile_position) {
+            return Err(WorldError::TileOccupied);
         }
-        false
+
+        self.tiles.set(TileLayer::tile_of(&tile_position), kind);
+        self.map.take();
+        Ok(())
+    }
+
+    pub fn is_tile_blocked(&self, tile_position: &Vector2F) -> bool {
+        self.tiles.is_blocked(tile_position)
+    }
+
+    /// Tiles seen by seeker, obstacles and world bounds block the view.
+    /// `None` if entity is not seeker or world has no view limit.
+    pub fn seeker_sight(&self, entity_id: EntityId) -> Option<Sight> {
+        let radius = self.settings.seeker_view_radius?;
+        let entity = self.get_entity_by_id(entity_id)?;
+        if !matches!(entity.get_player_role(), Some(PlayerRole::Seeker { stats: _ })) {
+            return None;
+        }
+
+        let origin = TileLayer::tile_of(&entity.position);
+        Some(Sight::compute(origin, radius, |tile| {
+            self.tiles.get(tile).is_some() || self.bounds.is_some_and(|bounds| !bounds.contains_tile(tile))
+        }))
+    }
+
+    /// Moving entity is seen when any of its tiles is
+    pub fn is_entity_in_sight(entity: &Entity, sight: &Sight) -> bool {
+        entity.occupied_tiles().into_iter().any(|tile| sight.contains(tile))
+    }
+
+    /// Ordered by tile
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.cached_map().obstacles
+    }
+
+    /// Obstacles and bounds, list is built only once for unchanged world
+    pub fn map(&self) -> Arc<WorldMap> {
+        self.cached_map().clone()
+    }
+
+    fn cached_map(&self) -> &Arc<WorldMap> {
+        self.map.get_or_init(|| Arc::new(WorldMap::new(self.tiles.obstacles(), self.bounds)))
+    }
+
+    pub fn bounds(&self) -> Option<TileBounds> {
+        self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: Option<TileBounds>) {
+        self.bounds = bounds;
+        self.map.take();
+    }
+
+    pub fn points_of_interest(&self) -> &[Vector2I] {
+        &self.points_of_interest
+    }
+
+    pub fn set_points_of_interest(&mut self, points_of_interest: Vec<Vector2I>) {
+        self.points_of_interest = points_of_interest;
+    }
+
+    /// Always true for unbounded world
+    pub fn is_inside_bounds(&self, position: &Vector2F) -> bool {
+        self.bounds.is_none_or(|bounds| bounds.contains(position))
+    }
+
+    /// Tile blocked by obstacle or taken by entity standing or moving there
+    pub fn is_tile_occupied(&self, tile_position: &Vector2F) -> bool {
+        self.tiles.is_blocked(tile_position) || self.index.is_occupied(TileLayer::tile_of(tile_position))
     }
 
     // TODO add some types to distinguish tiled metrics from units overall
@@ -329,6 +797,9 @@ impl World {
 
                 if enough_tile_corners_intersecting {
                     let tile_position = center_point + get_tiled_vec(ix, iy);
+                    if !self.is_inside_bounds(&tile_position) {
+                        continue;
+                    }
 
                     // Toglle search
                     if find_free_tiles != self.is_tile_occupied(&tile_position) {
@@ -348,30 +819,39 @@ impl World {
         self.get_tiles_positions(center_point, circle_range, false)
     }
 
-    pub fn tick(&mut self) {
+    /// Advance simulation by `dt` of real time, returns movements finished in this tick
+    pub fn tick(&mut self, dt: Duration) -> Vec<Movement> {
         log::trace!("World tick");
-
-        // TODO Do it better
-        // BUG 2 entities can select the same destination this way
-        let occupied_positions: Vec<_> = self
-        .entities
-        .iter()
-        .map(|e| match &e.state {
-            EntityState::Moving { destination, from_position } => vec![*destination, *from_position],
-            EntityState::Idle => vec![e.position],
-        })
-        .flatten()
-        .collect();
-
+        self.ticks += 1;
+
+        let rng = self.rng.as_mut();
+        let tiles = &self.tiles;
+        // Destinations are reserved in the same step they are checked, two entities never select the same one
+        let index = &mut self.index;
+        let bounds = self.bounds;
+        let points_of_interest = &self.points_of_interest;
+        // NPCs see entities where they were at tick start, ordered by id like entities
+        let entities_tiles: Vec<(EntityId, Vector2I)> = self.entities.iter()
+            .map(|e| (e.id, TileLayer::tile_of(&e.position)))
+            .collect();
+        let movement_mode = self.settings.movement_mode;
+        // Entities collide with others where they are now, moved ones included
+        let mut bodies: Vec<EntityBody> = match movement_mode {
+            MovementMode::Tiles => vec![],
+            MovementMode::Free => self.entities.iter().map(EntityBody::of).collect(),
+        };
+        let mut movements = vec![];
         self.entities.iter_mut().for_each(|e| {
             log::trace!(" - {e:?}");
 
-            if let EntityState::Moving { from_position, destination } = e.state {
+            if movement_mode == MovementMode::Free {
+                movements.extend(move_freely(e, dt, tiles, index, bounds, &mut bodies));
+            } else if let EntityState::Moving { from_position, destination } = e.state {
                 // Interpolate movement
                 // Destination was checked when entity was idle -> no need to check
                 let direction = (destination - from_position).normal();
                 let previous_location_to_destination = destination - e.position;
-                e.position += direction * e.stats.movement_speed;    
+                e.position += direction * (e.stats.movement_speed * dt.as_secs_f32());    
                 let new_location_to_destination = destination - e.position;        
                 
                 // Check if destination was reached, by checking change of dot product
@@ -381,86 +861,225 @@ impl World {
 
                 log::trace!("   {} moving, now in {}, closeness: {}", e.name, e.position, transition_closness);
 
-                // Align to destination, Change state to Idle and reset counter
+                // Align to destination and change state to Idle
                 if destination_was_reached {
                     log::debug!("   {} reached destination {} go IDLE", e.name, destination);
-                    e.state = EntityState::Idle;
-                    if let EntityController::Npc(npc_controller) = &mut e.controller {
-                        npc_controller.change_destination_counter = rand::random_range(NPC_DIRECTION_SELECTION_TICKS_RANGE);
+                    let destination_tile = TileLayer::tile_of(&destination);
+                    for tile in e.occupied_tiles().into_iter().filter(|tile| *tile != destination_tile) {
+                        index.release(e.id, tile);
                     }
+                    e.state = EntityState::Idle;
                     e.position = destination;
+                    movements.push(Movement { entity_id: e.id, from: from_position, to: destination });
                 }
             }
 
             match &mut e.controller {
                 EntityController::Npc(npc_controller) => {
-                    // Check if is capable of roaming
-                    if let Some(range) = npc_controller.roaming_range {
-
-                        // Every `xx` try selecting new destination
-                        // If destination is not valid (out of range, occupied or reserved)
-                        // then try next time.
-                        if e.state == EntityState::Idle {
-                            // Count down, at counting exhaustion try selecting new destination
-                            if npc_controller.change_destination_counter > 0 {
-                                log::debug!("   {} counting in IDLE {}...", e.name, npc_controller.change_destination_counter);
-                                npc_controller.change_destination_counter -= 1;
-                            } else {
-                                // Triggered -> try selecting new destination
-                                let directions = [
-                                    Vector2F::new(1.0, 0.0),
-                                    Vector2F::new(-1.0, 0.0),
-                                    Vector2F::new(0.0, 1.0),
-                                    Vector2F::new(0.0, -1.0),
-                                ];
-
-                                let random_direction = directions.choose(&mut rand::rng()).unwrap();
-
-                                let destination_position = e.position + (*random_direction * TILE_SIZE);
-
-                                if (destination_position - npc_controller.spawnpoint).length_squared() > range.powi(2) {
-                                    log::trace!("   Tile {destination_position} out of range!");
-                                    return;
-                                }
-
-                                if !occupied_positions.contains(&destination_position) {
-                                    log::info!("   {} Setting new destination from {} -to-> {} go MOVING!", 
-                                        e.name, e.position, destination_position
-                                    );
-                                    e.state = EntityState::Moving {
-                                        from_position: e.position,
-                                        destination: destination_position
-                                    };
-                                } else {
-                                    log::info!("   Tile {destination_position} already occupied!");
-                                }
-                            }
+                    // Every `xx` ask behavior what to do next.
+                    // If selected tile is not valid anymore (occupied or reserved)
+                    // then ask again next tick.
+                    if e.state != EntityState::Idle {
+                        return;
+                    }
+
+                    if !npc_controller.walk_left.is_zero() {
+                        npc_controller.walk_left = npc_controller.walk_left.saturating_sub(dt);
+                        if npc_controller.walk_left.is_zero() {
+                            npc_controller.velocity = Vector2F::zero();
                         }
+                        return;
+                    }
+
+                    // Count down, at counting exhaustion let behavior decide
+                    if !npc_controller.change_destination_delay.is_zero() {
+                        log::trace!("   {} counting in IDLE {:?}...", e.name, npc_controller.change_destination_delay);
+                        npc_controller.change_destination_delay = npc_controller.change_destination_delay.saturating_sub(dt);
+                        return;
+                    }
+
+                    let is_walkable = |tile| is_tile_walkable_for(e.id, tile, tiles, index, bounds);
+                    let entities_around = |center, tiles_range| index.entities_around(center, tiles_range);
+                    let context = NpcContext {
+                        entity_id: e.id,
+                        tile: TileLayer::tile_of(&e.position),
+                        spawn_tile: TileLayer::tile_of(&npc_controller.spawnpoint),
+                        roaming_range: npc_controller.roaming_range,
+                        entities: &entities_tiles,
+                        points_of_interest,
+                        is_walkable: &is_walkable,
+                        entities_around: &entities_around,
+                        movement_mode,
+                    };
+                    let behavior = npc_controller.personality.behavior_mut();
+                    let (action, delay) = behavior.decide(&context, rng);
+                    npc_controller.change_destination_delay = delay;
+
+                    let next_tile = match action {
+                        NpcAction::Step(next_tile) => next_tile,
+                        NpcAction::Walk { direction, tiles: distance } if movement_mode == MovementMode::Free => {
+                            let walk_secs = distance * TILE_SIZE / e.stats.movement_speed;
+                            npc_controller.walk_left = Duration::try_from_secs_f32(walk_secs).unwrap_or_default();
+                            if !npc_controller.walk_left.is_zero() {
+                                npc_controller.velocity = if direction.length_squared() > 1.0 { direction.normal() } else { direction };
+                            }
+                            return;
+                        },
+                        NpcAction::Walk { direction: _, tiles: _ } | NpcAction::Wait => return,
+                    };
+                    let destination_position = get_tiled_vec(next_tile.x, next_tile.y);
+
+                    if try_reserve_step(e.id, context.tile, next_tile, tiles, index, bounds) {
+                        log::info!("   {} Setting new destination from {} -to-> {} go MOVING!", 
+                            e.name, e.position, destination_position
+                        );
+                        e.state = EntityState::Moving {
+                            from_position: e.position,
+                            destination: destination_position
+                        };
+                        EntityBody::update(&mut bodies, e);
+                    } else {
+                        log::trace!("   Tile {destination_position} out of bounds, blocked or occupied!");
+                        behavior.step_rejected();
+                        npc_controller.change_destination_delay = Duration::ZERO;
+                    }
+                },
+                EntityController::Player(player_controller) => {
+                    if e.state != EntityState::Idle {
+                        return;
+                    }
+                    let (Some(next_tile), Some(goal)) = (player_controller.path.front().copied(), player_controller.path.back().copied()) else {
+                        return;
+                    };
+
+                    if try_reserve_step(e.id, TileLayer::tile_of(&e.position), next_tile, tiles, index, bounds) {
+                        player_controller.path.pop_front();
+                        player_controller.path_blocked_ticks = 0;
+                        e.state = EntityState::Moving {
+                            from_position: e.position,
+                            destination: get_tiled_vec(next_tile.x, next_tile.y)
+                        };
+                        EntityBody::update(&mut bodies, e);
+                        return;
+                    }
+
+                    // Something stepped into the way, try going around it
+                    let replanned_path = pathfinding::find_path(
+                        TileLayer::tile_of(&e.position), 
+                        goal, 
+                        |tile| is_tile_walkable_for(e.id, tile, tiles, index, bounds)
+                    );
+                    match replanned_path {
+                        Some(path) if path.front() != Some(&next_tile) => {
+                            log::debug!("   {} path blocked at {next_tile}, replanned", e.name);
+                            player_controller.path = path;
+                        },
+                        _ => {
+                            player_controller.path_blocked_ticks += 1;
+                            if player_controller.path_blocked_ticks > PATH_BLOCKED_TICKS_LIMIT {
+                                log::debug!("   {} path blocked at {next_tile}, giving up", e.name);
+                                player_controller.path.clear();
+                                player_controller.path_blocked_ticks = 0;
+                            }
+                        },
                     }
                 },
-                EntityController::Player(_player_controller) => { },
             }
         });
+        movements
     }
     
     pub fn iter_entities(&self) -> impl Iterator<Item = &Entity> {
         self.entities.iter()
     }
 
+    /// Entity keeps its tile and reserves destination one until it arrives
     pub fn try_start_move_entity_to(&mut self, entity_id: EntityId, next_position: Vector2F) -> Result<(), WorldError> {
-        if self.is_tile_occupied(&next_position) {
-            Err(WorldError::EntityCannotMoveThere)
-        } else {
-            let entity = self.get_entity_by_id_mut(entity_id).ok_or(WorldError::EntityNotExist)?;
-            
-            entity.state = EntityState::Moving {
-                from_position: entity.position,
-                destination: next_position
-            };
-            Ok(())
+        let entity_index = self.entity_index_of(entity_id).ok_or(WorldError::EntityNotExist)?;
+        if self.entities[entity_index].is_moving() {
+            return Err(WorldError::EntityAlreadyMoving);
+        }
+        if !self.is_inside_bounds(&next_position) {
+            return Err(WorldError::OutOfBounds(next_position));
+        }
+        let from_tile = TileLayer::tile_of(&self.entities[entity_index].position);
+        if TileLayer::tile_of(&next_position) == from_tile {
+            return Err(WorldError::EntityCannotMoveThere);
+        }
+        // Diagonal step can not cut corner of obstacle or entity
+        let step = pathfinding::step_tiles(from_tile, TileLayer::tile_of(&next_position));
+        if step.iter().any(|tile| self.tiles.get(*tile).is_some()) || !self.index.try_reserve_all(entity_id, &step) {
+            return Err(WorldError::EntityCannotMoveThere);
+        }
+
+        // Manual step replaces planned path
+        let entity = &mut self.entities[entity_index];
+        entity.clear_path();
+        entity.state = EntityState::Moving {
+            from_position: entity.position,
+            destination: next_position
+        };
+        Ok(())
+    }
+
+    /// Plans path to tile containing `target`, player walks it step by step in following ticks.
+    /// Moving player continues from its destination. Returns number of steps.
+    pub fn set_entity_path_to(&mut self, entity_id: EntityId, target: Vector2F) -> Result<usize, WorldError> {
+        if !self.is_inside_bounds(&target) {
+            return Err(WorldError::OutOfBounds(target));
+        }
+
+        let entity_index = self.entity_index_of(entity_id).ok_or(WorldError::EntityNotExist)?;
+        let start = match self.entities[entity_index].state {
+            EntityState::Idle => self.entities[entity_index].position,
+            EntityState::Moving { from_position: _, destination } => destination,
+        };
+
+        let (tiles, index, bounds) = (&self.tiles, &self.index, self.bounds);
+        let path = pathfinding::find_path(
+            TileLayer::tile_of(&start), 
+            TileLayer::tile_of(&target), 
+            |tile| is_tile_walkable_for(entity_id, tile, tiles, index, bounds)
+        ).ok_or(WorldError::NoPathFound)?;
+
+        match &mut self.entities[entity_index].controller {
+            EntityController::Player(player_controller) => {
+                let steps = path.len();
+                player_controller.path = path;
+                player_controller.path_blocked_ticks = 0;
+                player_controller.velocity = Vector2F::zero();
+                Ok(steps)
+            },
+            EntityController::Npc(_) => Err(WorldError::EntityNotPlayer),
         }
     }
 
+    /// Player keeps walking in `direction` in free movement mode, zero stops it.
+    /// Longer direction is shortened to 1. Replaces planned path, step in progress is finished first.
+    pub fn set_entity_velocity(&mut self, entity_id: EntityId, direction: Vector2F) -> Result<(), WorldError> {
+        if self.settings.movement_mode != MovementMode::Free {
+            return Err(WorldError::WrongMovementMode(self.settings.movement_mode));
+        }
+        if !direction.x.is_finite() || !direction.y.is_finite() {
+            return Err(WorldError::EntityCannotMoveThere);
+        }
+
+        let entity_index = self.entity_index_of(entity_id).ok_or(WorldError::EntityNotExist)?;
+        let entity = &mut self.entities[entity_index];
+        entity.clear_path();
+        match &mut entity.controller {
+            EntityController::Player(player_controller) => {
+                player_controller.velocity = if direction.length_squared() > 1.0 { direction.normal() } else { direction };
+                Ok(())
+            },
+            EntityController::Npc(_) => Err(WorldError::EntityNotPlayer),
+        }
+    }
+
+    pub fn movement_mode(&self) -> MovementMode {
+        self.settings.movement_mode
+    }
+
     pub fn get_seeker_hiders_summary(&self) -> SeekerHidersSummary {
         let mut summary = SeekerHidersSummary {
             seeker: None,
@@ -524,9 +1143,60 @@ impl World {
             log::warn!("Seeker not found!");
         }
     }
+
+    pub fn tick_hiders_survival_time(&mut self) {
+        for (_, hider_stats) in self.access_hiders_states_mut() {
+            if hider_stats.covered {
+                hider_stats.survived_ticks += 1;
+            }
+        }
+    }
 }
 
 impl Entity {
+    pub fn rect(&self) -> Rect2F {
+        Rect2F { pos: self.position, size: self.size }
+    }
+
+    /// Idle entity takes tiles under it, moving one also all tiles it moves between
+    fn occupied_tiles(&self) -> Vec<Vector2I> {
+        let mut tiles = rect_tiles(&self.rect());
+        if let EntityState::Moving { from_position, destination: _ } = self.state {
+            tiles.extend(rect_tiles(&Rect2F { pos: from_position, size: self.size }));
+            tiles.extend(self.step_tiles());
+            tiles.sort();
+            tiles.dedup();
+        }
+        tiles
+    }
+
+    /// Tiles reserved by current step, empty for idle entity
+    fn step_tiles(&self) -> Vec<Vector2I> {
+        match self.state {
+            EntityState::Moving { from_position, destination } => {
+                pathfinding::step_tiles(TileLayer::tile_of(&from_position), TileLayer::tile_of(&destination))
+            },
+            EntityState::Idle => vec![],
+        }
+    }
+
+    /// Remaining steps of planned path, empty for NPCs
+    pub fn path(&self) -> Vec<Vector2I> {
+        match &self.controller {
+            EntityController::Player(player_controller) => player_controller.path.iter().copied().collect(),
+            EntityController::Npc(_) => vec![],
+        }
+    }
+
+    /// Also stops walking with velocity
+    fn clear_path(&mut self) {
+        if let EntityController::Player(player_controller) = &mut self.controller {
+            player_controller.path.clear();
+            player_controller.path_blocked_ticks = 0;
+            player_controller.velocity = Vector2F::zero();
+        }
+    }
+
     pub fn is_player(&self) -> bool {
         matches!(self.controller, EntityController::Player(_))
     }
@@ -559,6 +1229,23 @@ impl Entity {
         }
     }
 
+    pub fn reward_seeker(&mut self) -> Result<(), WorldError> {
+        match &mut self.controller {
+            EntityController::Npc(_) => Err(WorldError::EntityNotPlayer),
+            EntityController::Player(player_controller) => {
+                match &mut player_controller.role {
+                    PlayerRole::Hider { stats: _ } => {
+                        Err(WorldError::EntityNotSeeker)
+                    },
+                    PlayerRole::Seeker { stats } => {
+                        stats.hiders_found += 1;
+                        Ok(())
+                    },
+                }
+            },
+        }
+    }
+
     pub fn punish_seeker(&mut self) -> Result<(), WorldError> {
         match &mut self.controller {
             EntityController::Npc(_) => Err(WorldError::EntityNotPlayer),
@@ -710,3 +1397,330 @@ fn test_entities_positions_not_inrange() {
     let p2 = get_tiled_vec(1, 0);
     assert!(!World::is_entity_inrange(p1, p2));
 }
+
+#[test]
+fn test_only_covered_hiders_survival_time_grows() {
+    let mut world = World::new();
+    let hider_1 = world.create_entity_player("Hider_1", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let hider_2 = world.create_entity_player("Hider_2", get_tiled_vec(2, 0), ENTITY_SIZE);
+
+    world.tick_hiders_survival_time();
+    world.get_entity_by_id_mut(hider_2).unwrap().set_hider_covered(false).unwrap();
+    world.tick_hiders_survival_time();
+
+    let survived_ticks = |id| match world.get_entity_by_id(id).unwrap().get_player_role() {
+        Some(PlayerRole::Hider { stats }) => stats.survived_ticks,
+        role => panic!("Bad role={role:?}"),
+    };
+    assert_eq!(survived_ticks(hider_1), 2);
+    assert_eq!(survived_ticks(hider_2), 1);
+}
+
+#[test]
+fn test_same_seed_gives_same_npcs_roaming() {
+    fn simulate(seed: u64) -> Vec<(Vector2F, [u8; 3])> {
+        let mut world = World::with_seed(WorldSettings::default(), seed);
+        for x in 0..5 {
+            world.create_entity_npc("NPC", get_tiled_vec(x * 3, 0), ENTITY_SIZE);
+        }
+        for _ in 0..300 {
+            world.tick(Duration::from_millis(33));
+        }
+        world.iter_entities().map(|e| (e.position, e.color)).collect()
+    }
+
+    assert_eq!(simulate(42), simulate(42));
+    assert_ne!(simulate(42), simulate(43));
+}
+
+#[test]
+fn test_obstacle_blocks_tile_and_movement() {
+    let mut world = World::new();
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    world.place_obstacle(get_tiled_vec(1, 0), ObstacleKind::Rock).unwrap();
+
+    assert!(world.is_tile_occupied(&get_tiled_vec(1, 0)));
+    assert!(matches!(world.place_obstacle(get_tiled_vec(0, 0), ObstacleKind::Crate), Err(WorldError::TileOccupied)));
+    assert!(matches!(world.try_start_move_entity_to(player, get_tiled_vec(1, 0)), Err(WorldError::EntityCannotMoveThere)));
+    assert!(!world.get_free_tiles_positions(Vector2F::zero(), TILE_SIZE * 2.0).contains(&get_tiled_vec(1, 0)));
+    assert_eq!(world.obstacles().len(), 1);
+}
+
+#[test]
+fn test_map_is_shared_until_it_changes() {
+    let mut world = World::new();
+    world.place_obstacle(get_tiled_vec(1, 0), ObstacleKind::Rock).unwrap();
+    let map = world.map();
+    assert!(Arc::ptr_eq(&map, &world.map()));
+
+    world.set_bounds(Some(TileBounds::new(Vector2I::new(-4, -4), Vector2I::new(8, 8))));
+    let bounded_map = world.map();
+    assert_ne!(bounded_map.id, map.id);
+    assert_eq!(bounded_map.bounds, world.bounds());
+
+    world.place_obstacle(get_tiled_vec(2, 0), ObstacleKind::Crate).unwrap();
+    assert_eq!(world.map().obstacles.len(), 2);
+    assert_ne!(world.map().id, bounded_map.id);
+}
+
+#[test]
+fn test_npc_does_not_roam_into_obstacles() {
+    let mut world = World::with_seed(WorldSettings::default(), 5);
+    let npc = world.create_entity_npc("NPC", get_tiled_vec(0, 0), ENTITY_SIZE);
+    for (x, y) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+        world.place_obstacle(get_tiled_vec(x, y), ObstacleKind::Wall).unwrap();
+    }
+
+    for _ in 0..300 {
+        world.tick(Duration::from_millis(33));
+    }
+    assert_eq!(world.get_entity_by_id(npc).unwrap().position, get_tiled_vec(0, 0));
+}
+
+#[test]
+fn test_world_bounds_limit_movement() {
+    let mut world = World::with_seed(WorldSettings::default(), 7);
+    world.set_bounds(Some(TileBounds::new(Vector2I::new(-1, -1), Vector2I::new(3, 3))));
+    let player = world.create_entity_player("Player", get_tiled_vec(1, 0), ENTITY_SIZE);
+    let npc = world.create_entity_npc("NPC", get_tiled_vec(-1, -1), ENTITY_SIZE);
+
+    assert!(matches!(world.try_start_move_entity_to(player, get_tiled_vec(2, 0)), Err(WorldError::OutOfBounds(_))));
+    assert!(world.try_start_move_entity_to(player, get_tiled_vec(1, 1)).is_ok());
+    assert_eq!(world.get_free_tiles_positions(Vector2F::zero(), TILE_SIZE * 3.0).len(), 9 - 3);
+
+    for _ in 0..300 {
+        world.tick(Duration::from_millis(33));
+        let position = world.get_entity_by_id(npc).unwrap().position;
+        assert!(world.is_inside_bounds(&position), "NPC left bounds at {position}");
+    }
+}
+
+#[test]
+fn test_spatial_index_follows_entities() {
+    let mut world = World::with_seed(WorldSettings::default(), 3);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let npc = world.create_entity_npc("NPC", get_tiled_vec(4, 0), ENTITY_SIZE);
+
+    world.try_start_move_entity_to(player, get_tiled_vec(1, 0)).unwrap();
+    assert!(world.is_tile_occupied(&get_tiled_vec(0, 0)));
+    assert!(world.is_tile_occupied(&get_tiled_vec(1, 0)));
+    for _ in 0..10 {
+        world.tick(Duration::from_millis(50));
+    }
+    assert!(!world.is_tile_occupied(&get_tiled_vec(0, 0)));
+    assert!(world.is_tile_occupied(&get_tiled_vec(1, 0)));
+
+    world.teleport_entity(player, get_tiled_vec(-3, 2)).unwrap();
+    assert!(!world.is_tile_occupied(&get_tiled_vec(1, 0)));
+    assert!(world.is_tile_occupied(&get_tiled_vec(-3, 2)));
+
+    let near_player: Vec<EntityId> = world.get_entities_in_range(get_tiled_vec(-3, 2), TILE_SIZE * 2.0).iter().map(|e| e.id).collect();
+    assert_eq!(near_player, vec![player]);
+
+    let npc_position = world.get_entity_by_id(npc).unwrap().position;
+    world.remove_entity(npc).unwrap();
+    assert!(!world.is_tile_occupied(&npc_position));
+    assert!(world.get_entities_in_range(npc_position, TILE_SIZE * 3.0).is_empty());
+}
+
+#[test]
+fn test_roaming_npcs_never_share_tile() {
+    let mut world = World::with_seed(WorldSettings::default(), 9);
+    for i in 0..25 {
+        world.create_entity_npc(format!("NPC{i}"), get_tiled_vec(i % 5, i / 5), ENTITY_SIZE);
+    }
+
+    for _ in 0..200 {
+        world.tick(Duration::from_millis(50));
+        let mut taken: Vec<Vector2I> = world.iter_entities().flat_map(|e| e.occupied_tiles()).collect();
+        let taken_count = taken.len();
+        taken.sort();
+        taken.dedup();
+        assert_eq!(taken.len(), taken_count);
+    }
+}
+
+#[test]
+fn test_player_walks_planned_path_around_obstacles() {
+    let mut world = World::with_seed(WorldSettings::default(), 1);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    for y in -1..=1 {
+        world.place_obstacle(get_tiled_vec(1, y), ObstacleKind::Wall).unwrap();
+    }
+
+    assert!(matches!(world.set_entity_path_to(player, get_tiled_vec(1, 0)), Err(WorldError::NoPathFound)));
+    assert_eq!(world.set_entity_path_to(player, get_tiled_vec(2, 0)).unwrap(), 2 + 2 + 2);
+
+    for _ in 0..100 {
+        world.tick(Duration::from_millis(50));
+        let position = world.get_entity_by_id(player).unwrap().position;
+        assert!(!world.is_tile_blocked(&position), "Player walked into obstacle at {position}");
+    }
+    let player_entity = world.get_entity_by_id(player).unwrap();
+    assert_eq!(player_entity.position, get_tiled_vec(2, 0));
+    assert!(player_entity.path().is_empty());
+}
+
+#[test]
+fn test_player_replans_path_when_blocked() {
+    let mut world = World::with_seed(WorldSettings::default(), 1);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    world.set_entity_path_to(player, get_tiled_vec(4, 0)).unwrap();
+    assert_eq!(world.get_entity_by_id(player).unwrap().path()[1], Vector2I::new(2, 0));
+
+    // Other player steps into the straight path after it was planned
+    let blocker = world.create_entity_player("Blocker", get_tiled_vec(2, 0), ENTITY_SIZE);
+    for _ in 0..100 {
+        world.tick(Duration::from_millis(50));
+    }
+    assert_eq!(world.get_entity_by_id(player).unwrap().position, get_tiled_vec(4, 0));
+    assert_eq!(world.get_entity_by_id(blocker).unwrap().position, get_tiled_vec(2, 0));
+
+    // Manual step cancels planned path
+    world.set_entity_path_to(player, get_tiled_vec(8, 0)).unwrap();
+    world.try_start_move_entity_to(player, get_tiled_vec(4, 1)).unwrap();
+    assert!(world.get_entity_by_id(player).unwrap().path().is_empty());
+}
+
+#[test]
+fn test_seeker_does_not_see_behind_walls() {
+    let mut world = World::with_seed(WorldSettings::default(), 2);
+    let seeker = world.create_entity_player("Seeker", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let hider = world.create_entity_player("Hider", get_tiled_vec(3, 0), ENTITY_SIZE);
+    let npc = world.create_entity_npc("NPC", get_tiled_vec(0, 3), ENTITY_SIZE);
+    let far_npc = world.create_entity_npc("FarNPC", get_tiled_vec(0, -20), ENTITY_SIZE);
+    for y in -1..=1 {
+        world.place_obstacle(get_tiled_vec(2, y), ObstacleKind::Wall).unwrap();
+    }
+
+    // Hiders see everything
+    assert!(world.seeker_sight(hider).is_none());
+
+    world.select_entity_as_seeker(seeker, 100, 3).unwrap();
+    let sight = world.seeker_sight(seeker).unwrap();
+    let in_sight = |id| World::is_entity_in_sight(world.get_entity_by_id(id).unwrap(), &sight);
+    assert!(in_sight(seeker));
+    assert!(in_sight(npc));
+    assert!(!in_sight(hider));
+    assert!(!in_sight(far_npc));
+}
+
+#[test]
+fn test_diagonal_step_timing_and_corners() {
+    let mut world = World::with_seed(WorldSettings::default(), 6);
+    let straight = world.create_entity_player("Straight", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let diagonal = world.create_entity_player("Diagonal", get_tiled_vec(0, 4), ENTITY_SIZE);
+    world.try_start_move_entity_to(straight, get_tiled_vec(1, 0)).unwrap();
+    world.try_start_move_entity_to(diagonal, get_tiled_vec(1, 5)).unwrap();
+
+    // Diagonal step is sqrt(2) longer at the same speed
+    let ticks_until_idle = |world: &mut World, id| (1..100).find(|_| {
+        world.tick(Duration::from_millis(10));
+        !world.get_entity_by_id(id).unwrap().is_moving()
+    }).unwrap();
+    let straight_ticks = ticks_until_idle(&mut world, straight);
+    let diagonal_ticks = straight_ticks + ticks_until_idle(&mut world, diagonal);
+    assert!((diagonal_ticks as f32 / straight_ticks as f32 - 2f32.sqrt()).abs() < 0.1, "{straight_ticks} vs {diagonal_ticks}");
+    assert_eq!(world.get_entity_by_id(diagonal).unwrap().position, get_tiled_vec(1, 5));
+    assert_eq!(world.index.len(), 2);
+
+    // Corners can not be cut, neither next to obstacle nor next to entity
+    world.place_obstacle(get_tiled_vec(2, 5), ObstacleKind::Rock).unwrap();
+    assert!(matches!(world.try_start_move_entity_to(diagonal, get_tiled_vec(2, 6)), Err(WorldError::EntityCannotMoveThere)));
+    assert!(matches!(world.try_start_move_entity_to(diagonal, get_tiled_vec(2, 4)), Err(WorldError::EntityCannotMoveThere)));
+    world.create_entity_npc("Blocker", get_tiled_vec(1, 6), ENTITY_SIZE);
+    assert!(matches!(world.try_start_move_entity_to(diagonal, get_tiled_vec(0, 6)), Err(WorldError::EntityCannotMoveThere)));
+    assert!(matches!(world.try_start_move_entity_to(diagonal, get_tiled_vec(0, 4)), Ok(())));
+    assert_eq!(world.get_entity_by_id(diagonal).unwrap().occupied_tiles().len(), 4);
+}
+
+#[test]
+fn test_free_movement_slides_along_walls() {
+    let mut tiled_world = World::with_seed(WorldSettings::default(), 7);
+    let tiled_player = tiled_world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    assert!(matches!(tiled_world.set_entity_velocity(tiled_player, Vector2F::new(1.0, 0.0)), Err(WorldError::WrongMovementMode(MovementMode::Tiles))));
+
+    let mut world = World::with_seed(WorldSettings { movement_mode: MovementMode::Free, ..Default::default() }, 7);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let blocker = world.create_entity_player("Blocker", get_tiled_vec(1, 6), ENTITY_SIZE);
+    let npc = world.create_entity_npc("NPC", get_tiled_vec(-10, -10), ENTITY_SIZE);
+    assert!(matches!(world.set_entity_velocity(npc, Vector2F::new(1.0, 0.0)), Err(WorldError::EntityNotPlayer)));
+    for y in -1..=10 {
+        world.place_obstacle(get_tiled_vec(2, y), ObstacleKind::Wall).unwrap();
+    }
+
+    // Walking diagonally into the wall slides up along it until other player blocks the way
+    world.set_entity_velocity(player, Vector2F::new(3.0, 3.0)).unwrap();
+    for _ in 0..200 {
+        world.tick(Duration::from_millis(10));
+        let player_rect = world.get_entity_by_id(player).unwrap().rect();
+        assert!(world.obstacles().iter().all(|obstacle| !player_rect.intersects(&Rect2F { pos: get_tiled_vec(obstacle.tile.x, obstacle.tile.y), size: Vector2F::new(TILE_SIZE, TILE_SIZE) })));
+        assert!(!player_rect.intersects(&world.get_entity_by_id(blocker).unwrap().rect()));
+    }
+    let position = world.get_entity_by_id(player).unwrap().position;
+    assert!((position.x - (get_tiled_value(2) - ENTITY_SIZE.x)).abs() < 0.01, "{position:?}");
+    assert!((position.y - (get_tiled_value(6) - ENTITY_SIZE.y)).abs() < 0.01, "{position:?}");
+
+    // Stopping keeps entity off tile grid
+    world.set_entity_velocity(player, Vector2F::zero()).unwrap();
+    world.tick(Duration::from_millis(10));
+    assert_eq!(world.get_entity_by_id(player).unwrap().position, position);
+}
+
+#[test]
+fn test_tick_reports_finished_movements() {
+    let mut world = World::with_seed(WorldSettings::default(), 3);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    world.set_entity_path_to(player, get_tiled_vec(2, 0)).unwrap();
+
+    let movements: Vec<Movement> = (0..100).flat_map(|_| world.tick(Duration::from_millis(10))).collect();
+    assert_eq!(movements, vec![
+        Movement { entity_id: player, from: get_tiled_vec(0, 0), to: get_tiled_vec(1, 0) },
+        Movement { entity_id: player, from: get_tiled_vec(1, 0), to: get_tiled_vec(2, 0) },
+    ]);
+
+    // Free moving entity reports every tile it gets to
+    let mut world = World::with_seed(WorldSettings { movement_mode: MovementMode::Free, ..Default::default() }, 3);
+    let player = world.create_entity_player("Player", get_tiled_vec(0, 0), ENTITY_SIZE);
+    world.set_entity_velocity(player, Vector2F::new(1.0, 0.0)).unwrap();
+
+    let movements: Vec<Movement> = (0..100).flat_map(|_| world.tick(Duration::from_millis(10))).collect();
+    let reached_tiles: Vec<Vector2I> = movements.iter().map(|movement| TileLayer::tile_of(&movement.to)).collect();
+    let end_tile = TileLayer::tile_of(&world.get_entity_by_id(player).unwrap().position);
+    assert_eq!(reached_tiles, (1..=end_tile.x).map(|x| Vector2I::new(x, 0)).collect::<Vec<_>>());
+    assert!(end_tile.x >= 4);
+}
+
+#[test]
+fn test_free_moving_npcs_stop_off_tile_grid() {
+    let mut world = World::with_seed(WorldSettings { movement_mode: MovementMode::Free, ..Default::default() }, 5);
+    let npcs: Vec<EntityId> = (0..4)
+        .map(|i| world.create_entity_npc_with_personality(format!("NPC{i}"), get_tiled_vec(i * 4, 0), ENTITY_SIZE, NpcPersonality::wanderer()))
+        .collect();
+
+    let mut stopped_off_grid = false;
+    for _ in 0..300 {
+        world.tick(Duration::from_millis(33));
+        stopped_off_grid |= npcs.iter().any(|npc| {
+            let position = world.get_entity_by_id(*npc).unwrap().position;
+            let tile = TileLayer::tile_of(&position);
+            position != get_tiled_vec(tile.x, tile.y)
+        });
+    }
+    assert!(stopped_off_grid);
+}
+
+#[test]
+fn test_free_moving_entity_does_not_enter_reserved_step() {
+    let mut world = World::with_seed(WorldSettings { movement_mode: MovementMode::Free, ..Default::default() }, 5);
+    let walker = world.create_entity_player("Walker", get_tiled_vec(0, 0), ENTITY_SIZE);
+    let stepper = world.create_entity_player("Stepper", get_tiled_vec(1, 1), ENTITY_SIZE);
+
+    world.try_start_move_entity_to(stepper, get_tiled_vec(1, 0)).unwrap();
+    world.set_entity_velocity(walker, Vector2F::new(1.0, 0.0)).unwrap();
+    for _ in 0..100 {
+        world.tick(Duration::from_millis(10));
+    }
+    assert_eq!(world.get_entity_by_id(stepper).unwrap().position, get_tiled_vec(1, 0));
+    assert!(world.get_entity_by_id(walker).unwrap().position.x < get_tiled_value(1));
+}
@@ -4,7 +4,20 @@ use clap::{
     Args
 };
 
-use rust_multiplayer::DEFAULT_SERVER_ADRESS;
+use std::path::PathBuf;
+
+use rust_multiplayer::{
+    app::server::config::{
+        ConfigOverrides, 
+        ServerConfig, 
+        ServerConfigError
+    }, 
+    game::map_file::{
+        MapFile, 
+        MapFileError
+    }, 
+    DEFAULT_SERVER_ADRESS
+};
 
 /// # Global Arguments
 #[derive(Debug, Parser)]
@@ -23,7 +36,10 @@ enum Mode {
     Request(RequestArgs),
 
     /// Run player cleint app
-    Player(PlayerClientArgs)
+    Player(PlayerClientArgs),
+
+    /// Play back recorded round
+    Replay(ReplayArgs),
 }
 
 #[derive(Debug, Args)]
@@ -31,6 +47,60 @@ struct ServerArgs {
     /// Server address
     #[arg(short = 'a', long = "address", value_name = "SERVER_ADDRESS", default_value_t = String::from(DEFAULT_SERVER_ADRESS))]
     address: String,
+
+    /// Gameplay rules TOML file, changes are applied between rounds. If not provided defaults will be used
+    #[arg(short = 'c', long = "config", value_name = "CONFIG_PATH", required = false)]
+    config_path: Option<PathBuf>,
+
+    /// Simulation ticks per second, overrides config
+    #[arg(short = 't', long = "tps", value_name = "TICKS_PER_SECOND", required = false)]
+    ticks_per_second: Option<u32>,
+
+    /// Clients required to start the game, overrides config
+    #[arg(long = "clients-required", value_name = "COUNT", required = false)]
+    clients_required_to_start: Option<usize>,
+
+    /// Time seeker has to find hiders in seconds, overrides config
+    #[arg(long = "seeking-time", value_name = "SECONDS", required = false)]
+    seeking_max_time_secs: Option<f32>,
+
+    /// Wrong guesses seeker can make, overrides config
+    #[arg(long = "seeking-tries", value_name = "COUNT", required = false)]
+    seeking_max_tries: Option<usize>,
+
+    /// Hand-authored map TOML file played every round instead of generated maps
+    #[arg(short = 'm', long = "map", value_name = "MAP_PATH", required = false)]
+    map_path: Option<PathBuf>,
+}
+
+impl ServerArgs {
+    fn config_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            ticks_per_second: self.ticks_per_second,
+            clients_required_to_start: self.clients_required_to_start,
+            seeking_max_time_secs: self.seeking_max_time_secs,
+            seeking_max_tries: self.seeking_max_tries,
+        }
+    }
+
+    fn load_config(&self) -> Result<ServerConfig, ServerConfigError> {
+        let overrides = self.config_overrides();
+        match &self.config_path {
+            Some(config_path) => ServerConfig::load_with_overrides(config_path, &overrides),
+            None => {
+                let mut config = ServerConfig::default();
+                overrides.apply_to(&mut config);
+                config.validate()?;
+                Ok(config)
+            },
+        }
+    }
+
+    fn load_map(&self) -> Result<Option<MapFile>, MapFileError> {
+        self.map_path.as_ref()
+            .map(MapFile::load)
+            .transpose()
+    }
 }
 
 #[derive(Debug, Args)]
@@ -49,6 +119,17 @@ struct PlayerClientArgs {
     /// Player name, if not provided autogenerated will be set
     #[arg(short = 'n', long = "name", value_name = "PLAYER_NAME", required = false)]
     player_name: Option<String>,
+
+    /// Account password, logs in as registered player instead of guest
+    #[arg(short = 'p', long = "password", value_name = "PASSWORD", required = false, requires = "player_name")]
+    player_password: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct ReplayArgs {
+    /// Replay file written by server with `storage.replay_dir` set
+    #[arg(value_name = "REPLAY_PATH")]
+    replay_path: PathBuf,
 }
 
 fn main() {
@@ -63,7 +144,22 @@ fn main() {
     
     match cli_args.mode {
         Mode::Server(server_args) => {
-            cli_server::run(&server_args.address);
+            let config = match server_args.load_config() {
+                Ok(config) => config,
+                Err(e) => {
+                    log::error!("Could not load server config: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let map = match server_args.load_map() {
+                Ok(map) => map,
+                Err(e) => {
+                    log::error!("Could not load map {:?}: {e}", server_args.map_path);
+                    std::process::exit(1);
+                }
+            };
+            let overrides = server_args.config_overrides();
+            cli_server::run(&server_args.address, config, server_args.config_path, overrides, map);
         },
         Mode::Request(request_args) => {
             cli_request::run(&request_args.address);
@@ -71,19 +167,43 @@ fn main() {
         Mode::Player(player_client_args) => {
             cli_player_client::run(
                 &player_client_args.address, 
-                player_client_args.player_name
+                player_client_args.player_name,
+                player_client_args.player_password
             );
         },
+        Mode::Replay(replay_args) => {
+            cli_player_client::run_replay(&replay_args.replay_path);
+        },
     }
 }
 
 mod cli_server {
-    use rust_multiplayer::app::server::MultiplayerServer;
+    use std::path::PathBuf;
 
-    pub fn run<A: tokio::net::ToSocketAddrs>(addr: A) {
+    use rust_multiplayer::{
+        app::server::{
+            config::{
+                ConfigOverrides, 
+                ServerConfig
+            }, 
+            MultiplayerServer
+        }, 
+        game::map_file::MapFile
+    };
+
+    pub fn run<A: tokio::net::ToSocketAddrs>(addr: A, config: ServerConfig, config_path: Option<PathBuf>, overrides: ConfigOverrides, map: Option<MapFile>) {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let server = MultiplayerServer::bind(addr).await.unwrap();
+            let mut server = MultiplayerServer::bind(addr).await.unwrap()
+                .with_config(config);
+
+            if let Some(config_path) = config_path {
+                server = server.with_config_watch(config_path, overrides);
+            }
+            if let Some(map) = map {
+                log::info!("Rounds are played on map '{}'", map.name);
+                server = server.with_map(map);
+            }
             log::info!("MP-server, address:{:?}",  server.get_local_address().unwrap());
             
             let server_handler = server.run().await.unwrap();
@@ -142,6 +262,7 @@ mod cli_request {
                 String::from("{\"type\":\"Healthcheck\"}"),
                 String::from("{\"type\":\"GetId\"}"),
                 String::from("{\"type\":\"WorldCheck\"}"),
+                String::from("{\"type\":\"GetServerTick\"}"),
             ];
 
             for request in requests {
@@ -159,17 +280,21 @@ mod cli_request {
 
 mod cli_player_client {
     use rust_multiplayer::{
-        app::client::{
-            gui_client::{
-                guis::{AppGui, GuiLayout}, renderer::Renderer, AppData
+        app::{
+            client::{
+                gui_client::{
+                    guis::{AppGui, GuiLayout}, renderer::Renderer, AppData
+                }, 
+                MultiplayerClient
             }, 
-            MultiplayerClient
+            replay::Replay
         }, game::math::Vector2F
         
     };
 
     use std::{
         cell::RefCell, 
+        path::Path, 
         rc::Rc, 
         sync::Arc, 
         time::Instant
@@ -232,8 +357,10 @@ mod cli_player_client {
                     println!("The close button was pressed; stopping");
                     event_loop.exit();
 
-                    let client_handler = self.app_data.borrow_mut().client_handler.take().unwrap();
-                    client_handler.wait_until_finished().unwrap()
+                    // Replay runs without connection
+                    if let Some(client_handler) = self.app_data.borrow_mut().client_handler.take() {
+                        client_handler.wait_until_finished().unwrap()
+                    }
                 }
                 WindowEvent::RedrawRequested => {
                     const TARGET_FRAME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(33);
@@ -305,29 +432,57 @@ mod cli_player_client {
         }
     }
 
-    pub fn run<A: std::net::ToSocketAddrs + std::fmt::Debug>(addr: A, player_name: Option<String>) {
-        // wgpu uses `log` for all of our logging, so we initialize a logger with the `env_logger` crate.
-        
-        let event_loop = EventLoop::new().unwrap();
-
-        // When the current loop iteration finishes, immediately begin a new
-        // iteration regardless of whether or not new events are available to
-        // process. Preferred for applications that want to render as fast as
-        // possible, like games.
-        event_loop.set_control_flow(ControlFlow::Poll);
-
+    pub fn run<A: std::net::ToSocketAddrs + std::fmt::Debug>(addr: A, player_name: Option<String>, player_password: Option<String>) {
         let client_handler = MultiplayerClient::connect(addr).unwrap()
             .run().unwrap();
 
-        let app_data = Rc::new(RefCell::new(AppData { 
+        run_app(AppData { 
             client_handler: Some(client_handler),
             player_name,
+            player_password,
             app_gui_expected_transition: None,
             last_width: INITIAL_WINDOW_SIZE.x,
             last_height: INITIAL_WINDOW_SIZE.y, 
             world_scale: 0.02,
             camera: Vector2F::zero(),
-        }));
+            replay: None,
+        });
+    }
+
+    pub fn run_replay(replay_path: &Path) {
+        let replay = match Replay::load(replay_path) {
+            Ok(replay) => replay,
+            Err(e) => {
+                log::error!("Could not load replay {replay_path:?}: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        run_app(AppData { 
+            client_handler: None,
+            player_name: None,
+            player_password: None,
+            app_gui_expected_transition: None,
+            last_width: INITIAL_WINDOW_SIZE.x,
+            last_height: INITIAL_WINDOW_SIZE.y, 
+            world_scale: 0.02,
+            camera: Vector2F::zero(),
+            replay: Some(replay),
+        });
+    }
+
+    fn run_app(app_data: AppData) {
+        // wgpu uses `log` for all of our logging, so we initialize a logger with the `env_logger` crate.
+        
+        let event_loop = EventLoop::new().unwrap();
+
+        // When the current loop iteration finishes, immediately begin a new
+        // iteration regardless of whether or not new events are available to
+        // process. Preferred for applications that want to render as fast as
+        // possible, like games.
+        event_loop.set_control_flow(ControlFlow::Poll);
+
+        let app_data = Rc::new(RefCell::new(app_data));
 
         let mut app = App {
             active_app_gui: AppGui::new(app_data.clone()),
@@ -9,14 +9,32 @@ use crate::{
             ClientSessionData, 
             ClientSessionId
         }, 
+        game_mode::RoundScore, 
+        leaderboard::{
+            LeaderboardSort, 
+            PlayerStats
+        }, 
+        match_history::{
+            MatchId, 
+            MatchRecord, 
+            MatchSummary
+        }, 
         GameplayResult, 
         GameplayState
     }, 
     game::{
-        math::Vector2F, 
+        math::{
+            Vector2F, 
+            Vector2I
+        }, 
+        tile_layer::{
+            Obstacle, 
+            TileBounds
+        }, 
         world::{
             Entity, 
             EntityId, 
+            MovementMode, 
             PlayerRole
         }
     }
@@ -28,9 +46,29 @@ pub enum MoveDirection {
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl MoveDirection {
+    /// One tile step, diagonal steps are one tile on both axes
+    pub fn offset(&self) -> Vector2F {
+        match self {
+            MoveDirection::Up => Vector2F::new(0.0, 1.0),
+            MoveDirection::Down => Vector2F::new(0.0, -1.0),
+            MoveDirection::Left => Vector2F::new(-1.0, 0.0),
+            MoveDirection::Right => Vector2F::new(1.0, 0.0),
+            MoveDirection::UpLeft => Vector2F::new(-1.0, 1.0),
+            MoveDirection::UpRight => Vector2F::new(1.0, 1.0),
+            MoveDirection::DownLeft => Vector2F::new(-1.0, -1.0),
+            MoveDirection::DownRight => Vector2F::new(1.0, -1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GameplayStateBrief {
     Lobby {
         counting_to_start: Option<u32>,
@@ -59,7 +97,7 @@ impl From<&GameplayState> for GameplayStateBrief {
                 counting_to_start: *counting,
                 last_result: *res
             },
-            GameplayState::GameRunning { world: _ } => GameplayStateBrief::GameRunning,
+            GameplayState::GameRunning { world: _, game_mode: _, match_recorder: _, replay: _ } => GameplayStateBrief::GameRunning,
             GameplayState::Ending { countdown: counting , result: res} => GameplayStateBrief::Ending {
                 countdown: *counting, 
                 result: *res
@@ -86,24 +124,79 @@ pub enum ClientRequest {
     SetName {
         new_name: Option<String>,
     },
+    Register {
+        name: String,
+        password: String,
+    },
+    Login {
+        name: String,
+        password: String,
+    },
+    JoinLobby,
     SetReady {
         ready: bool
     },
     GetEntityId,
     WorldCheck,
+    /// Obstacles and bounds of running round, fetched again when `WorldCheck` reports other map id
+    GetMap,
     ServerCheck,
     CheckGameplayState,
     Move {
         dir: MoveDirection
     },
+    /// Walk to tile containing `target` along path planned by server
+    MoveTo {
+        target: Vector2F
+    },
+    /// Keep walking in `direction` in free movement mode, zero stops
+    SetVelocity {
+        direction: Vector2F
+    },
     GetRole,
     GetStartCountdownTime,
     TryUncover {
         id: EntityId
-    }
+    },
+    GetServerTick,
+    GetRoundScore,
+    GetLeaderboard {
+        top_n: usize,
+        sort_by: LeaderboardSort,
+    },
+    GetPlayerStats {
+        name: String,
+    },
+    /// Newest matches first
+    GetMatchHistory {
+        limit: usize,
+    },
+    GetMatch {
+        id: MatchId,
+    },
+    /// Allowed only for accounts listed in `admin.accounts` config
+    Admin {
+        command: AdminCommand,
+    },
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AdminCommand {
+    /// Seed used by next round only, overrides config
+    SetNextRoundSeed {
+        seed: u64,
+    },
+    /// Dump world of running round to `storage.saved_worlds_dir`
+    SaveWorld {
+        name: String,
+    },
+    /// Replace current state with saved round, players are attached to entities with their names
+    RestoreWorld {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EntityType {
     Npc,
     Hider {
@@ -112,7 +205,7 @@ pub enum EntityType {
     Seeker
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityCheckData {
     pub position: Vector2F,
     pub size: Vector2F,
@@ -133,6 +226,49 @@ pub enum SetNameError {
     
     #[error("NameGenerateExhausted")]
     NameGenerateExhausted,
+
+    #[error("GuestModeDisabled")]
+    GuestModeDisabled,
+}
+
+#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
+pub enum AdminError {
+    #[error("NotAdmin")]
+    NotAdmin,
+
+    #[error("SavedWorldsDisabled")]
+    SavedWorldsDisabled,
+
+    #[error("BadState")]
+    BadState,
+
+    #[error("Failed, reason='{reason}'")]
+    Failed {
+        reason: String
+    },
+}
+
+#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
+pub enum AuthError {
+    #[error("NameEmpty")]
+    NameEmpty,
+
+    #[error("NameAlreadyUsed")]
+    NameAlreadyUsed,
+
+    #[error("PasswordTooShort, min_length={min_length}")]
+    PasswordTooShort {
+        min_length: usize
+    },
+
+    #[error("InvalidCredentials")]
+    InvalidCredentials,
+
+    #[error("AlreadyLoggedIn")]
+    AlreadyLoggedIn,
+
+    #[error("StorageFailed")]
+    StorageFailed,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -159,6 +295,15 @@ pub enum ClientResponse {
     SetName {
         result: Result<(), SetNameError>
     },
+    Register {
+        result: Result<(), AuthError>
+    },
+    Login {
+        result: Result<(), AuthError>
+    },
+    JoinLobby {
+        joined: bool
+    },
     SetReady {
         was_set: bool
     },
@@ -166,7 +311,18 @@ pub enum ClientResponse {
         id: Option<EntityId>
     },
     WorldCheck {
-        entities: Vec<EntityCheckData>
+        entities: Vec<EntityCheckData>,
+        /// Id of map sent by `GetMap`, changes only when obstacles or bounds do
+        map_id: u64,
+        /// Tiles in sight of seeker, `None` when whole world is seen
+        visible_tiles: Option<Vec<Vector2I>>,
+        movement_mode: MovementMode
+    },
+    GetMap {
+        map_id: u64,
+        obstacles: Vec<Obstacle>,
+        /// Play area, `None` for unbounded world
+        bounds: Option<TileBounds>
     },
     ServerCheck {
         msg: String,
@@ -191,6 +347,17 @@ pub enum ClientResponse {
     Move {
         started: bool
     },
+    /// Number of steps of planned path, `None` when target can not be reached
+    MoveTo {
+        steps: Option<usize>
+    },
+    /// Move would leave world bounds
+    MoveOutOfBounds {
+        destination: Vector2F
+    },
+    SetVelocity {
+        was_set: bool
+    },
     GetRole {
         role: PlayerRole
     },
@@ -199,7 +366,29 @@ pub enum ClientResponse {
     },
     TryUncover {
         uncover_result: UncoverResult
-    }
+    },
+    GetServerTick {
+        tick: u64,
+        ticks_per_second: u32,
+    },
+    GetRoundScore {
+        score: Option<RoundScore>
+    },
+    GetLeaderboard {
+        entries: Vec<PlayerStats>
+    },
+    GetPlayerStats {
+        stats: Option<PlayerStats>
+    },
+    GetMatchHistory {
+        matches: Vec<MatchSummary>
+    },
+    GetMatch {
+        record: Option<MatchRecord>
+    },
+    Admin {
+        result: Result<(), AdminError>
+    },
 }
 
 impl EntityCheckData {
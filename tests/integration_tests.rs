@@ -9,9 +9,21 @@ use std::{
 use rust_multiplayer::{
     app::{
         client::{MultiplayerClient, MultiplayerClientHandle}, 
-        server::{client_session::ClientSessionState, GameplayState, MultiplayerServer}
-    }, game::world::PlayerRole, requests::{
-        ClientRequest, ClientResponse, GameplayStateBrief, MoveDirection
+        server::{
+            auth, 
+            client_session::ClientSessionState, 
+            config::ServerConfig, 
+            events::GameEvent, 
+            game_mode::GameModeKind, 
+            leaderboard::LeaderboardSort, 
+            match_history::{HiderFound, InMemoryMatchHistory, MatchHistoryStore, MatchRecord}, 
+            profiles::{InMemoryProfileStore, PlayerProfile, ProfileStore}, 
+            saved_world::SavedWorld, 
+            GameplayResult, 
+            MultiplayerServer
+        }
+    }, game::{math::Vector2F, world::{EntityId, PlayerRole}}, requests::{
+        AdminCommand, AdminError, AuthError, ClientRequest, ClientResponse, GameplayStateBrief, MoveDirection, SetNameError
     }
 };
 
@@ -20,6 +32,13 @@ where
     F: FnOnce(MultiplayerClientHandle) + Send + 'static,
 {
     let server = MultiplayerServer::bind_any_local().await.unwrap();
+    run_single_client_test_on_server(server, test_fn).await
+}
+
+async fn run_single_client_test_on_server<F>(server: MultiplayerServer, test_fn: F) 
+where
+    F: FnOnce(MultiplayerClientHandle) + Send + 'static,
+{
     let server_address = server.get_local_address().unwrap();
     let server_handler = server.run().await.unwrap();
     assert_eq!(server_handler.connections_count(), 0);
@@ -44,6 +63,67 @@ where
     server_handler.shutdown().await.unwrap();
 }
 
+/// Server with admin account "Root" and saved worlds dir holding copy of fixture world
+async fn fixture_server(fixture_name: &str) -> (MultiplayerServer, tempfile::TempDir) {
+    let saved_worlds_dir = tempfile::tempdir().unwrap();
+    let file_name = format!("{fixture_name}.json");
+    std::fs::copy(std::path::Path::new("tests/fixtures/worlds").join(&file_name), saved_worlds_dir.path().join(&file_name)).unwrap();
+
+    let mut config = ServerConfig::default();
+    config.admin.accounts = vec!["Root".to_string()];
+    config.storage.saved_worlds_dir = Some(saved_worlds_dir.path().to_path_buf());
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_config(config);
+    (server, saved_worlds_dir)
+}
+
+/// Register as admin "Root" and join lobby
+fn join_lobby_as_admin(client_handler: &MultiplayerClientHandle) {
+    let response = client_handler.make_request_with_timeout(ClientRequest::Register { name: "Root".to_string(), password: "password".to_string() }, None).unwrap();
+    assert!(matches!(response, ClientResponse::Register { result: Ok(()) }), "Bad response={response:?}");
+    let response = client_handler.make_request_with_timeout(ClientRequest::JoinLobby, None).unwrap();
+    assert!(matches!(response, ClientResponse::JoinLobby { joined: true }), "Bad response={response:?}");
+}
+
+fn restore_fixture(client_handler: &MultiplayerClientHandle, fixture_name: &str) {
+    let response = client_handler.make_request_with_timeout(ClientRequest::Admin { command: AdminCommand::RestoreWorld { name: fixture_name.to_string() } }, None).unwrap();
+    assert!(matches!(response, ClientResponse::Admin { result: Ok(()) }), "Bad response={response:?}");
+}
+
+/// Runs test once fixture world is restored, client controls entity named "Root".
+/// Returns saved worlds dir so test can inspect it afterwards.
+async fn run_test_on_restored_fixture<F>(fixture_name: &'static str, test_fn: F) -> tempfile::TempDir
+where
+    F: FnOnce(MultiplayerClientHandle) + Send + 'static,
+{
+    let (server, saved_worlds_dir) = fixture_server(fixture_name).await;
+    run_single_client_test_on_server(server, move |client_handler| {
+        join_lobby_as_admin(&client_handler);
+        restore_fixture(&client_handler, fixture_name);
+        test_fn(client_handler);
+    }).await;
+    saved_worlds_dir
+}
+
+/// Polls world until entity stands at position, panics once deadline passes
+fn wait_for_entity_at(client_handler: &MultiplayerClientHandle, id: EntityId, position: Vector2F, deadline: Duration) {
+    let started = std::time::Instant::now();
+    loop {
+        let response = client_handler.make_request_with_timeout(ClientRequest::WorldCheck, None).unwrap();
+        let current = match response {
+            ClientResponse::WorldCheck { entities, map_id: _, visible_tiles: _, movement_mode: _ } => {
+                entities.iter().find(|entity| entity.id == id).unwrap().position
+            },
+            _ => panic!("Bad response={response:?}"),
+        };
+        if current == position {
+            return;
+        }
+        assert!(started.elapsed() < deadline, "Entity {id} stuck at {current:?}, expected {position:?}");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
 struct MultipleClientsTestCfg {
     clients_count: usize,
     start_delay: core::ops::Range<Duration>,
@@ -276,6 +356,27 @@ async fn test_client_ping_server() {
     }).await;
 }
 
+#[tokio::test]
+async fn test_server_tick_is_monotonic() {
+    run_single_client_test(|client_handler| {
+        let read_tick = || {
+            let response = client_handler.make_request_with_timeout(ClientRequest::GetServerTick, None).unwrap();
+            match response {
+                ClientResponse::GetServerTick { tick, ticks_per_second } => {
+                    assert!(ticks_per_second > 0);
+                    tick
+                },
+                _ => panic!("Bad response={response:?}"),
+            }
+        };
+
+        let first_tick = read_tick();
+        std::thread::sleep(Duration::from_millis(300));
+        let second_tick = read_tick();
+        assert!(second_tick > first_tick, "first={first_tick}, second={second_tick}");
+    }).await;
+}
+
 #[tokio::test]
 async fn test_new_client_has_no_points() {
     run_single_client_test(|client_handler| {
@@ -286,6 +387,340 @@ async fn test_new_client_has_no_points() {
             },
             _ => panic!("Bad response={response:?}"),
         }
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetRoundScore, None).unwrap();
+        match response {
+            ClientResponse::GetRoundScore { score } => {
+                assert!(score.is_none());
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+    }).await;
+}
+
+#[tokio::test]
+async fn test_returning_player_gets_stored_points() {
+    let profiles = Arc::new(InMemoryProfileStore::default());
+    profiles.save(&PlayerProfile { total_points: 17, ..PlayerProfile::new("Beaver_1") }).unwrap();
+
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_profile_store(profiles);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::SetName { new_name: Some("Beaver_1".to_string()) }, None).unwrap();
+        assert!(matches!(response, ClientResponse::SetName { result: Ok(()) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetPointsCount, None).unwrap();
+        match response {
+            ClientResponse::GetPointsCount { points_count } => {
+                assert_eq!(points_count, 17);
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+    }).await;
+}
+
+#[tokio::test]
+async fn test_register_and_join_lobby_with_auth_enabled() {
+    let mut config = ServerConfig::default();
+    config.auth.enabled = true;
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_config(config);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::SetName { new_name: Some("Guest".to_string()) }, None).unwrap();
+        assert!(matches!(response, ClientResponse::SetName { result: Err(SetNameError::GuestModeDisabled) }), "Bad response={response:?}");
+
+        let register_request = |password: &str| ClientRequest::Register { name: "Horse_9".to_string(), password: password.to_string() };
+
+        let response = client_handler.make_request_with_timeout(register_request("short"), None).unwrap();
+        assert!(matches!(response, ClientResponse::Register { result: Err(AuthError::PasswordTooShort { min_length: 8 }) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(register_request("long enough"), None).unwrap();
+        assert!(matches!(response, ClientResponse::Register { result: Ok(()) }), "Bad response={response:?}");
+
+        // Authenticated players are not in lobby yet
+        let response = client_handler.make_request_with_timeout(ClientRequest::SetReady { ready: true }, None).unwrap();
+        assert!(matches!(response, ClientResponse::BadState), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::JoinLobby, None).unwrap();
+        assert!(matches!(response, ClientResponse::JoinLobby { joined: true }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetClientSessionData, None).unwrap();
+        match response {
+            ClientResponse::GetClientSessionData { data } => {
+                assert!(matches!(data.state, ClientSessionState::NameWasSet { name, ready_to_start: false, entity_player_id: None } if name == "Horse_9"));
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+    }).await;
+}
+
+#[tokio::test]
+async fn test_concurrent_registrations_of_same_name() {
+    let registered_count = Arc::new(Mutex::new(0));
+    let registered_count_shared = registered_count.clone();
+    let cfg = MultipleClientsTestCfg {
+        clients_count: 4,
+        start_delay: Duration::from_millis(0)..Duration::from_millis(2),
+        end_delay: Duration::from_millis(0)..Duration::from_millis(1),
+    };
+
+    run_multiple_client_test(cfg, move |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::Register { name: "Horse_9".to_string(), password: "password".to_string() }, None).unwrap();
+        match response {
+            ClientResponse::Register { result: Ok(()) } => *registered_count_shared.lock().unwrap() += 1,
+            ClientResponse::Register { result: Err(AuthError::NameAlreadyUsed) } => {},
+            _ => panic!("Bad response={response:?}"),
+        }
+    }).await;
+
+    assert_eq!(*registered_count.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_login_to_registered_account() {
+    let profiles = Arc::new(InMemoryProfileStore::default());
+    profiles.save(&PlayerProfile { 
+        total_points: 4, 
+        password_hash: Some(auth::hash_password("correct horse").unwrap()), 
+        ..PlayerProfile::new("Cat_1") 
+    }).unwrap();
+
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_profile_store(profiles);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        // Guests cannot take name of account
+        let response = client_handler.make_request_with_timeout(ClientRequest::SetName { new_name: Some("Cat_1".to_string()) }, None).unwrap();
+        assert!(matches!(response, ClientResponse::SetName { result: Err(SetNameError::NameAlreadyUsed) }), "Bad response={response:?}");
+
+        let login_request = |password: &str| ClientRequest::Login { name: "Cat_1".to_string(), password: password.to_string() };
+
+        let response = client_handler.make_request_with_timeout(login_request("wrong horse"), None).unwrap();
+        assert!(matches!(response, ClientResponse::Login { result: Err(AuthError::InvalidCredentials) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(login_request("correct horse"), None).unwrap();
+        assert!(matches!(response, ClientResponse::Login { result: Ok(()) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetPointsCount, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetPointsCount { points_count: 4 }), "Bad response={response:?}");
+
+        // Already authenticated
+        let response = client_handler.make_request_with_timeout(login_request("correct horse"), None).unwrap();
+        assert!(matches!(response, ClientResponse::BadState), "Bad response={response:?}");
+    }).await;
+}
+
+#[tokio::test]
+async fn test_leaderboard_and_player_stats() {
+    let profiles = Arc::new(InMemoryProfileStore::default());
+    profiles.save(&PlayerProfile { total_points: 3, games_played: 2, games_as_hider: 2, wins_as_hider: 1, ..PlayerProfile::new("Pig_1") }).unwrap();
+    profiles.save(&PlayerProfile { total_points: 9, games_played: 1, ..PlayerProfile::new("Pig_2") }).unwrap();
+    profiles.save(&PlayerProfile { total_points: 6, games_played: 5, ..PlayerProfile::new("Pig_3") }).unwrap();
+
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_profile_store(profiles);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetLeaderboard { top_n: 2, sort_by: LeaderboardSort::TotalPoints }, None).unwrap();
+        match response {
+            ClientResponse::GetLeaderboard { entries } => {
+                let names: Vec<_> = entries.iter().map(|stats| stats.name.as_str()).collect();
+                assert_eq!(names, vec!["Pig_2", "Pig_3"]);
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetLeaderboard { top_n: 1, sort_by: LeaderboardSort::GamesPlayed }, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetLeaderboard { entries } if entries[0].name == "Pig_3"));
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetPlayerStats { name: "Pig_1".to_string() }, None).unwrap();
+        match response {
+            ClientResponse::GetPlayerStats { stats: Some(stats) } => {
+                assert_eq!(stats.hider_win_rate, Some(0.5));
+                assert_eq!(stats.seeker_win_rate, None);
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetPlayerStats { name: "Nobody".to_string() }, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetPlayerStats { stats: None }), "Bad response={response:?}");
+    }).await;
+}
+
+#[tokio::test]
+async fn test_match_history() {
+    let match_history = Arc::new(InMemoryMatchHistory::default());
+    for id in 0..3 {
+        match_history.add(MatchRecord {
+            id,
+            game_mode: GameModeKind::HideAndSeek,
+            seed: 0,
+            started_at: std::time::SystemTime::UNIX_EPOCH,
+            ended_at: std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+            players: vec![],
+            uncover_attempts: vec![],
+            hiders_found: vec![HiderFound { name: "Pig_1".to_string(), at_secs: 12.5 }],
+            result: GameplayResult::SeekerWin { reward: 5 },
+        }).unwrap();
+    }
+
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_match_history(match_history);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetMatchHistory { limit: 2 }, None).unwrap();
+        match response {
+            ClientResponse::GetMatchHistory { matches } => {
+                let ids: Vec<_> = matches.iter().map(|summary| summary.id).collect();
+                assert_eq!(ids, vec![2, 1]);
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetMatch { id: 1 }, None).unwrap();
+        match response {
+            ClientResponse::GetMatch { record: Some(record) } => {
+                assert_eq!(record.id, 1);
+                assert_eq!(record.hiders_found[0].name, "Pig_1");
+            },
+            _ => panic!("Bad response={response:?}"),
+        }
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetMatch { id: 7 }, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetMatch { record: None }), "Bad response={response:?}");
+    }).await;
+}
+
+#[tokio::test]
+async fn test_game_events_of_lobby() {
+    let server = MultiplayerServer::bind_any_local().await.unwrap();
+    let server_address = server.get_local_address().unwrap();
+    let server_handler = server.run().await.unwrap();
+    let mut events = server_handler.server_context.events.subscribe();
+
+    let client_offloaded_task = tokio::task::spawn_blocking(move || {
+        let client_handler = MultiplayerClient::connect(server_address).unwrap().run().unwrap();
+        client_handler.make_request_with_timeout(ClientRequest::SetName { new_name: Some("Pig_1".to_string()) }, None).unwrap();
+        client_handler.make_request_with_timeout(ClientRequest::SetReady { ready: true }, None).unwrap();
+        client_handler.make_request_with_timeout(ClientRequest::SetReady { ready: true }, None).unwrap();
+        client_handler.make_request_with_timeout(ClientRequest::SetReady { ready: false }, None).unwrap();
+    });
+
+    server_handler.await_any_connection().await;
+    client_offloaded_task.await.unwrap();
+    server_handler.await_all_disconnect().await;
+
+    let mut received_events = vec![];
+    while let Ok(entry) = events.try_recv() {
+        assert_eq!(entry.match_id, None);
+        received_events.push(entry.event.clone());
+    }
+    assert_eq!(received_events, vec![
+        GameEvent::PlayerJoined { client_id: 0, name: "Pig_1".to_string() },
+        GameEvent::ReadyToggled { client_id: 0, ready: true },
+        GameEvent::ReadyToggled { client_id: 0, ready: false },
+        GameEvent::PlayerLeft { client_id: 0, name: Some("Pig_1".to_string()) },
+    ]);
+
+    server_handler.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_admin_commands_require_listed_account() {
+    let mut config = ServerConfig::default();
+    config.admin.accounts = vec!["Root".to_string()];
+    let server = MultiplayerServer::bind_any_local().await.unwrap()
+        .with_config(config);
+
+    run_single_client_test_on_server(server, |client_handler| {
+        let admin_request = || ClientRequest::Admin { command: AdminCommand::SetNextRoundSeed { seed: 7 } };
+
+        let response = client_handler.make_request_with_timeout(admin_request(), None).unwrap();
+        assert!(matches!(response, ClientResponse::Admin { result: Err(AdminError::NotAdmin) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::Register { name: "Root".to_string(), password: "password".to_string() }, None).unwrap();
+        assert!(matches!(response, ClientResponse::Register { result: Ok(()) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(admin_request(), None).unwrap();
+        assert!(matches!(response, ClientResponse::Admin { result: Ok(()) }), "Bad response={response:?}");
+    }).await;
+}
+
+#[tokio::test]
+async fn test_admin_restores_saved_world_fixture() {
+    let (server, saved_worlds_dir) = fixture_server("hider_and_seeker").await;
+
+    let saved_world_path = saved_worlds_dir.path().join("after_restore.json");
+    run_single_client_test_on_server(server, move |client_handler| {
+        let response = client_handler.make_request_with_timeout(ClientRequest::MoveTo { target: Vector2F::new(30.0, -10.0) }, None).unwrap();
+        assert!(matches!(response, ClientResponse::BadState), "Bad response={response:?}");
+
+        join_lobby_as_admin(&client_handler);
+
+        // Nothing to save in lobby
+        let admin_request = |command| ClientRequest::Admin { command };
+        let response = client_handler.make_request_with_timeout(admin_request(AdminCommand::SaveWorld { name: "after_restore".to_string() }), None).unwrap();
+        assert!(matches!(response, ClientResponse::Admin { result: Err(AdminError::BadState) }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(admin_request(AdminCommand::RestoreWorld { name: "../hider_and_seeker".to_string() }), None).unwrap();
+        assert!(matches!(response, ClientResponse::Admin { result: Err(AdminError::Failed { reason: _ }) }), "Bad response={response:?}");
+
+        restore_fixture(&client_handler, "hider_and_seeker");
+
+        let response = client_handler.make_request_with_timeout(ClientRequest::CheckGameplayState, None).unwrap();
+        assert!(matches!(response, ClientResponse::CheckGameplayState { state: GameplayStateBrief::GameRunning }), "Bad response={response:?}");
+
+        // Fixture has player entity named after client
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetEntityId, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetEntityId { id: Some(0) }), "Bad response={response:?}");
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetRole, None).unwrap();
+        assert!(matches!(response, ClientResponse::GetRole { role: PlayerRole::Hider { stats: _ } }), "Bad response={response:?}");
+
+        let response = client_handler.make_request_with_timeout(admin_request(AdminCommand::SaveWorld { name: "after_restore".to_string() }), None).unwrap();
+        assert!(matches!(response, ClientResponse::Admin { result: Ok(()) }), "Bad response={response:?}");
+    }).await;
+
+    let saved_world = SavedWorld::load(&saved_world_path).unwrap();
+    assert_eq!(saved_world.game_mode, GameModeKind::HideAndSeek);
+    assert_eq!(saved_world.world.iter_entities().count(), 5);
+    assert!(saved_world.world.ticks() >= 20);
+}
+
+#[tokio::test]
+async fn test_player_walks_to_target_along_planned_path() {
+    run_test_on_restored_fixture("hider_and_seeker", |client_handler| {
+        // Root stands at tile (2, 0), NPCs roam far from the target
+        let response = client_handler.make_request_with_timeout(ClientRequest::MoveTo { target: Vector2F::new(31.0, -9.0) }, None).unwrap();
+        assert!(matches!(response, ClientResponse::MoveTo { steps: Some(4) }), "Bad response={response:?}");
+
+        wait_for_entity_at(&client_handler, 0, Vector2F::new(30.0, -10.0), Duration::from_secs(10));
+    }).await;
+}
+
+#[tokio::test]
+async fn test_hider_uncover_is_rejected() {
+    run_test_on_restored_fixture("hider_and_seeker", |client_handler| {
+        let read_tick = || match client_handler.make_request_with_timeout(ClientRequest::GetServerTick, None).unwrap() {
+            ClientResponse::GetServerTick { tick, ticks_per_second: _ } => tick,
+            response => panic!("Bad response={response:?}"),
+        };
+        let first_tick = read_tick();
+
+        // Root is hider, seeker is entity 1, rest are NPCs
+        for id in 1..5 {
+            let response = client_handler.make_request_with_timeout(ClientRequest::TryUncover { id }, None).unwrap();
+            assert!(matches!(response, ClientResponse::OtherError { err: _ }), "Bad response={response:?}");
+        }
+
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(read_tick() > first_tick);
+        let response = client_handler.make_request_with_timeout(ClientRequest::WorldCheck, None).unwrap();
+        match response {
+            ClientResponse::WorldCheck { entities, map_id: _, visible_tiles: _, movement_mode: _ } => assert_eq!(entities.len(), 5),
+            _ => panic!("Bad response={response:?}"),
+        }
     }).await;
 }
 
@@ -492,11 +927,12 @@ async fn test_multiple_clients_getting_ready_trigger_countdown_await_expiration(
 
         // Players should be ingame now, check what role they have
         let response = client_handler.make_request_with_timeout(ClientRequest::GetRole, None).unwrap();
-        match response {
+        let is_seeker = match response {
             ClientResponse::GetRole { role } => {
                 println!("{:?}", role);
                 let mut players_roles_guard = players_roles_clonned.lock().unwrap();
                 players_roles_guard.push(role);
+                matches!(role, PlayerRole::Seeker { stats: _ })
             },
             _ => panic!("Bad response={response:?}"),
         };
@@ -510,12 +946,30 @@ async fn test_multiple_clients_getting_ready_trigger_countdown_await_expiration(
             _ => panic!("Bad response={response:?}"),
         };
 
-        // Each playermust see at least 'clients_count' entities
+        // Each hider must see at least 'clients_count' entities, seeker sees only ones around itself
         let response = client_handler.make_request_with_timeout(ClientRequest::WorldCheck, None).unwrap();
-        match response {
-            ClientResponse::WorldCheck { entities } => {
-                assert!(entities.len() >= clients_count);
+        let checked_map_id = match response {
+            ClientResponse::WorldCheck { entities, map_id, visible_tiles, movement_mode: _ } => {
+                if is_seeker {
+                    assert!(!entities.is_empty());
+                    assert!(visible_tiles.is_some());
+                } else {
+                    assert!(entities.len() >= clients_count);
+                    assert!(visible_tiles.is_none());
+                }
                 println!("entities.len={}", entities.len());
+                map_id
+            },
+            _ => panic!("Bad response={response:?}"),
+        };
+
+        // Obstacles come only with map, seekers get whole map too
+        let response = client_handler.make_request_with_timeout(ClientRequest::GetMap, None).unwrap();
+        match response {
+            ClientResponse::GetMap { map_id, obstacles, bounds } => {
+                assert_eq!(map_id, checked_map_id);
+                assert!(!obstacles.is_empty());
+                assert!(bounds.is_some());
             },
             _ => panic!("Bad response={response:?}"),
         };
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use proptest::prelude::*;
+use rust_multiplayer::game::{
+    math::Vector2F,
+    npc_behavior::NpcPersonality,
+    world::{
+        get_tiled_vec,
+        EntityId,
+        MovementMode,
+        World,
+        WorldSettings,
+        ENTITY_SIZE,
+        TILE_SIZE
+    }
+};
+
+const AREA_SIDE: i32 = 6;
+const DIRECTIONS: [Vector2F; 8] = [
+    Vector2F { x: 1.0, y: 0.0 },
+    Vector2F { x: -1.0, y: 0.0 },
+    Vector2F { x: 0.0, y: 1.0 },
+    Vector2F { x: 0.0, y: -1.0 },
+    Vector2F { x: 1.0, y: 1.0 },
+    Vector2F { x: -1.0, y: 1.0 },
+    Vector2F { x: 1.0, y: -1.0 },
+    Vector2F { x: -1.0, y: -1.0 },
+];
+
+/// Player tries to move in direction or walk to target tile, then world runs for some ticks
+#[derive(Debug, Clone)]
+struct Step {
+    player: usize,
+    direction: usize,
+    path_target: Option<(i32, i32)>,
+    ticks: usize,
+}
+
+fn step_strategy() -> impl Strategy<Value = Step> {
+    (0..4usize, 0..DIRECTIONS.len(), prop::option::of((-2..AREA_SIDE + 2, -2..AREA_SIDE + 2)), 0..4usize)
+        .prop_map(|(player, direction, path_target, ticks)| Step { player, direction, path_target, ticks })
+}
+
+/// Player changes velocity in free movement mode, then world runs for some ticks
+#[derive(Debug, Clone)]
+struct FreeStep {
+    player: usize,
+    velocity: Vector2F,
+    ticks: usize,
+}
+
+fn free_step_strategy() -> impl Strategy<Value = FreeStep> {
+    (0..4usize, -1.5..1.5f32, -1.5..1.5f32, 0..6usize)
+        .prop_map(|(player, x, y, ticks)| FreeStep { player, velocity: Vector2F::new(x, y), ticks })
+}
+
+const PERSONALITIES: [fn() -> NpcPersonality; 5] = [
+    NpcPersonality::default,
+    NpcPersonality::wanderer,
+    NpcPersonality::follower,
+    NpcPersonality::idler,
+    NpcPersonality::path_walker,
+];
+
+/// Entities packed into small square, so they keep running into each other
+fn crowded_world(settings: WorldSettings, seed: u64, players_count: usize, npcs_count: usize) -> (World, Vec<EntityId>) {
+    let mut world = World::with_seed(settings, seed);
+    let mut tiles = (0..AREA_SIDE * AREA_SIDE).map(|i| get_tiled_vec(i % AREA_SIDE, i / AREA_SIDE));
+
+    let players = (0..players_count)
+        .map(|i| world.create_entity_player(format!("Player{i}"), tiles.next().unwrap(), ENTITY_SIZE))
+        .collect();
+    for i in 0..npcs_count {
+        let personality = PERSONALITIES[i % PERSONALITIES.len()]();
+        world.create_entity_npc_with_personality(format!("Npc{i}"), tiles.next().unwrap(), ENTITY_SIZE, personality);
+    }
+    (world, players)
+}
+
+fn assert_no_overlap(world: &World) -> Result<(), TestCaseError> {
+    let entities: Vec<_> = world.iter_entities().collect();
+    for (i, a) in entities.iter().enumerate() {
+        for b in entities.iter().skip(i + 1) {
+            let distance = a.position - b.position;
+            let overlapping = distance.x.abs() < ENTITY_SIZE.x && distance.y.abs() < ENTITY_SIZE.y;
+            prop_assert!(!overlapping, "{} at {} overlaps {} at {}", a.name, a.position, b.name, b.position);
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn entities_never_overlap(
+        seed in any::<u64>(),
+        players_count in 1..4usize,
+        npcs_count in 0..24usize,
+        steps in prop::collection::vec(step_strategy(), 1..80)
+    ) {
+        let (mut world, players) = crowded_world(WorldSettings::default(), seed, players_count, npcs_count);
+
+        for step in steps {
+            let player = players[step.player % players.len()];
+            match step.path_target {
+                Some((x, y)) => {
+                    let _ = world.set_entity_path_to(player, get_tiled_vec(x, y));
+                },
+                None => {
+                    let position = world.get_entity_by_id(player).unwrap().position;
+                    let _ = world.try_start_move_entity_to(player, position + DIRECTIONS[step.direction] * TILE_SIZE);
+                },
+            }
+            assert_no_overlap(&world)?;
+
+            for _ in 0..step.ticks {
+                world.tick(Duration::from_millis(50));
+                assert_no_overlap(&world)?;
+            }
+        }
+    }
+
+    #[test]
+    fn free_moving_entities_never_overlap(
+        seed in any::<u64>(),
+        players_count in 1..4usize,
+        npcs_count in 0..24usize,
+        steps in prop::collection::vec(free_step_strategy(), 1..80)
+    ) {
+        let settings = WorldSettings { movement_mode: MovementMode::Free, ..Default::default() };
+        let (mut world, players) = crowded_world(settings, seed, players_count, npcs_count);
+
+        for step in steps {
+            let player = players[step.player % players.len()];
+            prop_assert!(world.set_entity_velocity(player, step.velocity).is_ok());
+
+            for _ in 0..step.ticks {
+                world.tick(Duration::from_millis(50));
+                assert_no_overlap(&world)?;
+            }
+        }
+    }
+
+    #[test]
+    fn npcs_finish_their_steps_among_free_moving_players(
+        seed in any::<u64>(),
+        players_count in 1..4usize,
+        npcs_count in 0..24usize,
+        steps in prop::collection::vec(free_step_strategy(), 1..80)
+    ) {
+        let settings = WorldSettings { movement_mode: MovementMode::Free, ..Default::default() };
+        let (mut world, players) = crowded_world(settings, seed, players_count, npcs_count);
+
+        for step in steps {
+            let player = players[step.player % players.len()];
+            prop_assert!(world.set_entity_velocity(player, step.velocity).is_ok());
+
+            for _ in 0..step.ticks {
+                let stepping: Vec<EntityId> = world.iter_entities()
+                    .filter(|e| !e.is_player() && e.is_moving())
+                    .map(|e| e.id)
+                    .collect();
+                let movements = world.tick(Duration::from_millis(50));
+
+                // Step ends only by arriving, never stopped halfway by someone walking in
+                for id in stepping {
+                    let npc = world.get_entity_by_id(id).unwrap();
+                    if !npc.is_moving() {
+                        let arrived = movements.iter().any(|movement| movement.entity_id == id && movement.to == npc.position);
+                        prop_assert!(arrived, "{} stopped at {} before finishing step", npc.name, npc.position);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_one_entity_gets_contested_tile(seed in any::<u64>(), first_left in any::<bool>()) {
+        let mut world = World::with_seed(WorldSettings::default(), seed);
+        let left = world.create_entity_player("Left", get_tiled_vec(0, 0), ENTITY_SIZE);
+        let right = world.create_entity_player("Right", get_tiled_vec(2, 0), ENTITY_SIZE);
+        let (first, second) = if first_left { (left, right) } else { (right, left) };
+
+        prop_assert!(world.try_start_move_entity_to(first, get_tiled_vec(1, 0)).is_ok());
+        prop_assert!(world.try_start_move_entity_to(second, get_tiled_vec(1, 0)).is_err());
+
+        for _ in 0..20 {
+            world.tick(Duration::from_millis(50));
+        }
+        prop_assert_eq!(world.get_entity_by_id(first).unwrap().position, get_tiled_vec(1, 0));
+        prop_assert!(world.try_start_move_entity_to(second, get_tiled_vec(1, 0)).is_err());
+    }
+}